@@ -0,0 +1,301 @@
+use std::str::FromStr;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::diagnostics::SOURCE;
+
+/// severity of a diagnostic, from the most to the least severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl Severity {
+    /// a diagnostic without severity is an error
+    pub fn of(diagnostic: &Diagnostic) -> Self {
+        match diagnostic.severity {
+            Some(DiagnosticSeverity::WARNING) => Self::Warning,
+            Some(DiagnosticSeverity::INFORMATION) => Self::Information,
+            Some(DiagnosticSeverity::HINT) => Self::Hint,
+            _ => Self::Error,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Information => "information",
+            Self::Hint => "hint",
+        }
+    }
+
+    // https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html#_Toc34317648
+    fn sarif_level(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Information | Self::Hint => "note",
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "warning" => Ok(Self::Warning),
+            "information" => Ok(Self::Information),
+            "hint" => Ok(Self::Hint),
+            _ => Err(format!(
+                "unknown severity `{s}`, expected `error`, `warning`, `information` or `hint`"
+            )),
+        }
+    }
+}
+
+/// diagnostics of a checked file, `path` is shown as given on the command line
+#[derive(Debug)]
+pub struct FileReport {
+    pub path: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// one diagnostic of the json output, lines and columns start at 1
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    path: &'a str,
+    line: u32,
+    column: u32,
+    end_line: u32,
+    end_column: u32,
+    severity: &'static str,
+    message: &'a str,
+}
+
+/// diagnostics of the files of a `ccls check` run
+#[derive(Debug)]
+pub struct CheckReport {
+    pub files: Vec<FileReport>,
+    /// the outputs only show the diagnostics at least as severe as this one
+    pub severity: Severity,
+}
+
+impl CheckReport {
+    pub fn new(files: Vec<FileReport>, severity: Severity) -> Self {
+        Self { files, severity }
+    }
+
+    // the shown diagnostics, with the path of their file
+    fn diagnostics(&self) -> impl Iterator<Item = (&str, &Diagnostic)> {
+        self.files.iter().flat_map(move |file| {
+            file.diagnostics
+                .iter()
+                .filter(move |diagnostic| Severity::of(diagnostic) <= self.severity)
+                .map(|diagnostic| (file.path.as_str(), diagnostic))
+        })
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics()
+            .filter(|(_, diagnostic)| Severity::of(diagnostic) == severity)
+            .count()
+    }
+
+    /// a diagnostic is at least as severe as `threshold`, shown or not
+    pub fn fails(&self, threshold: Severity) -> bool {
+        self.files
+            .iter()
+            .flat_map(|file| &file.diagnostics)
+            .any(|diagnostic| Severity::of(diagnostic) <= threshold)
+    }
+
+    /// eg: main.circom:3:5: warning: `out` is assigned with `<--` but never constrained
+    pub fn to_human(&self) -> String {
+        let mut lines: Vec<String> = self
+            .diagnostics()
+            .map(|(path, diagnostic)| {
+                let start = diagnostic.range.start;
+                format!(
+                    "{path}:{}:{}: {}: {}",
+                    start.line + 1,
+                    start.character + 1,
+                    Severity::of(diagnostic).name(),
+                    diagnostic.message
+                )
+            })
+            .collect();
+
+        let counts: Vec<String> = [
+            Severity::Error,
+            Severity::Warning,
+            Severity::Information,
+            Severity::Hint,
+        ]
+        .into_iter()
+        .filter_map(|severity| match self.count(severity) {
+            0 => None,
+            1 => Some(format!("1 {}", severity.name())),
+            count => Some(format!("{count} {}s", severity.name())),
+        })
+        .collect();
+        let files = match self.files.len() {
+            1 => "1 file".to_string(),
+            count => format!("{count} files"),
+        };
+        lines.push(if counts.is_empty() {
+            format!("no problems in {files}")
+        } else {
+            format!("{} in {files}", counts.join(", "))
+        });
+
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> String {
+        let diagnostics: Vec<JsonDiagnostic> = self
+            .diagnostics()
+            .map(|(path, diagnostic)| JsonDiagnostic {
+                path,
+                line: diagnostic.range.start.line + 1,
+                column: diagnostic.range.start.character + 1,
+                end_line: diagnostic.range.end.line + 1,
+                end_column: diagnostic.range.end.character + 1,
+                severity: Severity::of(diagnostic).name(),
+                message: &diagnostic.message,
+            })
+            .collect();
+        serde_json::to_string_pretty(&diagnostics).unwrap()
+    }
+
+    /// SARIF 2.1.0 log with one run, for code scanning tools
+    pub fn to_sarif(&self) -> String {
+        let results: Vec<serde_json::Value> = self
+            .diagnostics()
+            .map(|(path, diagnostic)| {
+                let range = diagnostic.range;
+                json!({
+                    "level": Severity::of(diagnostic).sarif_level(),
+                    "message": { "text": diagnostic.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": path.replace('\\', "/") },
+                            "region": {
+                                "startLine": range.start.line + 1,
+                                "startColumn": range.start.character + 1,
+                                "endLine": range.end.line + 1,
+                                "endColumn": range.end.character + 1,
+                            }
+                        }
+                    }]
+                })
+            })
+            .collect();
+
+        let log = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": SOURCE,
+                        "version": env!("CARGO_PKG_VERSION"),
+                    }
+                },
+                "results": results,
+            }]
+        });
+        serde_json::to_string_pretty(&log).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+
+    use crate::diagnostics::{error, warning};
+
+    use super::{CheckReport, FileReport, Severity};
+
+    fn report(severity: Severity) -> CheckReport {
+        let range = |line, character| {
+            Range::new(
+                Position::new(line, character),
+                Position::new(line, character + 3),
+            )
+        };
+        let files = vec![
+            FileReport {
+                path: "circuits/main.circom".to_string(),
+                diagnostics: vec![
+                    error(range(2, 4), "template `Bits` is not declared".to_string()),
+                    warning(range(5, 0), "`out` is never constrained".to_string()),
+                ],
+            },
+            FileReport {
+                path: "circuits/lib.circom".to_string(),
+                diagnostics: Vec::new(),
+            },
+        ];
+        CheckReport::new(files, severity)
+    }
+
+    #[test]
+    fn human_test() {
+        assert_eq!(
+            report(Severity::Hint).to_human(),
+            r#"circuits/main.circom:3:5: error: template `Bits` is not declared
+circuits/main.circom:6:1: warning: `out` is never constrained
+1 error, 1 warning in 2 files"#
+        );
+        assert_eq!(
+            report(Severity::Error).to_human(),
+            r#"circuits/main.circom:3:5: error: template `Bits` is not declared
+1 error in 2 files"#
+        );
+    }
+
+    #[test]
+    fn fails_test() {
+        // hidden diagnostics count too
+        let mut report = report(Severity::Error);
+        assert!(report.fails(Severity::Error));
+        assert!(report.fails(Severity::Warning));
+
+        report.files[0].diagnostics.remove(0);
+        assert!(!report.fails(Severity::Error));
+        assert!(report.fails(Severity::Hint));
+    }
+
+    #[test]
+    fn json_test() {
+        let diagnostics: serde_json::Value =
+            serde_json::from_str(&report(Severity::Hint).to_json()).unwrap();
+        assert_eq!(diagnostics.as_array().unwrap().len(), 2);
+        assert_eq!(diagnostics[1]["path"], "circuits/main.circom");
+        assert_eq!(diagnostics[1]["line"], 6);
+        assert_eq!(diagnostics[1]["end_column"], 4);
+        assert_eq!(diagnostics[1]["severity"], "warning");
+    }
+
+    #[test]
+    fn sarif_test() {
+        let log: serde_json::Value =
+            serde_json::from_str(&report(Severity::Hint).to_sarif()).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        let results = &log["runs"][0]["results"];
+        assert_eq!(results.as_array().unwrap().len(), 2);
+        assert_eq!(results[0]["level"], "error");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "circuits/main.circom");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["startColumn"], 5);
+    }
+}
@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use anyhow::{anyhow, Context, Result};
+use lsp_types::Url;
+use path_absolutize::Absolutize;
+use rowan::ast::AstNode;
+use syntax::format::{format, FormatOptions};
+
+use crate::check::{CheckReport, FileReport, Severity};
+use crate::flags::{self, CheckFormat, GraphFormat};
+use crate::global_state::{circom_files, GlobalState, TextDocument};
+use crate::graph::circuit_graph;
+
+// load a file and its includes from disk
+fn open(global_state: &mut GlobalState, path: &Path) -> Result<Url> {
+    let path = path.absolutize()?;
+    let text =
+        fs::read_to_string(&path).with_context(|| format!("can not read {}", path.display()))?;
+    let url = Url::from_file_path(&path).map_err(|_| anyhow!("invalid path {}", path.display()))?;
+
+    global_state.handle_update(&TextDocument::new(url.clone(), text))?;
+    Ok(url)
+}
+
+// a global state with a file and its includes loaded from disk
+fn load(path: &Path) -> Result<(GlobalState, Url)> {
+    let mut global_state = GlobalState::new();
+    let url = open(&mut global_state, path)?;
+    Ok((global_state, url))
+}
+
+impl flags::Constraints {
+    /// one line per template instance, eg: `Num2Bits(8)  8`
+    pub fn run(self) -> Result<()> {
+        let (global_state, url) = load(&self.path)?;
+
+        let mut rows = Vec::new();
+        for estimate in global_state.constraint_estimates(&url) {
+            if estimate.instances.is_empty() {
+                let name = estimate
+                    .template
+                    .name()
+                    .map(|name| name.syntax().text().to_string())
+                    .unwrap_or_default();
+                rows.push((name, None));
+            }
+            rows.extend(estimate.instances);
+        }
+
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, constraints) in rows {
+            let constraints = match constraints {
+                Some(constraints) => constraints.to_string(),
+                None => "unknown".to_string(),
+            };
+            println!("{name:width$}  {constraints}");
+        }
+        Ok(())
+    }
+}
+
+impl flags::Graph {
+    pub fn run(self) -> Result<()> {
+        let (global_state, url) = load(&self.path)?;
+
+        let graph = circuit_graph(&url, &global_state.included_files(&url));
+        match self.format.unwrap_or_default() {
+            GraphFormat::Dot => println!("{}", graph.to_dot()),
+            GraphFormat::Json => println!("{}", graph.to_json()),
+        }
+        Ok(())
+    }
+}
+
+impl flags::Check {
+    /// exits with status 1 if a diagnostic reaches the `--fail-on` severity
+    pub fn run(self) -> Result<()> {
+        let mut paths = Vec::new();
+        for path in &self.paths {
+            circom_files(path, &mut paths)?;
+        }
+        if paths.is_empty() {
+            return Err(anyhow!("no circom files to check"));
+        }
+
+        // all files are loaded first, to know which of them are entry points
+        let mut global_state = GlobalState::new();
+        global_state.config.warn_missing_main = self.warn_missing_main;
+        let mut urls = Vec::new();
+        for path in &paths {
+            urls.push(open(&mut global_state, path)?);
+        }
+
+        let files = paths
+            .iter()
+            .zip(&urls)
+            .map(|(path, url)| FileReport {
+                path: path.display().to_string(),
+                diagnostics: global_state.diagnostics(url),
+            })
+            .collect();
+        let report = CheckReport::new(files, self.severity.unwrap_or(Severity::Hint));
+
+        match self.format.unwrap_or_default() {
+            CheckFormat::Human => println!("{}", report.to_human()),
+            CheckFormat::Json => println!("{}", report.to_json()),
+            CheckFormat::Sarif => println!("{}", report.to_sarif()),
+        }
+        if report.fails(self.fail_on.unwrap_or(Severity::Error)) {
+            process::exit(1);
+        }
+        Ok(())
+    }
+}
+
+impl flags::Fmt {
+    /// exits with status 1 if a file has syntax errors, or is not formatted with `--check`
+    pub fn run(self) -> Result<()> {
+        let mut paths = Vec::new();
+        for path in &self.paths {
+            circom_files(path, &mut paths)?;
+        }
+
+        let mut failed = false;
+        for path in paths {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("can not read {}", path.display()))?;
+            let Some(formatted) = format(&text, &FormatOptions::default()) else {
+                eprintln!("{}: syntax errors, not formatted", path.display());
+                failed = true;
+                continue;
+            };
+            if formatted == text {
+                continue;
+            }
+
+            if self.check {
+                println!("{}", path.display());
+                failed = true;
+            } else {
+                fs::write(&path, formatted)
+                    .with_context(|| format!("can not write {}", path.display()))?;
+            }
+        }
+
+        if failed {
+            process::exit(1);
+        }
+        Ok(())
+    }
+}
@@ -17,7 +17,7 @@ use syntax::{
     syntax_node::{SyntaxNode, SyntaxToken},
 };
 
-/**
+/*
 * We will store
 * Open data -> Parse -> output -> Syntax -> analyzer -> db{
    FileID {
@@ -79,8 +79,9 @@ impl FileDB {
     }
 
     pub fn get_path(&self) -> PathBuf {
-        let p = self.file_path.path();
-        PathBuf::from(p)
+        self.file_path
+            .to_file_path()
+            .unwrap_or_else(|_| PathBuf::from(self.file_path.path()))
     }
 
     pub fn off_set(&self, position: Position) -> TextSize {
@@ -0,0 +1,36 @@
+use lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+
+pub mod arity;
+pub mod degree;
+pub mod dimension;
+pub mod main_component;
+pub mod pragma;
+pub mod quadratic;
+pub mod signal_assignment;
+pub mod signal_type;
+pub mod signal_usage;
+pub mod syntax_error;
+pub mod undeclared;
+
+/// name of the server, shown as the source of every diagnostic
+pub const SOURCE: &str = "ccls";
+
+pub fn error(range: Range, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some(SOURCE.to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+pub fn warning(range: Range, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::WARNING),
+        source: Some(SOURCE.to_string()),
+        message,
+        ..Default::default()
+    }
+}
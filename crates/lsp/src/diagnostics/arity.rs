@@ -0,0 +1,335 @@
+use std::collections::HashSet;
+
+use lsp_types::{Diagnostic, Url};
+use parser::token_kind::TokenKind::*;
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{
+    AstCall, AstCircomProgram, AstComponentDecl, AstExpr, AstParameterList,
+};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+
+use super::degree::{signal_name, Scope, Symbol};
+use super::error;
+use super::signal_assignment::{assign_operators, assignment_sides};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CallableKind {
+    Template,
+    Function,
+}
+
+impl CallableKind {
+    fn describe(&self) -> &'static str {
+        match self {
+            CallableKind::Template => "template",
+            CallableKind::Function => "function",
+        }
+    }
+}
+
+pub(super) fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{count} {word}")
+    } else {
+        format!("{count} {word}s")
+    }
+}
+
+/// eg: template `Num2Bits` takes 1 argument but 2 arguments were supplied
+pub fn arity_message(kind: &str, name: &str, expected: usize, found: usize) -> String {
+    format!(
+        "{kind} `{name}` takes {} but {} supplied",
+        plural(expected, "argument"),
+        if found == 1 {
+            "1 argument was".to_string()
+        } else {
+            format!("{found} arguments were")
+        }
+    )
+}
+
+fn parameter_count(parameters: Option<AstParameterList>) -> usize {
+    parameters
+        .map(|parameters| parameters.parameters().len())
+        .unwrap_or(0)
+}
+
+// kind and number of parameters of the template or function `name`
+fn find_callable(programs: &[&AstCircomProgram], name: &str) -> Option<(CallableKind, usize)> {
+    for program in programs {
+        for template in program.template_list() {
+            if template
+                .name()
+                .is_some_and(|template_name| template_name.syntax().text() == name)
+            {
+                let count = parameter_count(template.parameter_list());
+                return Some((CallableKind::Template, count));
+            }
+        }
+        for function in program.function_list() {
+            if function
+                .function_name()
+                .is_some_and(|function_name| function_name.syntax().text() == name)
+            {
+                let count = parameter_count(function.argument_list());
+                return Some((CallableKind::Function, count));
+            }
+        }
+    }
+    None
+}
+
+pub(super) fn strip_parentheses(expr: AstExpr) -> Option<AstExpr> {
+    match expr {
+        AstExpr::Expression(expression) => strip_parentheses(expression.expr()?),
+        expr => Some(expr),
+    }
+}
+
+/**
+ * calls which instantiate a component:
+ * - `c = T(...)` where `c` is a component
+ * - `T(...)(...)`, an anonymous component
+ */
+fn instantiations(root: &SyntaxNode, scope: &Scope) -> HashSet<SyntaxNode> {
+    let mut result = HashSet::new();
+
+    for operator in assign_operators(root, &[Assign]) {
+        let Some((target, value)) = assignment_sides(&operator) else {
+            continue;
+        };
+        if signal_name(&target).and_then(|name| scope.symbol(&name)) != Some(Symbol::Component) {
+            continue;
+        }
+        if let Some(AstExpr::Call(call)) = AstExpr::cast(value).and_then(strip_parentheses) {
+            result.insert(call.syntax().clone());
+        }
+    }
+
+    for call in root.descendants().filter_map(AstCall::cast) {
+        if let Some(AstExpr::Call(callee)) = call.callee() {
+            result.insert(callee.syntax().clone());
+        }
+    }
+
+    result
+}
+
+fn check_calls(
+    file: &FileDB,
+    root: &SyntaxNode,
+    scope: &Scope,
+    programs: &[&AstCircomProgram],
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let instantiations = instantiations(root, scope);
+
+    for call in root.descendants().filter_map(AstCall::cast) {
+        let Some(AstExpr::Atom(callee)) = call.callee() else {
+            continue;
+        };
+        let Some(name) = callee.identifier() else {
+            continue;
+        };
+        let name_text = name.syntax().text().to_string();
+        let Some((kind, expected)) = find_callable(programs, &name_text) else {
+            continue;
+        };
+
+        let is_instantiation = instantiations.contains(call.syntax());
+        let (node, message) = match kind {
+            CallableKind::Template if !is_instantiation => (
+                name.syntax().clone(),
+                format!("`{name_text}` is a template, it can not be called as a function"),
+            ),
+            CallableKind::Function if is_instantiation => (
+                name.syntax().clone(),
+                format!("`{name_text}` is a function, it can not be instantiated as a component"),
+            ),
+            _ => {
+                let found = call.arguments().len();
+                if expected == found {
+                    continue;
+                }
+                (
+                    call.syntax().clone(),
+                    arity_message(kind.describe(), &name_text, expected, found),
+                )
+            }
+        };
+        diagnostics.push(error(file.range(&node), message));
+    }
+
+    diagnostics
+}
+
+// component c = T(...);
+fn check_component_declarations(
+    file: &FileDB,
+    root: &SyntaxNode,
+    programs: &[&AstCircomProgram],
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for declaration in root.descendants().filter_map(AstComponentDecl::cast) {
+        let (Some(template), Some(call)) = (declaration.template(), declaration.call()) else {
+            continue;
+        };
+        let name = template.syntax().text().to_string();
+
+        match find_callable(programs, &name) {
+            Some((CallableKind::Function, _)) => diagnostics.push(error(
+                file.range(template.syntax()),
+                format!("`{name}` is a function, it can not be instantiated as a component"),
+            )),
+            Some((CallableKind::Template, expected)) => {
+                let found = call.arguments().len();
+                if expected != found {
+                    diagnostics.push(error(
+                        file.range(call.syntax()),
+                        arity_message("template", &name, expected, found),
+                    ));
+                }
+            }
+            None => {}
+        }
+    }
+
+    diagnostics
+}
+
+/**
+ * calls of templates and functions declared in the file or its included libs
+ * - the number of arguments matches the number of parameters
+ * - templates are only instantiated as components, functions are only called
+ *
+ * `component main = T(...);` is checked by `check_main_component`
+ */
+pub fn check_arity(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    libs: &[(Url, AstCircomProgram)],
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let programs: Vec<&AstCircomProgram> = std::iter::once(ast)
+        .chain(libs.iter().map(|(_, lib)| lib))
+        .collect();
+
+    for template in ast.template_list() {
+        let scope = Scope::new(&template);
+        diagnostics.extend(check_component_declarations(
+            file,
+            template.syntax(),
+            &programs,
+        ));
+        diagnostics.extend(check_calls(file, template.syntax(), &scope, &programs));
+    }
+
+    for function in ast.function_list() {
+        let scope = Scope::function(&function);
+        diagnostics.extend(check_component_declarations(
+            file,
+            function.syntax(),
+            &programs,
+        ));
+        diagnostics.extend(check_calls(file, function.syntax(), &scope, &programs));
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::check_arity;
+
+    const LIB: &str = r#"
+    template Num2Bits(n) {
+        signal input in;
+        signal output out[n];
+    }
+
+    function nbits(a) {
+        return a;
+    }"#;
+
+    fn messages(source: &str) -> Vec<String> {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        let lib_url = Url::from_file_path(Path::new("/tmp/lib.circom")).unwrap();
+        let libs = vec![(
+            lib_url,
+            AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(LIB)).unwrap(),
+        )];
+
+        check_arity(&file, &ast, &libs)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    fn template(body: &str) -> Vec<String> {
+        messages(&format!(
+            "template A(n) {{\n    signal input a;\n    signal output b;\n{body}\n}}"
+        ))
+    }
+
+    #[test]
+    fn valid_calls_test() {
+        assert!(template("    component c = Num2Bits(8);\n    var v = nbits(n);").is_empty());
+        assert!(template("    component c[2];\n    c[0] = Num2Bits(nbits(n));").is_empty());
+        assert!(template("    b <== Num2Bits(8)(a);").is_empty());
+        // unknown names are reported elsewhere
+        assert!(template("    component c = Unknown(1, 2);\n    var v = g();").is_empty());
+    }
+
+    #[test]
+    fn argument_count_test() {
+        assert_eq!(
+            template("    component c = Num2Bits(8, 3);"),
+            vec!["template `Num2Bits` takes 1 argument but 2 arguments were supplied"]
+        );
+        assert_eq!(
+            template("    component c[2];\n    c[1] = Num2Bits();"),
+            vec!["template `Num2Bits` takes 1 argument but 0 arguments were supplied"]
+        );
+        assert_eq!(
+            template("    var v = nbits(1, n);"),
+            vec!["function `nbits` takes 1 argument but 2 arguments were supplied"]
+        );
+        assert_eq!(
+            messages("function f(x, y) {\n    return f(x) + nbits();\n}"),
+            vec![
+                "function `f` takes 2 arguments but 1 argument was supplied",
+                "function `nbits` takes 1 argument but 0 arguments were supplied",
+            ]
+        );
+    }
+
+    #[test]
+    fn template_or_function_test() {
+        assert_eq!(
+            template("    var v = Num2Bits(8);"),
+            vec!["`Num2Bits` is a template, it can not be called as a function"]
+        );
+        assert_eq!(
+            template("    component c = nbits(8);"),
+            vec!["`nbits` is a function, it can not be instantiated as a component"]
+        );
+        assert_eq!(
+            template("    component c;\n    c = nbits(8);"),
+            vec!["`nbits` is a function, it can not be instantiated as a component"]
+        );
+    }
+}
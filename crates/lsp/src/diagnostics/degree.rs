@@ -0,0 +1,451 @@
+use std::collections::{HashMap, HashSet};
+
+use parser::token_kind::TokenKind::{self, *};
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{
+    AstAssignStatement, AstExpr, AstExpression, AstFunctionDef, AstParameterList, AstTemplateDef,
+};
+use syntax::const_eval::compound_operator;
+use syntax::syntax_node::SyntaxNode;
+
+// above this degree, the degree of a variable is unknown
+const MAX_VARIABLE_DEGREE: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol {
+    Signal,
+    Variable,
+    Parameter,
+    Component,
+}
+
+// first identifier of every ComplexIdentifier declared by nodes of `kinds`
+fn declared_names(root: &SyntaxNode, kinds: &[TokenKind]) -> HashSet<String> {
+    root.descendants()
+        .filter(|node| kinds.contains(&node.kind()))
+        .flat_map(|declaration| declaration.children())
+        .filter(|node| node.kind() == ComplexIdentifier)
+        .filter_map(|identifier| identifier.first_child())
+        .map(|name| name.text().to_string())
+        .collect()
+}
+
+/// names of the signals declared in a template, eg: `a` for `signal input a[N];`
+pub fn template_signals(template: &AstTemplateDef) -> HashSet<String> {
+    declared_names(
+        template.syntax(),
+        &[SignalDecl, InputSignalDecl, OutputSignalDecl],
+    )
+}
+
+/**
+ * shape of an expression in the signals of a template, ordered from the simplest.
+ * a constraint of circom has the form A * B + C with A, B and C linear
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Form {
+    Constant,
+    Linear,
+    /// A * B + C
+    Quadratic,
+    NonQuadratic,
+}
+
+impl Form {
+    fn of_degree(degree: u32) -> Self {
+        match degree {
+            0 => Form::Constant,
+            1 => Form::Linear,
+            2 => Form::Quadratic,
+            _ => Form::NonQuadratic,
+        }
+    }
+
+    /// form of a + b and a - b, eg: a * b + c * d is not quadratic
+    pub fn sum(self, other: Self) -> Self {
+        match (self, other) {
+            (Form::Quadratic, Form::Quadratic) => Form::NonQuadratic,
+            _ => self.max(other),
+        }
+    }
+
+    fn product(self, other: Self) -> Self {
+        match (self, other) {
+            (Form::Constant, form) | (form, Form::Constant) => form,
+            (Form::Linear, Form::Linear) => Form::Quadratic,
+            _ => Form::NonQuadratic,
+        }
+    }
+}
+
+/// signals, variables, components and parameters of a template (or a function)
+#[derive(Debug, Default)]
+pub struct Scope {
+    signals: HashSet<String>,
+    variables: HashSet<String>,
+    components: HashSet<String>,
+    parameters: HashSet<String>,
+    // the highest degree and form of the values assigned to a variable, None if unknown
+    variable_degrees: HashMap<String, Option<u32>>,
+    variable_forms: HashMap<String, Option<Form>>,
+}
+
+// values assigned to variables, with the operator of compound assignments, eg: `*` for v *= a
+fn variable_assignments(
+    root: &SyntaxNode,
+    scope: &Scope,
+) -> Vec<(String, Option<TokenKind>, AstExpr)> {
+    let mut result = Vec::new();
+
+    for node in root.descendants() {
+        if node.kind() == VarDecl {
+            // var a = 1, b[2], c = a;
+            let mut declared = None;
+            for child in node.children() {
+                if child.kind() == ComplexIdentifier {
+                    declared = signal_name(&child);
+                } else if let Some(value) =
+                    AstExpression::cast(child).and_then(|value| value.expr())
+                {
+                    result.extend(declared.clone().map(|name| (name, None, value)));
+                }
+            }
+        } else if let Some(assign) = AstAssignStatement::cast(node) {
+            let (Some(target), Some(value)) = (
+                assign.lhs().and_then(|lhs| lhs.expr()),
+                assign.rhs().and_then(|rhs| rhs.expr()),
+            ) else {
+                continue;
+            };
+            let Some(name) =
+                signal_name(target.syntax()).filter(|name| scope.variables.contains(name))
+            else {
+                continue;
+            };
+            match assign.operator() {
+                Some(Assign) => result.push((name, None, value)),
+                Some(operator) => result.extend(
+                    compound_operator(operator).map(|operator| (name, Some(operator), value)),
+                ),
+                None => {}
+            }
+        }
+    }
+
+    result
+}
+
+// degree of `lhs <operator> rhs` from the degrees of its operands
+fn binary_degree(operator: TokenKind, lhs: Option<u32>, rhs: Option<u32>) -> Option<u32> {
+    match operator {
+        Add | Sub => Some(lhs?.max(rhs?)),
+        Mul => Some(lhs? + rhs?),
+        // division by a constant is a multiplication by its inverse
+        Div if rhs? == 0 => lhs,
+        _ if lhs? == 0 && rhs? == 0 => Some(0),
+        _ => None,
+    }
+}
+
+fn binary_form(operator: TokenKind, lhs: Option<Form>, rhs: Option<Form>) -> Option<Form> {
+    match operator {
+        Add | Sub => Some(lhs?.sum(rhs?)),
+        Mul => Some(lhs?.product(rhs?)),
+        Div if rhs? == Form::Constant => lhs,
+        _ if lhs? == Form::Constant && rhs? == Form::Constant => Some(Form::Constant),
+        _ => None,
+    }
+}
+
+impl Scope {
+    fn collect(root: &SyntaxNode, parameters: Option<AstParameterList>) -> Self {
+        let parameters = parameters
+            .map(|list| list.parameters())
+            .unwrap_or_default()
+            .iter()
+            .map(|parameter| parameter.syntax().text().to_string())
+            .collect();
+
+        let mut scope = Self {
+            signals: declared_names(root, &[SignalDecl, InputSignalDecl, OutputSignalDecl]),
+            variables: declared_names(root, &[VarDecl]),
+            components: declared_names(root, &[ComponentDecl]),
+            parameters,
+            ..Default::default()
+        };
+        scope.infer_variables(root);
+        scope
+    }
+
+    /**
+     * degree and form of the variables, from the values assigned to them,
+     * eg: `t` has degree 2 with var t = a * b; and signals a and b.
+     * they only grow with each assignment (a loop may assign a variable again),
+     * until they are unknown
+     */
+    fn infer_variables(&mut self, root: &SyntaxNode) {
+        let assignments = variable_assignments(root, self);
+
+        loop {
+            let mut changed = false;
+            for (name, operator, value) in &assignments {
+                let (degree_before, form_before) =
+                    (self.variable_degree(name), self.variable_form(name));
+                let (assigned_degree, assigned_form) = match operator {
+                    Some(operator) => (
+                        binary_degree(*operator, degree_before, degree(value, self)),
+                        binary_form(*operator, form_before, form(value, self)),
+                    ),
+                    None => (degree(value, self), form(value, self)),
+                };
+
+                let degree = match (degree_before, assigned_degree) {
+                    (Some(before), Some(assigned)) if assigned <= MAX_VARIABLE_DEGREE => {
+                        Some(before.max(assigned))
+                    }
+                    _ => None,
+                };
+                let form = match (form_before, assigned_form) {
+                    (Some(before), Some(assigned)) => Some(before.max(assigned)),
+                    _ => None,
+                };
+
+                if (degree, form) != (degree_before, form_before) {
+                    self.variable_degrees.insert(name.clone(), degree);
+                    self.variable_forms.insert(name.clone(), form);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    fn variable_degree(&self, name: &str) -> Option<u32> {
+        self.variable_degrees.get(name).copied().unwrap_or(Some(0))
+    }
+
+    fn variable_form(&self, name: &str) -> Option<Form> {
+        self.variable_forms
+            .get(name)
+            .copied()
+            .unwrap_or(Some(Form::Constant))
+    }
+
+    pub fn new(template: &AstTemplateDef) -> Self {
+        Self::collect(template.syntax(), template.parameter_list())
+    }
+
+    pub fn function(function: &AstFunctionDef) -> Self {
+        Self::collect(function.syntax(), function.argument_list())
+    }
+
+    pub fn symbol(&self, name: &str) -> Option<Symbol> {
+        if self.signals.contains(name) {
+            Some(Symbol::Signal)
+        } else if self.variables.contains(name) {
+            Some(Symbol::Variable)
+        } else if self.components.contains(name) {
+            Some(Symbol::Component)
+        } else if self.parameters.contains(name) {
+            Some(Symbol::Parameter)
+        } else {
+            None
+        }
+    }
+}
+
+/**
+ * name of the signal an expression refers to, indices are ignored
+ * eg: `a` for a[i], `c.out` for c[i].out
+ */
+pub fn signal_name(node: &SyntaxNode) -> Option<String> {
+    if node.kind() == ComplexIdentifier {
+        return node.first_child().map(|name| name.text().to_string());
+    }
+
+    match AstExpr::cast(node.clone())? {
+        AstExpr::Atom(atom) => atom
+            .identifier()
+            .map(|name| name.syntax().text().to_string()),
+        AstExpr::Expression(expression) => signal_name(expression.expr()?.syntax()),
+        AstExpr::ArrayQuery(query) => signal_name(query.indices().0?.syntax()),
+        AstExpr::ComponentCall(call) => {
+            let component = signal_name(call.component()?.syntax())?;
+            let signal = call.signal()?;
+            Some(format!("{component}.{}", signal.syntax().text()))
+        }
+        _ => None,
+    }
+}
+
+/**
+ * degree of an expression as a polynomial in the signals of a template,
+ * None if it is not a polynomial (eg: a / b, a % 2 with a signal a)
+ * signals of sub-components (c.out) are signals too,
+ * variables have the degree of their values, parameters and constants have degree 0
+ */
+pub fn degree(expr: &AstExpr, scope: &Scope) -> Option<u32> {
+    // operands which must not depend on signals
+    let constant = |operands: Vec<Option<AstExpr>>| {
+        for operand in operands.into_iter().flatten() {
+            if degree(&operand, scope)? > 0 {
+                return None;
+            }
+        }
+        Some(0)
+    };
+
+    match expr {
+        AstExpr::ComponentCall(_) => Some(1),
+        AstExpr::Atom(_) => {
+            let name = signal_name(expr.syntax());
+            match name.as_deref().and_then(|name| scope.symbol(name)) {
+                Some(Symbol::Signal) => Some(1),
+                Some(Symbol::Variable) => scope.variable_degree(name.as_deref()?),
+                _ => Some(0),
+            }
+        }
+        AstExpr::Expression(expression) => degree(&expression.expr()?, scope),
+        AstExpr::ArrayQuery(query) => {
+            let (array, indices) = query.indices();
+            constant(indices.iter().map(|index| index.expr()).collect())?;
+            degree(&array?, scope)
+        }
+        AstExpr::Binary(binary) => binary_degree(
+            binary.operator(),
+            degree(&binary.lhs()?, scope),
+            degree(&binary.rhs()?, scope),
+        ),
+        AstExpr::Prefix(prefix) => match prefix.operator() {
+            Sub => degree(&prefix.operand()?, scope),
+            _ => constant(vec![prefix.operand()]),
+        },
+        AstExpr::Postfix(postfix) => constant(vec![postfix.operand()]),
+        // the condition is known at compile time, so only one branch is used
+        AstExpr::Tenary(tenary) => {
+            constant(vec![tenary.condition()?.expr()])?;
+            let if_true = degree(&tenary.if_true()?.expr()?, scope)?;
+            let if_false = degree(&tenary.if_false()?.expr()?, scope)?;
+            Some(if_true.max(if_false))
+        }
+        AstExpr::Call(call) => constant(call.arguments().into_iter().map(Some).collect()),
+    }
+}
+
+/// form of an expression, None if it is not a polynomial in the signals (see `degree`)
+pub fn form(expr: &AstExpr, scope: &Scope) -> Option<Form> {
+    match expr {
+        AstExpr::Atom(_) => match signal_name(expr.syntax()) {
+            Some(name) if scope.symbol(&name) == Some(Symbol::Variable) => {
+                scope.variable_form(&name)
+            }
+            _ => degree(expr, scope).map(Form::of_degree),
+        },
+        AstExpr::Expression(expression) => form(&expression.expr()?, scope),
+        AstExpr::ArrayQuery(query) => {
+            // indices which do not depend on signals
+            degree(expr, scope)?;
+            form(&query.indices().0?, scope)
+        }
+        AstExpr::Binary(binary) => binary_form(
+            binary.operator(),
+            form(&binary.lhs()?, scope),
+            form(&binary.rhs()?, scope),
+        ),
+        AstExpr::Prefix(prefix) if prefix.operator() == Sub => form(&prefix.operand()?, scope),
+        AstExpr::Tenary(tenary) => {
+            degree(expr, scope)?;
+            let if_true = form(&tenary.if_true()?.expr()?, scope)?;
+            let if_false = form(&tenary.if_false()?.expr()?, scope)?;
+            Some(if_true.max(if_false))
+        }
+        _ => degree(expr, scope).map(Form::of_degree),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rowan::ast::AstNode;
+    use syntax::abstract_syntax_tree::{AstCircomProgram, AstStatement};
+    use syntax::syntax::SyntaxTreeBuilder;
+
+    use super::{degree, Scope, Symbol};
+
+    // degree of the right hand side of `x <-- <expression>;`
+    fn expression_degree(expression: &str) -> Option<u32> {
+        let source = format!(
+            "template A(n) {{ signal input a; signal input b[2]; var v = 2; component c = B(); x <-- {expression}; }}"
+        );
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(&source)).unwrap();
+        let template = &ast.template_list()[0];
+        let scope = Scope::new(template);
+
+        let statements = template.statements().unwrap().statement_list();
+        let Some(AstStatement::AssignStatement(assign)) = statements.last() else {
+            unreachable!()
+        };
+        degree(&assign.rhs()?.expr()?, &scope)
+    }
+
+    #[test]
+    fn degree_test() {
+        assert_eq!(expression_degree("n * 2 + 1"), Some(0));
+        assert_eq!(expression_degree("a * v * n"), Some(1));
+        assert_eq!(expression_degree("a + n"), Some(1));
+        assert_eq!(expression_degree("a * b[0] - 1"), Some(2));
+        assert_eq!(expression_degree("(a + 1) * (c.out - n)"), Some(2));
+        assert_eq!(expression_degree("a * a * b[n]"), Some(3));
+        assert_eq!(expression_degree("-a * b[1] / 2"), Some(2));
+        assert_eq!(expression_degree("a / b[0]"), None);
+        assert_eq!(expression_degree("b[a]"), None);
+        assert_eq!(expression_degree("a > 0 ? 1 : 0"), None);
+        assert_eq!(expression_degree("n > 0 ? 1 : 0"), Some(0));
+        assert_eq!(expression_degree("n > 0 ? a * b : a"), Some(2));
+        assert_eq!(expression_degree("f(a)"), None);
+    }
+
+    #[test]
+    fn variable_degree_test() {
+        let source = r#"template A(n) {
+    signal input a;
+    var t = a * a;
+    var k = n;
+    var acc = 0;
+    for (var i = 0; i < n; i++) {
+        acc += t * a;
+        k *= 2;
+    }
+    var p = 1;
+    for (var j = 0; j < n; j++) {
+        p *= a;
+    }
+}"#;
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        let scope = Scope::new(&ast.template_list()[0]);
+
+        assert_eq!(scope.variable_degree("t"), Some(2));
+        assert_eq!(scope.variable_degree("acc"), Some(3));
+        assert_eq!(scope.variable_degree("k"), Some(0));
+        assert_eq!(scope.variable_degree("i"), Some(0));
+        // grows with each iteration
+        assert_eq!(scope.variable_degree("p"), None);
+    }
+
+    #[test]
+    fn scope_test() {
+        let source =
+            "template A(n) { signal input a[n]; var v, w[2]; component c[n]; for (var i = 0; i < n; i++) {} }";
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        let scope = Scope::new(&ast.template_list()[0]);
+
+        assert_eq!(scope.symbol("a"), Some(Symbol::Signal));
+        assert_eq!(scope.symbol("v"), Some(Symbol::Variable));
+        assert_eq!(scope.symbol("w"), Some(Symbol::Variable));
+        assert_eq!(scope.symbol("i"), Some(Symbol::Variable));
+        assert_eq!(scope.symbol("c"), Some(Symbol::Component));
+        assert_eq!(scope.symbol("n"), Some(Symbol::Parameter));
+        assert_eq!(scope.symbol("x"), None);
+    }
+}
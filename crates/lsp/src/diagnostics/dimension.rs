@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use lsp_types::Diagnostic;
+use parser::token_kind::TokenKind::*;
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{
+    AstArrayQuery, AstCircomProgram, AstComplexIdentifier, AstExpr, AstExpression,
+    AstExpressionAtom,
+};
+use syntax::const_eval::{Bindings, ConstEvaluator};
+use syntax::field::Prime;
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+
+use super::arity::{plural, strip_parentheses};
+use super::degree::{Scope, Symbol};
+use super::error;
+use super::signal_assignment::{assign_operators, assignment_sides, trimmed_range};
+
+// name --> dimensions of its first declaration, eg: `a` --> [N, M - 1] for signal a[N][M - 1];
+fn declared_dimensions(root: &SyntaxNode) -> HashMap<String, Vec<AstExpression>> {
+    let mut result = HashMap::new();
+
+    let declarations = root.descendants().filter(|node| {
+        matches!(
+            node.kind(),
+            SignalDecl | InputSignalDecl | OutputSignalDecl | VarDecl | ComponentDecl
+        )
+    });
+    for declaration in declarations {
+        for identifier in declaration
+            .children()
+            .filter_map(AstComplexIdentifier::cast)
+        {
+            if let Some(name) = identifier.name() {
+                result
+                    .entry(name.syntax().text().to_string())
+                    .or_insert_with(|| identifier.dimensions());
+            }
+        }
+    }
+
+    result
+}
+
+// value of an index or a dimension which does not depend on template parameters
+fn constant(evaluator: &mut ConstEvaluator, expression: &AstExpression) -> Option<usize> {
+    evaluator
+        .evaluate(&expression.expr()?, &Bindings::new())?
+        .to_usize()
+}
+
+/// `a[i][j]` for the atom `a`: the outermost array query and the indices
+fn access(atom: &AstExpressionAtom) -> (SyntaxNode, Vec<AstExpression>) {
+    let mut node = atom.syntax().clone();
+    let mut indices = Vec::new();
+
+    while let Some(query) = node.parent().and_then(AstArrayQuery::cast) {
+        if query.array().map(|array| array.syntax().clone()) != Some(node.clone()) {
+            break;
+        }
+        if let Some(index) = query.index() {
+            indices.push(index);
+        }
+        node = query.syntax().clone();
+    }
+
+    (node, indices)
+}
+
+fn indices_message(name: &str, dimensions: usize, indices: usize) -> String {
+    format!(
+        "`{name}` has {} but {} used",
+        plural(dimensions, "dimension"),
+        if indices == 1 {
+            "1 index is".to_string()
+        } else {
+            format!("{indices} indices are")
+        }
+    )
+}
+
+// the sides of every `===`, `<==` and `==>`
+fn constraint_sides(root: &SyntaxNode) -> Vec<SyntaxNode> {
+    let mut result = Vec::new();
+    for operator in assign_operators(
+        root,
+        &[EqualSignal, RAssignConstraintSignal, LAssignContraintSignal],
+    ) {
+        if let Some((target, value)) = assignment_sides(&operator) {
+            result.push(target);
+            result.push(value);
+        }
+    }
+    result
+}
+
+// an operand of an arithmetic expression, not a whole array, eg: `a` in a * b
+fn is_operand(node: &SyntaxNode) -> bool {
+    let mut parent = node.parent();
+    while let Some(node) = parent.clone().filter(|node| node.kind() == Expression) {
+        parent = node.parent();
+    }
+    parent.and_then(AstExpr::cast).is_some_and(|expr| {
+        matches!(
+            expr,
+            AstExpr::Binary(_) | AstExpr::Prefix(_) | AstExpr::Postfix(_) | AstExpr::Tenary(_)
+        )
+    })
+}
+
+// the targets of every `=`, eg: `c[i]` in c[i] = T()
+fn assigned_expressions(root: &SyntaxNode) -> Vec<SyntaxNode> {
+    assign_operators(root, &[Assign])
+        .into_iter()
+        .filter_map(|operator| assignment_sides(&operator))
+        .filter_map(|(target, _)| AstExpr::cast(target).and_then(strip_parentheses))
+        .map(|target| target.syntax().clone())
+        .collect()
+}
+
+fn check_accesses(
+    file: &FileDB,
+    root: &SyntaxNode,
+    scope: &Scope,
+    evaluator: &mut ConstEvaluator,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let declarations = declared_dimensions(root);
+    let constraint_sides = constraint_sides(root);
+    let assigned = assigned_expressions(root);
+
+    for atom in root.descendants().filter_map(AstExpressionAtom::cast) {
+        let Some(name) = atom
+            .identifier()
+            .map(|name| name.syntax().text().to_string())
+        else {
+            continue;
+        };
+        let (Some(dimensions), Some(symbol)) = (declarations.get(&name), scope.symbol(&name))
+        else {
+            continue;
+        };
+        let (node, indices) = access(&atom);
+        let range = trimmed_range(file, &node);
+
+        if indices.len() > dimensions.len() {
+            diagnostics.push(error(
+                range,
+                indices_message(&name, dimensions.len(), indices.len()),
+            ));
+            continue;
+        }
+
+        for (position, (index, dimension)) in indices.iter().zip(dimensions).enumerate() {
+            let (Some(index_value), Some(size)) =
+                (constant(evaluator, index), constant(evaluator, dimension))
+            else {
+                continue;
+            };
+            if index_value >= size {
+                diagnostics.push(error(
+                    trimmed_range(file, index.syntax()),
+                    format!(
+                        "index {index_value} is out of bounds for `{name}`, dimension {} has size {size}",
+                        position + 1
+                    ),
+                ));
+            }
+        }
+
+        if indices.len() == dimensions.len() {
+            continue;
+        }
+        match symbol {
+            // a single component is used, eg: c[i].out or c[i] = T()
+            Symbol::Component
+                if node
+                    .parent()
+                    .is_some_and(|parent| parent.kind() == ComponentCall)
+                    || assigned.contains(&node) =>
+            {
+                diagnostics.push(error(
+                    range,
+                    indices_message(&name, dimensions.len(), indices.len()),
+                ));
+            }
+            Symbol::Signal
+                if is_operand(&node)
+                    && constraint_sides
+                        .iter()
+                        .any(|side| side.text_range().contains_range(node.text_range())) =>
+            {
+                let message = if indices.is_empty() {
+                    format!("array signal `{name}` can not be used as a scalar in a constraint")
+                } else {
+                    indices_message(&name, dimensions.len(), indices.len())
+                };
+                diagnostics.push(error(range, message));
+            }
+            _ => {}
+        }
+    }
+
+    diagnostics
+}
+
+/**
+ * accesses to arrays of signals, variables and components
+ * - no more indices than the declared dimensions
+ * - a single component is used for `c[i].out` and `c[i] = T()`
+ * - array signals are not used as scalars in a constraint
+ * - constant indices are lower than constant dimensions
+ */
+pub fn check_dimensions(file: &FileDB, ast: &AstCircomProgram, prime: &Prime) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut evaluator = ConstEvaluator::new([ast], prime);
+
+    for template in ast.template_list() {
+        let scope = Scope::new(&template);
+        diagnostics.extend(check_accesses(
+            file,
+            template.syntax(),
+            &scope,
+            &mut evaluator,
+        ));
+    }
+
+    for function in ast.function_list() {
+        let scope = Scope::function(&function);
+        diagnostics.extend(check_accesses(
+            file,
+            function.syntax(),
+            &scope,
+            &mut evaluator,
+        ));
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, field::Prime, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::check_dimensions;
+
+    fn messages(source: &str) -> Vec<String> {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+
+        check_dimensions(&file, &ast, &Prime::default())
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    fn template(body: &str) -> Vec<String> {
+        messages(&format!(
+            "template A(n) {{\n    signal input a[n][2];\n    signal input b[3];\n    signal output c;\n    var v[4];\n    component d[2];\n{body}\n}}"
+        ))
+    }
+
+    #[test]
+    fn valid_access_test() {
+        assert!(template("    c <== a[0][1] * b[2] + v[3];").is_empty());
+        assert!(template("    d[1] = B();\n    d[1].in <== b;").is_empty());
+        assert!(template("    v[n + 4] = 1;\n    c <== a[n][1];").is_empty());
+    }
+
+    #[test]
+    fn index_count_test() {
+        assert_eq!(
+            template("    c <== b[0][1];"),
+            vec!["`b` has 1 dimension but 2 indices are used"]
+        );
+        assert_eq!(
+            template("    c <== a[0] * 2;"),
+            vec!["`a` has 2 dimensions but 1 index is used"]
+        );
+        assert_eq!(
+            template("    c <== b + 1;"),
+            vec!["array signal `b` can not be used as a scalar in a constraint"]
+        );
+        assert_eq!(
+            template("    d = B();\n    c <== d.out;"),
+            vec![
+                "`d` has 1 dimension but 0 indices are used",
+                "`d` has 1 dimension but 0 indices are used",
+            ]
+        );
+        assert_eq!(
+            messages("function f() {\n    var x[2];\n    return x[0][0];\n}"),
+            vec!["`x` has 1 dimension but 2 indices are used"]
+        );
+    }
+
+    #[test]
+    fn out_of_bounds_test() {
+        assert_eq!(
+            template("    c <== b[3];\n    v[2 * 2] = 1;"),
+            vec![
+                "index 3 is out of bounds for `b`, dimension 1 has size 3",
+                "index 4 is out of bounds for `v`, dimension 1 has size 4",
+            ]
+        );
+        assert_eq!(
+            template("    c <== a[5][1 + 1];"),
+            vec!["index 2 is out of bounds for `a`, dimension 2 has size 2"]
+        );
+        assert_eq!(
+            messages(
+                "function size() {\n    return 2;\n}\ntemplate B() {\n    signal input x[size()];\n    signal output y;\n    y <== x[1 << 1];\n}"
+            ),
+            vec!["index 2 is out of bounds for `x`, dimension 1 has size 2"]
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use lsp_types::{Diagnostic, Url};
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{AstCircomProgram, AstMainComponent, AstTemplateDef};
+
+use crate::database::FileDB;
+
+use super::arity::arity_message;
+use super::signal_assignment::trimmed_range;
+use super::{error, warning};
+
+/**
+ * semantic checks of `component main {public [...]} = T(...);`
+ * - at most one main component in the file and its included libs,
+ *   reported once with all the files declaring one
+ * - T is declared and called with the right number of arguments
+ * - every public signal is an input signal of T
+ * - with `needs_main` (the `warnMissingMain` setting, for an entrypoint: a file not included
+ *   by any other file, whose templates are not used by any other file) there is a main
+ *   component, reported at the first template of the file
+ */
+pub fn check_main_component(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    libs: &[(Url, AstCircomProgram)],
+    needs_main: bool,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let main_components: Vec<AstMainComponent> = ast
+        .syntax()
+        .children()
+        .filter_map(AstMainComponent::cast)
+        .collect();
+
+    // the included libs which declare a main component
+    let mut lib_mains: Vec<String> = libs
+        .iter()
+        .filter(|(_, lib)| lib.main_component().is_some())
+        .map(|(lib_url, _)| display_path(lib_url))
+        .collect();
+    lib_mains.sort();
+
+    if main_components.is_empty() {
+        if lib_mains.len() > 1 {
+            // reported at the first include of the file
+            if let Some(include) = ast.libs().first() {
+                diagnostics.push(error(
+                    trimmed_range(file, include.syntax()),
+                    format!(
+                        "`component main` is declared in several included files: {}",
+                        lib_mains.join(", ")
+                    ),
+                ));
+            }
+            return diagnostics;
+        }
+        if !needs_main || !lib_mains.is_empty() {
+            return diagnostics;
+        }
+        // the name of the first template, or the last item of a file without templates
+        let anchor = match ast.template_list().first() {
+            Some(template) => template.name().map(|name| name.syntax().clone()),
+            None => ast
+                .syntax()
+                .children()
+                .filter(|child| !child.kind().is_trivial())
+                .last(),
+        };
+        if let Some(anchor) = anchor {
+            diagnostics.push(warning(
+                trimmed_range(file, &anchor),
+                "no `component main` in this file or its includes, it can not be compiled"
+                    .to_string(),
+            ));
+        }
+        return diagnostics;
+    }
+
+    for main_component in main_components.iter().skip(1) {
+        diagnostics.push(error(
+            file.range(main_component.syntax()),
+            "`component main` is already declared in this file".to_string(),
+        ));
+    }
+
+    if !lib_mains.is_empty() {
+        diagnostics.push(error(
+            file.range(main_components[0].syntax()),
+            format!(
+                "`component main` is already declared in {}",
+                lib_mains.join(", ")
+            ),
+        ));
+    }
+
+    for main_component in &main_components {
+        diagnostics.extend(check_instance(file, ast, libs, main_component));
+    }
+
+    diagnostics
+}
+
+fn display_path(url: &Url) -> String {
+    url.to_file_path()
+        .map_or(url.path().to_string(), |path| path.display().to_string())
+}
+
+// the instantiated template and the public signals
+fn check_instance(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    libs: &[(Url, AstCircomProgram)],
+    main_component: &AstMainComponent,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let Some(template_name) = main_component.template() else {
+        return diagnostics;
+    };
+    let name = template_name.syntax().text().to_string();
+
+    let template: Option<AstTemplateDef> = std::iter::once(ast)
+        .chain(libs.iter().map(|(_, lib)| lib))
+        .find_map(|program| program.get_template_by_name(&template_name));
+
+    let Some(template) = template else {
+        diagnostics.push(error(
+            file.range(template_name.syntax()),
+            format!("template `{name}` is not declared"),
+        ));
+        return diagnostics;
+    };
+
+    if let Some(call) = main_component.call() {
+        let expected = template
+            .parameter_list()
+            .map(|parameters| parameters.parameters().len())
+            .unwrap_or(0);
+        let found = call.arguments().len();
+
+        if expected != found {
+            diagnostics.push(error(
+                file.range(call.syntax()),
+                arity_message("template", &name, expected, found),
+            ));
+        }
+    }
+
+    for signal in main_component.public_signals() {
+        let signal_name = signal.syntax().text().to_string();
+        if template.find_input_signal(&signal_name).is_some() {
+            continue;
+        }
+
+        let message = if template.find_output_signal(&signal_name).is_some() {
+            format!(
+                "`{signal_name}` is an output signal of `{name}`, only input signals can be public"
+            )
+        } else if template.find_internal_signal(&signal_name).is_some() {
+            format!("`{signal_name}` is an intermediate signal of `{name}`, only input signals can be public")
+        } else {
+            format!("`{signal_name}` is not an input signal of `{name}`")
+        };
+        diagnostics.push(error(file.range(signal.syntax()), message));
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{DiagnosticSeverity, Position, Url};
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::check_main_component;
+
+    const TEMPLATE: &str = r#"
+    template Multiplier(n) {
+        signal input a;
+        signal input b;
+        signal t;
+        signal output c;
+        t <== a * b;
+        c <== t * n;
+    }"#;
+
+    fn parse(source: &str, path: &str) -> (FileDB, AstCircomProgram) {
+        let url = Url::from_file_path(Path::new(path)).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        (file, ast)
+    }
+
+    fn messages(source: &str, libs: &[&str], needs_main: bool) -> Vec<String> {
+        let (file, ast) = parse(source, "/tmp/main.circom");
+        let libs: Vec<(Url, AstCircomProgram)> = libs
+            .iter()
+            .enumerate()
+            .map(|(index, lib)| {
+                let (lib_file, lib_ast) = parse(lib, &format!("/tmp/lib{index}.circom"));
+                (lib_file.file_path, lib_ast)
+            })
+            .collect();
+
+        check_main_component(&file, &ast, &libs, needs_main)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn valid_main_component_test() {
+        let source = format!("{TEMPLATE}\ncomponent main {{public [a, b]}} = Multiplier(2);");
+        assert!(messages(&source, &[], true).is_empty());
+
+        // the template comes from an included lib
+        let source = "component main {public [a]} = Multiplier(2);";
+        assert!(messages(source, &[TEMPLATE], true).is_empty());
+    }
+
+    #[test]
+    fn duplicate_main_component_test() {
+        let main = "component main = Multiplier(2);";
+        let source = format!("{TEMPLATE}\n{main}\n{main}");
+        assert_eq!(
+            messages(&source, &[], true),
+            vec!["`component main` is already declared in this file"]
+        );
+
+        let source = format!("{TEMPLATE}\n{main}");
+        assert_eq!(
+            messages(&source, &[main], true),
+            vec!["`component main` is already declared in /tmp/lib0.circom"]
+        );
+
+        // reported once for all the libs
+        assert_eq!(
+            messages(&source, &[main, TEMPLATE, main], true),
+            vec!["`component main` is already declared in /tmp/lib0.circom, /tmp/lib2.circom"]
+        );
+
+        // two included libs, at the first include of the file
+        let source = "include \"lib0.circom\";\ninclude \"lib1.circom\";";
+        let (file, ast) = parse(source, "/tmp/main.circom");
+        let libs: Vec<(Url, AstCircomProgram)> = (0..2)
+            .map(|index| {
+                let (lib_file, lib_ast) = parse(main, &format!("/tmp/lib{index}.circom"));
+                (lib_file.file_path, lib_ast)
+            })
+            .collect();
+        let diagnostics = check_main_component(&file, &ast, &libs, false);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "`component main` is declared in several included files: /tmp/lib0.circom, /tmp/lib1.circom"
+        );
+        assert_eq!(diagnostics[0].range.start, Position::new(0, 0));
+        assert_eq!(diagnostics[0].range.end, Position::new(0, 22));
+    }
+
+    #[test]
+    fn template_of_main_component_test() {
+        assert_eq!(
+            messages("component main = Adder(1);", &[TEMPLATE], true),
+            vec!["template `Adder` is not declared"]
+        );
+
+        let source = format!("{TEMPLATE}\ncomponent main = Multiplier(1, 2);");
+        assert_eq!(
+            messages(&source, &[], true),
+            vec!["template `Multiplier` takes 1 argument but 2 arguments were supplied"]
+        );
+
+        let source = format!("{TEMPLATE}\ncomponent main = Multiplier(1, 2 * 3, f(4, 5));");
+        assert_eq!(
+            messages(&source, &[], true),
+            vec!["template `Multiplier` takes 1 argument but 3 arguments were supplied"]
+        );
+
+        let source = format!("{TEMPLATE}\ncomponent main = Multiplier(f(4, 5));");
+        assert!(messages(&source, &[], true).is_empty());
+
+        let source = format!("{TEMPLATE}\ncomponent main = Multiplier();");
+        assert_eq!(
+            messages(&source, &[], true),
+            vec!["template `Multiplier` takes 1 argument but 0 arguments were supplied"]
+        );
+    }
+
+    #[test]
+    fn public_signals_test() {
+        let source = format!("{TEMPLATE}\ncomponent main {{public [a, c, t, x]}} = Multiplier(2);");
+        assert_eq!(
+            messages(&source, &[], true),
+            vec![
+                "`c` is an output signal of `Multiplier`, only input signals can be public",
+                "`t` is an intermediate signal of `Multiplier`, only input signals can be public",
+                "`x` is not an input signal of `Multiplier`",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_main_component_test() {
+        let (file, ast) = parse(TEMPLATE, "/tmp/main.circom");
+        let diagnostics = check_main_component(&file, &ast, &[], true);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        // at the name of the template
+        assert_eq!(diagnostics[0].range.start, Position::new(1, 13));
+        assert_eq!(diagnostics[0].range.end, Position::new(1, 23));
+        // nothing to compile in an empty file
+        assert!(messages("// empty\n", &[], true).is_empty());
+
+        // a library, or the warning is not enabled
+        assert!(messages(TEMPLATE, &[], false).is_empty());
+        // main component declared in an included file
+        let main = "component main = Multiplier(2);";
+        assert!(messages(TEMPLATE, &[main], true).is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use lsp_types::{Diagnostic, Range};
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::AstCircomProgram;
+
+use super::warning;
+
+pub fn missing_pragma_diagnostic() -> Diagnostic {
+    warning(
+        Range::default(),
+        "no `pragma circom` version, the file is compiled as the latest circom version".to_string(),
+    )
+}
+
+/// a file with templates, functions... declares the circom version it is written for
+pub fn check_pragma(ast: &AstCircomProgram) -> Vec<Diagnostic> {
+    let empty = ast
+        .syntax()
+        .children()
+        .all(|child| child.kind().is_trivial());
+    if empty || ast.pragma().is_some() {
+        return Vec::new();
+    }
+    vec![missing_pragma_diagnostic()]
+}
+
+#[cfg(test)]
+mod tests {
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use super::check_pragma;
+
+    fn count(source: &str) -> usize {
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        check_pragma(&ast).len()
+    }
+
+    #[test]
+    fn check_pragma_test() {
+        assert_eq!(count("template A() {}"), 1);
+        assert_eq!(count("pragma circom 2.1.6;\ntemplate A() {}"), 0);
+        assert_eq!(count("// nothing yet\n"), 0);
+    }
+}
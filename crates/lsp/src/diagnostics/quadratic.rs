@@ -0,0 +1,290 @@
+use lsp_types::Diagnostic;
+use parser::token_kind::TokenKind::*;
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{AstCircomProgram, AstExpr};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+
+use super::degree::{degree, form, Form, Scope};
+use super::error;
+use super::signal_assignment::{
+    assign_operators, assignment_range, non_trivial_sibling, trimmed_range,
+};
+
+// the innermost subexpression which makes a constraint non quadratic
+struct Violation {
+    node: SyntaxNode,
+    message: String,
+}
+
+fn depends_on_signals(expr: &AstExpr, scope: &Scope) -> bool {
+    degree(expr, scope) != Some(0)
+}
+
+fn operator_text(node: &SyntaxNode) -> String {
+    node.children()
+        .find(|child| child.kind() == node.kind() && child.first_child().is_none())
+        .map(|operator| operator.text().to_string())
+        .unwrap_or_default()
+}
+
+fn violation(expr: &AstExpr, scope: &Scope) -> Option<Violation> {
+    let not_on_signals = |what: String, operands: Vec<Option<AstExpr>>| {
+        operands
+            .into_iter()
+            .flatten()
+            .any(|operand| depends_on_signals(&operand, scope))
+            .then(|| Violation {
+                node: expr.syntax().clone(),
+                message: format!("{what} can not be applied to signals in a constraint"),
+            })
+    };
+
+    match expr {
+        AstExpr::Atom(_) | AstExpr::ComponentCall(_) => None,
+        AstExpr::Expression(expression) => violation(&expression.expr()?, scope),
+        AstExpr::ArrayQuery(query) => {
+            let (array, indices) = query.indices();
+            for index in indices.iter().filter_map(|index| index.expr()) {
+                if let Some(violation) = violation(&index, scope) {
+                    return Some(violation);
+                }
+                if depends_on_signals(&index, scope) {
+                    return Some(Violation {
+                        node: index.syntax().clone(),
+                        message: "an array index in a constraint can not depend on signals"
+                            .to_string(),
+                    });
+                }
+            }
+            violation(&array?, scope)
+        }
+        AstExpr::Binary(binary) => {
+            let (lhs, rhs) = (binary.lhs()?, binary.rhs()?);
+            if let Some(violation) = violation(&lhs, scope).or_else(|| violation(&rhs, scope)) {
+                return Some(violation);
+            }
+
+            let operator = operator_text(binary.syntax());
+            match binary.operator() {
+                Add | Sub => None,
+                Mul => match degree(expr, scope) {
+                    Some(degree) if degree > 2 => Some(Violation {
+                        node: expr.syntax().clone(),
+                        message: format!(
+                            "constraints must be at most quadratic, this expression has degree {degree}"
+                        ),
+                    }),
+                    _ => None,
+                },
+                Div => depends_on_signals(&rhs, scope).then(|| Violation {
+                    node: expr.syntax().clone(),
+                    message: "division by a signal can not be used in a constraint".to_string(),
+                }),
+                _ => not_on_signals(format!("`{operator}`"), vec![Some(lhs), Some(rhs)]),
+            }
+        }
+        AstExpr::Prefix(prefix) => {
+            let operand = prefix.operand()?;
+            if let Some(violation) = violation(&operand, scope) {
+                return Some(violation);
+            }
+            match prefix.operator() {
+                Sub => None,
+                _ => not_on_signals(
+                    format!("`{}`", operator_text(prefix.syntax())),
+                    vec![Some(operand)],
+                ),
+            }
+        }
+        AstExpr::Postfix(postfix) => not_on_signals(
+            format!("`{}`", operator_text(postfix.syntax())),
+            vec![postfix.operand()],
+        ),
+        AstExpr::Tenary(tenary) => {
+            let condition = tenary.condition()?.expr()?;
+            let branches = [tenary.if_true(), tenary.if_false()];
+            let branches = branches
+                .into_iter()
+                .flatten()
+                .filter_map(|branch| branch.expr());
+            violation(&condition, scope)
+                .or_else(|| {
+                    not_on_signals(
+                        "a conditional expression".to_string(),
+                        vec![Some(condition)],
+                    )
+                })
+                .or_else(|| {
+                    branches
+                        .into_iter()
+                        .find_map(|branch| violation(&branch, scope))
+                })
+        }
+        AstExpr::Call(call) => not_on_signals(
+            "a function call".to_string(),
+            call.arguments().into_iter().map(Some).collect(),
+        ),
+    }
+}
+
+/**
+ * constraints (`===`, `<==`, `==>`) must be quadratic in the signals:
+ * lhs - rhs has the form A * B + C with A, B and C linear (so its degree is at most 2),
+ * and no division by a signal, `%`, `\`, bitwise,
+ * boolean or relational operators, conditional expressions
+ * or function calls on signals
+ */
+pub fn check_quadratic_constraints(file: &FileDB, ast: &AstCircomProgram) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for template in ast.template_list() {
+        let scope = Scope::new(&template);
+        let constraints = [EqualSignal, RAssignConstraintSignal, LAssignContraintSignal];
+
+        for operator in assign_operators(template.syntax(), &constraints) {
+            let sides = [false, true]
+                .map(|next| non_trivial_sibling(&operator, next).and_then(AstExpr::cast));
+
+            // a degree above 2 always comes from a multiplication, which is checked here
+            let found = sides
+                .iter()
+                .flatten()
+                .find_map(|side| violation(side, &scope));
+
+            if let Some(found) = found {
+                diagnostics.push(error(trimmed_range(file, &found.node), found.message));
+                continue;
+            }
+
+            // eg: a * b === c * d, c <== a * b + a * c
+            let [Some(lhs), Some(rhs)] =
+                sides.map(|side| side.and_then(|side| form(&side, &scope)))
+            else {
+                continue;
+            };
+            if lhs.sum(rhs) == Form::NonQuadratic {
+                diagnostics.push(error(
+                    assignment_range(file, &operator),
+                    "constraints must have the form A * B + C, with A, B and C linear in the signals"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{Position, Range, Url};
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::check_quadratic_constraints;
+
+    fn diagnostics(constraint: &str) -> Vec<(String, Range)> {
+        let source = format!(
+            "template A(n) {{\nsignal input a;\nsignal input b;\nsignal output c;\nvar v = 2;\n{constraint}\n}}"
+        );
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(&source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(&source)).unwrap();
+
+        check_quadratic_constraints(&file, &ast)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.message, diagnostic.range))
+            .collect()
+    }
+
+    fn messages(constraint: &str) -> Vec<String> {
+        diagnostics(constraint)
+            .into_iter()
+            .map(|(message, _)| message)
+            .collect()
+    }
+
+    #[test]
+    fn quadratic_constraint_test() {
+        assert!(messages("c <== a * b + v * n;").is_empty());
+        assert!(messages("c <== (a + 1) * (b - n) / 2;").is_empty());
+        assert!(messages("a * b === c;").is_empty());
+        assert!(messages("c <-- a * a * b;").is_empty());
+        assert!(messages("c <== n > 0 ? a * b : 0;").is_empty());
+    }
+
+    #[test]
+    fn non_quadratic_constraint_test() {
+        assert_eq!(
+            diagnostics("c <== 1 + a * b * a;"),
+            vec![(
+                "constraints must be at most quadratic, this expression has degree 3".to_string(),
+                Range::new(Position::new(5, 10), Position::new(5, 19))
+            )]
+        );
+        assert_eq!(
+            diagnostics("c <== a + b / a;"),
+            vec![(
+                "division by a signal can not be used in a constraint".to_string(),
+                Range::new(Position::new(5, 10), Position::new(5, 15))
+            )]
+        );
+        assert_eq!(
+            messages("c <== a % 2;"),
+            vec!["`%` can not be applied to signals in a constraint"]
+        );
+        assert_eq!(
+            messages("c <== (a >> 1) & 1;"),
+            vec!["`>>` can not be applied to signals in a constraint"]
+        );
+        assert_eq!(
+            messages("c <== a > 0 ? 1 : 0;"),
+            vec!["`>` can not be applied to signals in a constraint"]
+        );
+        assert_eq!(
+            messages("c <== a ? 1 : 0;"),
+            vec!["a conditional expression can not be applied to signals in a constraint"]
+        );
+        assert_eq!(
+            messages("c <== n > 0 ? a * a * b : 0;"),
+            vec!["constraints must be at most quadratic, this expression has degree 3"]
+        );
+        assert_eq!(
+            messages("a * b * c === 1;"),
+            vec!["constraints must be at most quadratic, this expression has degree 3"]
+        );
+    }
+
+    #[test]
+    fn quadratic_form_test() {
+        let message =
+            "constraints must have the form A * B + C, with A, B and C linear in the signals";
+        assert_eq!(
+            diagnostics("a * b === c * a;"),
+            vec![(
+                message.to_string(),
+                Range::new(Position::new(5, 0), Position::new(5, 15))
+            )]
+        );
+        assert_eq!(messages("c <== a * b + a * c;"), vec![message]);
+        assert!(messages("c * v <== (a + b) * (a - c) + 2 * a;").is_empty());
+        assert!(messages("a * b - v * c === n;").is_empty());
+
+        // the degree and form of a variable come from its values
+        assert!(messages("var t = a * b;\nc <== t + a;").is_empty());
+        assert_eq!(
+            messages("var t = a * b;\nc <== t * a;"),
+            vec!["constraints must be at most quadratic, this expression has degree 3"]
+        );
+        assert_eq!(
+            messages("var t = a * b;\nt += c * c;\nc <== t;"),
+            vec![message]
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashSet;
+
+use lsp_types::{Diagnostic, Range};
+use parser::token_kind::TokenKind::{self, *};
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{AstCircomProgram, AstExpr};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+
+use super::degree::{form, signal_name, Form, Scope};
+use super::warning;
+
+/// a signal assigned with `<--` (or `-->`) which does not appear in any constraint
+pub struct UnconstrainedAssignment {
+    pub signal: String,
+    /// the `<--` or `-->` operator
+    pub operator: SyntaxNode,
+    /// the assigned signal, eg: `out[i]` in out[i] <-- a * b
+    pub target: SyntaxNode,
+    /// the assigned value, eg: `a * b` in out[i] <-- a * b
+    pub value: SyntaxNode,
+    /// the value is at most quadratic in the signals, so `<==` can be used instead
+    pub quadratic: bool,
+}
+
+impl UnconstrainedAssignment {
+    /// the operator which also constrains the signal, eg: `<==` for `<--`
+    pub fn constraint_operator(&self) -> TokenKind {
+        match self.operator.kind() {
+            LAssignSignal => LAssignContraintSignal,
+            _ => RAssignConstraintSignal,
+        }
+    }
+}
+
+pub(super) fn non_trivial_sibling(node: &SyntaxNode, next: bool) -> Option<SyntaxNode> {
+    let mut sibling = if next {
+        node.next_sibling()
+    } else {
+        node.prev_sibling()
+    };
+    while let Some(current) = sibling {
+        if !current.kind().is_trivial() {
+            return Some(current);
+        }
+        sibling = if next {
+            current.next_sibling()
+        } else {
+            current.prev_sibling()
+        };
+    }
+    None
+}
+
+// the assign operators of statements and signal declarations, eg: `<--` in out <-- a * b;
+pub(super) fn assign_operators(root: &SyntaxNode, kinds: &[TokenKind]) -> Vec<SyntaxNode> {
+    operators_matching(root, |kind| kinds.contains(&kind))
+}
+
+pub(super) fn operators_matching(
+    root: &SyntaxNode,
+    predicate: impl Fn(TokenKind) -> bool,
+) -> Vec<SyntaxNode> {
+    root.descendants()
+        .filter(|node| predicate(node.kind()) && node.first_child().is_none())
+        .filter(|node| {
+            node.parent().is_some_and(|parent| {
+                matches!(
+                    parent.kind(),
+                    AssignStatement | SignalDecl | OutputSignalDecl
+                )
+            })
+        })
+        .collect()
+}
+
+/// the assigned and the assigned value of an operator, eg: `out` and `a * b` in a * b ==> out
+pub(super) fn assignment_sides(operator: &SyntaxNode) -> Option<(SyntaxNode, SyntaxNode)> {
+    let lhs = non_trivial_sibling(operator, false)?;
+    let rhs = non_trivial_sibling(operator, true)?;
+    match operator.kind() {
+        LAssignSignal | LAssignContraintSignal => Some((rhs, lhs)),
+        _ => Some((lhs, rhs)),
+    }
+}
+
+/// range of both sides of an operator, eg: out <== a * b
+pub(super) fn assignment_range(file: &FileDB, operator: &SyntaxNode) -> Range {
+    let start = non_trivial_sibling(operator, false).unwrap_or_else(|| operator.clone());
+    let end = non_trivial_sibling(operator, true).unwrap_or_else(|| operator.clone());
+    Range {
+        start: trimmed_range(file, &start).start,
+        end: trimmed_range(file, &end).end,
+    }
+}
+
+/**
+ * signals assigned with `<--`/`-->` which are never used in a constraint
+ * (`===`, `<==`, `==>`) of the same template.
+ * constraints do not depend on the order of statements,
+ * so a constraint before the assignment also counts
+ */
+pub fn unconstrained_assignments(ast: &AstCircomProgram) -> Vec<UnconstrainedAssignment> {
+    let mut result = Vec::new();
+
+    for template in ast.template_list() {
+        let scope = Scope::new(&template);
+
+        let mut constrained = HashSet::new();
+        let constraints = [EqualSignal, RAssignConstraintSignal, LAssignContraintSignal];
+        for operator in assign_operators(template.syntax(), &constraints) {
+            let sides = [false, true].map(|next| non_trivial_sibling(&operator, next));
+            for side in sides.into_iter().flatten() {
+                constrained.extend(side.descendants().filter_map(|node| signal_name(&node)));
+            }
+        }
+
+        let assignments = [RAssignSignal, LAssignSignal];
+        for operator in assign_operators(template.syntax(), &assignments) {
+            let Some((target, value)) = assignment_sides(&operator) else {
+                continue;
+            };
+
+            let Some(signal) = signal_name(&target) else {
+                continue;
+            };
+            if constrained.contains(&signal) {
+                continue;
+            }
+
+            let quadratic = AstExpr::cast(value.clone())
+                .and_then(|value| form(&value, &scope))
+                .is_some_and(|form| form <= Form::Quadratic);
+
+            result.push(UnconstrainedAssignment {
+                signal,
+                operator,
+                target,
+                value,
+                quadratic,
+            });
+        }
+    }
+
+    result
+}
+
+/// range of a node without its leading and trailing whitespaces and comments
+pub(crate) fn trimmed_range(file: &FileDB, node: &SyntaxNode) -> Range {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| !token.kind().is_trivial());
+
+    match tokens.next() {
+        Some(first) => {
+            let last = tokens.last().unwrap_or_else(|| first.clone());
+            Range {
+                start: file.position(first.text_range().start()),
+                end: file.position(last.text_range().end()),
+            }
+        }
+        None => file.range(node),
+    }
+}
+
+pub fn unconstrained_assignment_diagnostic(
+    file: &FileDB,
+    assignment: &UnconstrainedAssignment,
+) -> Diagnostic {
+    let operator = assignment.operator.text().to_string();
+    let hint = if assignment.quadratic {
+        format!(
+            "use {} to also constrain it",
+            assignment.constraint_operator().describe()
+        )
+    } else {
+        "add a constraint with `===`".to_string()
+    };
+
+    warning(
+        assignment_range(file, &assignment.operator),
+        format!(
+            "`{}` is assigned with `{operator}` but never constrained, {hint}",
+            assignment.signal
+        ),
+    )
+}
+
+/// `<--` assignments which are not followed by any constraint on the signal
+pub fn check_signal_assignments(file: &FileDB, ast: &AstCircomProgram) -> Vec<Diagnostic> {
+    unconstrained_assignments(ast)
+        .iter()
+        .map(|assignment| unconstrained_assignment_diagnostic(file, assignment))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::check_signal_assignments;
+
+    fn messages(body: &str) -> Vec<String> {
+        let source = format!(
+            "template A() {{\n    signal input a;\n    signal input b;\n    signal output c;\n{body}\n}}"
+        );
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(&source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(&source)).unwrap();
+
+        check_signal_assignments(&file, &ast)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn unconstrained_assignment_test() {
+        assert_eq!(
+            messages("    c <-- a * b;"),
+            vec![
+                "`c` is assigned with `<--` but never constrained, use `<==` to also constrain it"
+            ]
+        );
+        assert_eq!(
+            messages("    a / b --> c;"),
+            vec!["`c` is assigned with `-->` but never constrained, add a constraint with `===`"]
+        );
+        assert_eq!(
+            messages("    signal t <-- a * a * b;\n    c <== a;"),
+            vec!["`t` is assigned with `<--` but never constrained, add a constraint with `===`"]
+        );
+        // a * b + a * a is not of the form A * B + C
+        assert_eq!(
+            messages("    var v = a * b;\n    c <-- v + a * a;"),
+            vec!["`c` is assigned with `<--` but never constrained, add a constraint with `===`"]
+        );
+    }
+
+    #[test]
+    fn constrained_assignment_test() {
+        assert!(messages("    c <-- a / b;\n    c * b === a;").is_empty());
+        // the constraint may come first
+        assert!(messages("    c * b === a;\n    c <-- a / b;").is_empty());
+        assert!(messages("    signal t[2];\n    t[0] <-- a * b;\n    c <== t[1] + 1;").is_empty());
+        assert!(messages("    c <== a * b;").is_empty());
+    }
+}
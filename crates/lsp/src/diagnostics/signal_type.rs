@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, Location};
+use parser::token_kind::TokenKind::*;
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{
+    AstCircomProgram, AstExpression, AstForLoop, AstIfStatement, AstTemplateDef, AstWhileLoop,
+};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+
+use super::degree::{degree, signal_name, Scope, Symbol};
+use super::error;
+use super::signal_assignment::{
+    assignment_range, assignment_sides, operators_matching, trimmed_range,
+};
+
+// `=` on signals, `<==` on variables
+fn check_assign_operators(file: &FileDB, root: &SyntaxNode, scope: &Scope) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for operator in operators_matching(root, |kind| kind.is_assign_token() && kind != EqualSignal) {
+        let Some((target, _)) = assignment_sides(&operator) else {
+            continue;
+        };
+        let Some(name) = signal_name(&target) else {
+            continue;
+        };
+        let kind = operator.kind();
+        let text = operator.text().to_string();
+
+        let message = match scope.symbol(&name) {
+            Some(Symbol::Signal) if kind.is_var_assign_token() => {
+                format!("signal `{name}` can not be assigned with `{text}`, use `<==` or `<--`")
+            }
+            Some(Symbol::Variable | Symbol::Parameter) if kind.is_signal_assign_token() => {
+                format!("variable `{name}` can not be assigned with `{text}`, use `=`")
+            }
+            _ => continue,
+        };
+        diagnostics.push(error(assignment_range(file, &operator), message));
+    }
+
+    diagnostics
+}
+
+// the condition of an `if`, `for` or `while` statement, with its name
+fn block_condition(node: &SyntaxNode) -> Option<(&'static str, SyntaxNode)> {
+    match node.kind() {
+        IfStatement => Some((
+            "an `if`",
+            AstIfStatement::cast(node.clone())?
+                .condition()?
+                .syntax()
+                .clone(),
+        )),
+        ForLoop => Some((
+            "a `for`",
+            AstForLoop::cast(node.clone())?
+                .condition()?
+                .syntax()
+                .clone(),
+        )),
+        WhileLoop => Some((
+            "a `while`",
+            AstWhileLoop::cast(node.clone())?
+                .condition()?
+                .syntax()
+                .clone(),
+        )),
+        _ => None,
+    }
+}
+
+// signals declared inside an `if`/`for`/`while` whose condition is not known at compile time
+fn check_conditional_declarations(
+    file: &FileDB,
+    template: &AstTemplateDef,
+    scope: &Scope,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let declarations = template
+        .syntax()
+        .descendants()
+        .filter(|node| matches!(node.kind(), SignalDecl | InputSignalDecl | OutputSignalDecl));
+    for declaration in declarations {
+        let unknown_condition = declaration.ancestors().find_map(|ancestor| {
+            let (keyword, condition) = block_condition(&ancestor)?;
+            let expr = AstExpression::cast(condition)?.expr()?;
+            (degree(&expr, scope) != Some(0)).then_some(keyword)
+        });
+
+        if let Some(keyword) = unknown_condition {
+            diagnostics.push(error(
+                trimmed_range(file, &declaration),
+                format!(
+                    "signals can not be declared inside {keyword} whose condition depends on signals"
+                ),
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+// an assignment can be repeated in the two branches of an `if`
+fn in_exclusive_branches(first: &SyntaxNode, second: &SyntaxNode) -> bool {
+    first
+        .ancestors()
+        .filter_map(AstIfStatement::cast)
+        .any(|if_statement| {
+            let (Some(then_branch), Some(else_branch)) =
+                (if_statement.then_branch(), if_statement.else_branch())
+            else {
+                return false;
+            };
+            let contains = |branch: &SyntaxNode, node: &SyntaxNode| {
+                branch.text_range().contains_range(node.text_range())
+            };
+            let (then_branch, else_branch) = (then_branch.syntax(), else_branch.syntax());
+            (contains(then_branch, first) && contains(else_branch, second))
+                || (contains(else_branch, first) && contains(then_branch, second))
+        })
+}
+
+fn in_loop(node: &SyntaxNode) -> bool {
+    node.ancestors()
+        .any(|ancestor| matches!(ancestor.kind(), ForLoop | WhileLoop))
+}
+
+// signals which are assigned twice, eg: out <== a; out <== b;
+fn check_reassignments(file: &FileDB, template: &AstTemplateDef, scope: &Scope) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut assigned: HashMap<String, SyntaxNode> = HashMap::new();
+
+    for operator in operators_matching(template.syntax(), |kind| kind.is_signal_assign_token()) {
+        // the number of iterations of a loop is not known here
+        if in_loop(&operator) {
+            continue;
+        }
+        let Some((target, _)) = assignment_sides(&operator) else {
+            continue;
+        };
+        // variables assigned with `<==` are reported by `check_assign_operators`
+        if signal_name(&target).and_then(|name| scope.symbol(&name)) == Some(Symbol::Variable) {
+            continue;
+        }
+        let key: String = target
+            .text()
+            .to_string()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+
+        match assigned.get(&key) {
+            Some(first) if !in_exclusive_branches(first, &operator) => {
+                let mut diagnostic = error(
+                    assignment_range(file, &operator),
+                    format!("signal `{key}` is already assigned"),
+                );
+                diagnostic.related_information = Some(vec![DiagnosticRelatedInformation {
+                    location: Location::new(file.file_path.clone(), assignment_range(file, first)),
+                    message: format!("`{key}` is first assigned here"),
+                }]);
+                diagnostics.push(diagnostic);
+            }
+            Some(_) => {}
+            None => {
+                assigned.insert(key, operator);
+            }
+        }
+    }
+
+    diagnostics
+}
+
+// functions only work on variables
+fn check_function_signals(file: &FileDB, function: &SyntaxNode) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for node in function.descendants() {
+        if node.first_child().is_some() {
+            continue;
+        }
+        match node.kind() {
+            SignalKw => {
+                let declaration = node.ancestors().find(|ancestor| {
+                    matches!(
+                        ancestor.kind(),
+                        SignalDecl | InputSignalDecl | OutputSignalDecl
+                    )
+                });
+                let (range, message) = match declaration {
+                    Some(declaration) => (
+                        trimmed_range(file, &declaration),
+                        "signals can not be declared in a function",
+                    ),
+                    None => (
+                        trimmed_range(file, &node),
+                        "`signal` can not be used in a function",
+                    ),
+                };
+                diagnostics.push(error(range, message.to_string()));
+            }
+            EqualSignal => diagnostics.push(error(
+                assignment_range(file, &node),
+                "constraints can not be used in a function".to_string(),
+            )),
+            _ => {}
+        }
+    }
+
+    diagnostics
+}
+
+/**
+ * misuses of signals and variables which the compiler rejects:
+ * - a signal assigned with `=` (or `+=`, ...), a variable assigned with `<==` (or `<--`, ...)
+ * - a signal declared inside an `if`/`for`/`while` whose condition depends on signals
+ * - a signal assigned twice
+ * - signals and constraints inside a function
+ */
+pub fn check_signal_types(file: &FileDB, ast: &AstCircomProgram) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for template in ast.template_list() {
+        let scope = Scope::new(&template);
+        diagnostics.extend(check_assign_operators(file, template.syntax(), &scope));
+        diagnostics.extend(check_conditional_declarations(file, &template, &scope));
+        diagnostics.extend(check_reassignments(file, &template, &scope));
+    }
+
+    for function in ast.function_list() {
+        let scope = Scope::function(&function);
+        diagnostics.extend(check_function_signals(file, function.syntax()));
+        diagnostics.extend(check_assign_operators(file, function.syntax(), &scope));
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::check_signal_types;
+
+    fn messages(source: &str) -> Vec<String> {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+
+        check_signal_types(&file, &ast)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    fn template(body: &str) -> Vec<String> {
+        messages(&format!(
+            "template A(n) {{\n    signal input a;\n    signal output b;\n    var v;\n{body}\n}}"
+        ))
+    }
+
+    #[test]
+    fn assign_operator_test() {
+        assert!(template("    b <== a;\n    v = n + 1;\n    v += 2;").is_empty());
+        assert_eq!(
+            template("    b = a;"),
+            vec!["signal `b` can not be assigned with `=`, use `<==` or `<--`"]
+        );
+        assert_eq!(
+            template("    v <== a;\n    a ==> v;"),
+            vec![
+                "variable `v` can not be assigned with `<==`, use `=`",
+                "variable `v` can not be assigned with `==>`, use `=`",
+            ]
+        );
+        assert_eq!(
+            template("    signal t = a;"),
+            vec!["signal `t` can not be assigned with `=`, use `<==` or `<--`"]
+        );
+    }
+
+    #[test]
+    fn conditional_declaration_test() {
+        assert!(template("    if (n > 0) {\n        signal t;\n    }").is_empty());
+        assert!(template("    for (var i = 0; i < n; i++) {\n        signal t;\n    }").is_empty());
+        assert_eq!(
+            template("    if (a > 0) {\n        signal t;\n    }"),
+            vec!["signals can not be declared inside an `if` whose condition depends on signals"]
+        );
+        assert_eq!(
+            template("    while (v < a) {\n        signal t;\n        v++;\n    }"),
+            vec!["signals can not be declared inside a `while` whose condition depends on signals"]
+        );
+    }
+
+    #[test]
+    fn reassignment_test() {
+        assert_eq!(
+            template("    b <== a;\n    b <-- a * a;"),
+            vec!["signal `b` is already assigned"]
+        );
+        assert!(template("    if (n > 0) b <== a; else b <== 2 * a;").is_empty());
+        assert!(template("    signal c[2];\n    c[0] <== a;\n    c[1] <== a;").is_empty());
+        assert!(template("    for (var i = 0; i < n; i++) {\n        b <== a;\n    }").is_empty());
+    }
+
+    #[test]
+    fn function_test() {
+        let source = r#"
+function f(x) {
+    signal y;
+    var z;
+    z <== x;
+    z === x;
+    return z;
+}"#;
+        assert_eq!(
+            messages(source),
+            vec![
+                "signals can not be declared in a function",
+                "constraints can not be used in a function",
+                "variable `z` can not be assigned with `<==`, use `=`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,446 @@
+use std::collections::{HashMap, HashSet};
+
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, Location};
+use parser::token_kind::TokenKind::*;
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{
+    AstCircomProgram, AstComponentDecl, AstExpr, AstIdentifier, AstTemplateDef,
+};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::{FileDB, SemanticData, TokenId};
+
+use super::degree::signal_name;
+use super::signal_assignment::{
+    assign_operators, non_trivial_sibling, trimmed_range, unconstrained_assignments,
+};
+use super::warning;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignalKind {
+    Input,
+    Output,
+    Intermediate,
+}
+
+/**
+ * how the signals of a template are used
+ * names are the ones of `signal_name`, eg: `out` for out[i], `c.in` for c[i].in
+ */
+#[derive(Default)]
+struct SignalUsage {
+    /// signal --> the `<==`, `<--`, ... operators which assign it
+    writes: HashMap<String, Vec<SyntaxNode>>,
+    /// signals which appear in the value of an assignment or in a constraint
+    reads: HashSet<String>,
+    /// signals which appear in a constraint (`===`, `<==`, `==>`)
+    constrained: HashSet<String>,
+}
+
+fn signal_names(node: &SyntaxNode) -> impl Iterator<Item = String> {
+    node.descendants().filter_map(|node| signal_name(&node))
+}
+
+fn signal_usage(template: &AstTemplateDef) -> SignalUsage {
+    let mut usage = SignalUsage::default();
+
+    let operators = [
+        Assign,
+        EqualSignal,
+        RAssignSignal,
+        RAssignConstraintSignal,
+        LAssignSignal,
+        LAssignContraintSignal,
+    ];
+    for operator in assign_operators(template.syntax(), &operators) {
+        let (Some(lhs), Some(rhs)) = (
+            non_trivial_sibling(&operator, false),
+            non_trivial_sibling(&operator, true),
+        ) else {
+            continue;
+        };
+
+        match operator.kind() {
+            EqualSignal => {
+                for side in [lhs, rhs] {
+                    usage.reads.extend(signal_names(&side));
+                    usage.constrained.extend(signal_names(&side));
+                }
+            }
+            kind => {
+                let (target, value) = match kind {
+                    LAssignSignal | LAssignContraintSignal => (rhs, lhs),
+                    _ => (lhs, rhs),
+                };
+                usage.reads.extend(signal_names(&value));
+
+                if matches!(kind, RAssignConstraintSignal | LAssignContraintSignal) {
+                    usage.constrained.extend(signal_names(&target));
+                    usage.constrained.extend(signal_names(&value));
+                }
+
+                if let Some(name) = signal_name(&target) {
+                    usage.writes.entry(name).or_default().push(operator);
+                }
+            }
+        }
+    }
+
+    usage
+}
+
+// name of every signal declared in a template, with its kind
+fn declared_signals(template: &AstTemplateDef) -> Vec<(AstIdentifier, SignalKind)> {
+    let mut result = Vec::new();
+    for declaration in template.syntax().descendants() {
+        let kind = match declaration.kind() {
+            InputSignalDecl => SignalKind::Input,
+            OutputSignalDecl => SignalKind::Output,
+            SignalDecl => SignalKind::Intermediate,
+            _ => continue,
+        };
+        for identifier in declaration.children() {
+            if identifier.kind() != ComplexIdentifier {
+                continue;
+            }
+            if let Some(name) = identifier.first_child().and_then(AstIdentifier::cast) {
+                result.push((name, kind));
+            }
+        }
+    }
+    result
+}
+
+// component --> name of its template,
+// eg: component c = T(); or component c[N]; c[i] = T();
+fn component_templates(template: &AstTemplateDef) -> Vec<(AstIdentifier, String)> {
+    let mut declarations = HashMap::new();
+    let mut result = Vec::new();
+
+    for declaration in template.syntax().descendants() {
+        let Some(declaration) = AstComponentDecl::cast(declaration) else {
+            continue;
+        };
+        let Some(name) = declaration.component_identifier().and_then(|id| id.name()) else {
+            continue;
+        };
+        match declaration.template() {
+            Some(template_name) => result.push((name, template_name.syntax().text().to_string())),
+            None => {
+                declarations.insert(name.syntax().text().to_string(), name);
+            }
+        }
+    }
+
+    for operator in assign_operators(template.syntax(), &[Assign]) {
+        let (Some(target), Some(value)) = (
+            non_trivial_sibling(&operator, false),
+            non_trivial_sibling(&operator, true),
+        ) else {
+            continue;
+        };
+        let Some(name) = signal_name(&target).and_then(|name| declarations.remove(&name)) else {
+            continue;
+        };
+        let callee = AstExpr::cast(value)
+            .and_then(|value| match value {
+                AstExpr::Expression(expression) => expression.expr(),
+                value => Some(value),
+            })
+            .and_then(|value| match value {
+                AstExpr::Call(call) => call.callee(),
+                _ => None,
+            });
+        if let Some(callee) = callee {
+            result.push((name, callee.syntax().text().to_string().trim().to_string()));
+        }
+    }
+
+    result
+}
+
+fn find_template<'a>(
+    programs: &'a [(&'a FileDB, &'a AstCircomProgram)],
+    name: &str,
+) -> Option<(&'a FileDB, AstTemplateDef)> {
+    programs.iter().find_map(|(file, program)| {
+        program
+            .template_list()
+            .into_iter()
+            .find(|template| {
+                template
+                    .name()
+                    .is_some_and(|template_name| template_name.syntax().text() == name)
+            })
+            .map(|template| (*file, template))
+    })
+}
+
+fn related(
+    file: &FileDB,
+    range: lsp_types::Range,
+    message: String,
+) -> DiagnosticRelatedInformation {
+    DiagnosticRelatedInformation {
+        location: Location::new(file.file_path.clone(), range),
+        message,
+    }
+}
+
+/**
+ * underconstrained signals of every template:
+ * - output signals which are never assigned
+ * - input signals which are not used in any constraint
+ * - intermediate signals which are assigned but never constrained
+ *   (except the ones assigned with `<--`, reported by `check_signal_assignments`)
+ * - inputs of sub-components which are never assigned
+ */
+pub fn check_signal_usage(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    semantic: &SemanticData,
+    libs: &[(FileDB, AstCircomProgram)],
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let programs: Vec<(&FileDB, &AstCircomProgram)> = std::iter::once((file, ast))
+        .chain(libs.iter().map(|(lib_file, lib)| (lib_file, lib)))
+        .collect();
+
+    let assignments = unconstrained_assignments(ast);
+
+    for template in ast.template_list() {
+        let usage = signal_usage(&template);
+        let assigned_unconstrained: HashSet<&str> = assignments
+            .iter()
+            .filter(|assignment| {
+                assignment
+                    .operator
+                    .ancestors()
+                    .any(|node| &node == template.syntax())
+            })
+            .map(|assignment| assignment.signal.as_str())
+            .collect();
+        let template_data = semantic
+            .template_data_semantic
+            .get(&template.syntax().token_id());
+
+        for (name, kind) in declared_signals(&template) {
+            let signal = name.syntax().text().to_string();
+
+            let message = match kind {
+                SignalKind::Output if !usage.writes.contains_key(&signal) => {
+                    format!("output signal `{signal}` is never assigned")
+                }
+                SignalKind::Input if !usage.reads.contains(&signal) => {
+                    format!("input signal `{signal}` is never used")
+                }
+                SignalKind::Input if !usage.constrained.contains(&signal) => {
+                    format!("input signal `{signal}` is not used in any constraint")
+                }
+                SignalKind::Intermediate
+                    if usage.writes.contains_key(&signal)
+                        && !usage.constrained.contains(&signal)
+                        && !assigned_unconstrained.contains(signal.as_str()) =>
+                {
+                    format!("intermediate signal `{signal}` is assigned but never constrained")
+                }
+                _ => continue,
+            };
+
+            let mut diagnostic = warning(trimmed_range(file, name.syntax()), message);
+
+            let declarations = template_data
+                .and_then(|data| data.signal.0.get(&name.syntax().token_id()))
+                .cloned()
+                .unwrap_or_default();
+            let mut related_information: Vec<DiagnosticRelatedInformation> = declarations
+                .into_iter()
+                .map(|range| related(file, range, format!("`{signal}` is declared here")))
+                .collect();
+            for operator in usage.writes.get(&signal).into_iter().flatten() {
+                let range = trimmed_range(file, &operator.parent().unwrap());
+                related_information.push(related(
+                    file,
+                    range,
+                    format!("`{signal}` is assigned here"),
+                ));
+            }
+            diagnostic.related_information = Some(related_information);
+
+            diagnostics.push(diagnostic);
+        }
+
+        for (component, template_name) in component_templates(&template) {
+            let Some((template_file, sub_template)) = find_template(&programs, &template_name)
+            else {
+                continue;
+            };
+            let component_name = component.syntax().text().to_string();
+
+            for (input, kind) in declared_signals(&sub_template) {
+                let input_name = input.syntax().text().to_string();
+                if kind != SignalKind::Input
+                    || usage
+                        .writes
+                        .contains_key(&format!("{component_name}.{input_name}"))
+                {
+                    continue;
+                }
+
+                let mut diagnostic = warning(
+                    trimmed_range(file, component.syntax()),
+                    format!(
+                        "input `{input_name}` of component `{component_name}` (`{template_name}`) is never assigned"
+                    ),
+                );
+                let declaration = input
+                    .syntax()
+                    .ancestors()
+                    .find(|node| node.kind() == InputSignalDecl)
+                    .unwrap_or_else(|| input.syntax().clone());
+                diagnostic.related_information = Some(vec![related(
+                    template_file,
+                    trimmed_range(template_file, &declaration),
+                    format!("`{input_name}` is declared here"),
+                )]);
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::{FileDB, SemanticDB};
+    use crate::diagnostics::signal_assignment::check_signal_assignments;
+
+    use super::check_signal_usage;
+
+    const LIB: &str = r#"
+    template Square() {
+        signal input in;
+        signal output out;
+        out <== in * in;
+    }"#;
+
+    fn parse(source: &str, path: &str) -> (FileDB, AstCircomProgram) {
+        let url = Url::from_file_path(Path::new(path)).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        (file, ast)
+    }
+
+    fn messages(source: &str) -> Vec<String> {
+        let (file, ast) = parse(source, "/tmp/main.circom");
+        let mut db = SemanticDB::new();
+        db.circom_program_semantic(&file, &ast);
+        let libs = vec![parse(LIB, "/tmp/lib.circom")];
+
+        let semantic = db.semantic.get(&file.file_id).unwrap();
+        check_signal_usage(&file, &ast, semantic, &libs)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn signal_usage_test() {
+        let source = r#"
+    template A() {
+        signal input a;
+        signal input b;
+        signal input unused;
+        signal t;
+        signal u;
+        signal output c;
+        signal output d;
+        t <== a * a;
+        u <-- t * b;
+        c <== t + 1;
+    }"#;
+        assert_eq!(
+            messages(source),
+            vec![
+                "input signal `b` is not used in any constraint",
+                "input signal `unused` is never used",
+                "output signal `d` is never assigned",
+            ]
+        );
+    }
+
+    #[test]
+    fn unconstrained_assignment_test() {
+        let source = r#"
+    template A() {
+        signal input a;
+        signal u;
+        signal output c;
+        u <-- a * a;
+        c <== a;
+    }"#;
+        let (file, ast) = parse(source, "/tmp/main.circom");
+        let mut db = SemanticDB::new();
+        db.circom_program_semantic(&file, &ast);
+        let semantic = db.semantic.get(&file.file_id).unwrap();
+
+        // only the warning of `<--`, which has a quick fix
+        let mut diagnostics = check_signal_assignments(&file, &ast);
+        diagnostics.extend(check_signal_usage(&file, &ast, semantic, &[]));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "`u` is assigned with `<--` but never constrained, use `<==` to also constrain it"
+        );
+    }
+
+    #[test]
+    fn component_inputs_test() {
+        let source = r#"
+    template A() {
+        signal input a;
+        signal output b;
+        component s = Square();
+        component t[2];
+        t[0] = Square();
+        s.in <== a;
+        b <== s.out;
+    }"#;
+        assert_eq!(
+            messages(source),
+            vec!["input `in` of component `t` (`Square`) is never assigned"]
+        );
+
+        let (file, ast) = parse(source, "/tmp/main.circom");
+        let mut db = SemanticDB::new();
+        db.circom_program_semantic(&file, &ast);
+        let libs = vec![parse(LIB, "/tmp/lib.circom")];
+        let diagnostics =
+            check_signal_usage(&file, &ast, db.semantic.get(&file.file_id).unwrap(), &libs);
+        let related = diagnostics[0].related_information.as_ref().unwrap();
+        assert_eq!(related[0].location.uri.path(), "/tmp/lib.circom");
+        assert_eq!(related[0].location.range.start.line, 2);
+    }
+
+    #[test]
+    fn fully_constrained_test() {
+        let source = r#"
+    template IsZero() {
+        signal input in;
+        signal output out;
+        signal inv;
+        inv <-- in != 0 ? 1 / in : 0;
+        out <== -in * inv + 1;
+        in * out === 0;
+    }"#;
+        assert!(messages(source).is_empty());
+    }
+}
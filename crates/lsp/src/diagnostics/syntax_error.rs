@@ -0,0 +1,101 @@
+use lsp_types::{Diagnostic, Range};
+use parser::error::ParseError;
+use parser::token_kind::TokenKind::*;
+use rowan::{ast::AstNode, TextSize};
+use syntax::abstract_syntax_tree::AstCircomProgram;
+
+use crate::database::FileDB;
+
+use super::error;
+
+pub fn syntax_error_diagnostic(file: &FileDB, parse_error: &ParseError) -> Diagnostic {
+    let range = Range {
+        start: file.position(TextSize::from(parse_error.range.start as u32)),
+        end: file.position(TextSize::from(parse_error.range.end as u32)),
+    };
+    error(range, parse_error.message.clone())
+}
+
+/**
+ * where a `;` reported as missing goes: after the last token before the error
+ * eg: signal output c
+ *     }
+ * --> after `c`, not before `}`
+ */
+pub fn missing_semicolon(ast: &AstCircomProgram, parse_error: &ParseError) -> Option<TextSize> {
+    if parse_error.expected != [Semicolon] {
+        return None;
+    }
+
+    let offset = TextSize::from(parse_error.range.start as u32);
+    let mut token = ast.syntax().token_at_offset(offset).left_biased()?;
+    while token.kind().is_trivial() {
+        token = token.prev_token()?;
+    }
+    Some(token.text_range().end())
+}
+
+/**
+ * errors of the parser, eg: expected `;` after statement, found `}`
+ * (the syntax tree does not keep them, they are stored when the file is parsed)
+ */
+pub fn check_syntax(file: &FileDB, parse_errors: &[ParseError]) -> Vec<Diagnostic> {
+    parse_errors
+        .iter()
+        .map(|parse_error| syntax_error_diagnostic(file, parse_error))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{Position, Url};
+    use rowan::{ast::AstNode, TextSize};
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::{check_syntax, missing_semicolon};
+
+    const SOURCE: &str = r#"template A() {
+    signal input a;
+    signal output c // out
+}"#;
+
+    #[test]
+    fn check_syntax_test() {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(SOURCE, url);
+        let (_, errors) = SyntaxTreeBuilder::syntax_tree_with_errors(SOURCE);
+
+        let diagnostics = check_syntax(&file, &errors);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "expected `;` after declaration, found `}`"
+        );
+        assert_eq!(diagnostics[0].range.start, Position::new(3, 0));
+    }
+
+    #[test]
+    fn missing_semicolon_test() {
+        let (syntax, errors) = SyntaxTreeBuilder::syntax_tree_with_errors(SOURCE);
+        let ast = AstCircomProgram::cast(syntax).unwrap();
+
+        // after `c`, before the comment
+        let offset = SOURCE.find("c //").unwrap() + 1;
+        assert_eq!(
+            missing_semicolon(&ast, &errors[0]),
+            Some(TextSize::from(offset as u32))
+        );
+
+        let source = "template A( {}";
+        let (syntax, errors) = SyntaxTreeBuilder::syntax_tree_with_errors(source);
+        let ast = AstCircomProgram::cast(syntax).unwrap();
+        assert!(!errors.is_empty());
+        assert!(errors
+            .iter()
+            .all(|parse_error| missing_semicolon(&ast, parse_error).is_none()));
+    }
+}
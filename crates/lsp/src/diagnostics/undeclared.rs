@@ -0,0 +1,359 @@
+use lsp_types::{Diagnostic, Url};
+use parser::token_kind::TokenKind::*;
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{AstCircomProgram, AstExpressionAtom, AstTemplateDef};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+use crate::handler::code_lens::used_name;
+
+use super::degree::{signal_name, Scope};
+use super::error;
+use super::signal_assignment::{assign_operators, non_trivial_sibling, trimmed_range};
+
+/// names of the templates of some programs, and of their functions with `functions`
+pub fn callable_names(programs: &[&AstCircomProgram], functions: bool) -> Vec<String> {
+    let mut names = Vec::new();
+    for program in programs {
+        for template in program.template_list() {
+            names.extend(template.name().map(|name| name.syntax().text().to_string()));
+        }
+        if functions {
+            for function in program.function_list() {
+                names.extend(
+                    function
+                        .function_name()
+                        .map(|name| name.syntax().text().to_string()),
+                );
+            }
+        }
+    }
+    names
+}
+
+/// only a template can be used there, eg: `T` in component c = T(1);
+pub fn expects_template(used: &SyntaxNode) -> bool {
+    used.parent()
+        .is_some_and(|parent| matches!(parent.kind(), ComponentDecl | MainComponent))
+}
+
+/**
+ * templates and functions used in a file but declared neither in it nor in its libs,
+ * eg: the TemplateName `T` in component c = T(1); or the Identifier `f` in f(x)
+ */
+pub fn undeclared_callables(
+    ast: &AstCircomProgram,
+    libs: &[(Url, AstCircomProgram)],
+) -> Vec<SyntaxNode> {
+    let programs: Vec<&AstCircomProgram> = std::iter::once(ast)
+        .chain(libs.iter().map(|(_, lib)| lib))
+        .collect();
+    let declared = callable_names(&programs, true);
+
+    ast.syntax()
+        .descendants()
+        .filter_map(|node| used_name(&node))
+        .filter(|used| !declared.contains(&used.text().to_string()))
+        .collect()
+}
+
+pub fn undeclared_callable_diagnostic(file: &FileDB, used: &SyntaxNode) -> Diagnostic {
+    let name = used.text();
+    let message = if expects_template(used) {
+        format!("template `{name}` is not declared")
+    } else {
+        format!("template or function `{name}` is not declared")
+    };
+    error(file.range(used), message)
+}
+
+/// how an undeclared name is declared by its quick fix, guessed from its uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclarationKind {
+    /// assigned with `<==`, `<--`...
+    Signal,
+    /// only read in constraints and signal assignments
+    InputSignal,
+    Variable,
+}
+
+impl DeclarationKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            DeclarationKind::Signal => "signal",
+            DeclarationKind::InputSignal => "signal input",
+            DeclarationKind::Variable => "var",
+        }
+    }
+}
+
+/// a name used in a template which is not one of its signals, variables, components or parameters
+#[derive(Debug)]
+pub struct UndeclaredName {
+    pub name: String,
+    /// the Identifier nodes of its uses, in the order of the source
+    pub uses: Vec<SyntaxNode>,
+    pub kind: DeclarationKind,
+    /// used with an index, eg: out[i], its dimensions are unknown
+    pub indexed: bool,
+    /// the statement of the template body which contains the first use
+    pub statement: Option<SyntaxNode>,
+}
+
+// `f` in f(x), `T` in T(1)(a)
+fn is_callee(atom: &SyntaxNode) -> bool {
+    atom.parent().is_some_and(|parent| parent.kind() == Call)
+        && non_trivial_sibling(atom, false).is_none()
+}
+
+// `a` in a[i]
+fn is_indexed(atom: &SyntaxNode) -> bool {
+    atom.parent()
+        .is_some_and(|parent| parent.kind() == ArrayQuery)
+        && non_trivial_sibling(atom, false).is_none()
+}
+
+fn declaration_kind(template: &AstTemplateDef, name: &str) -> DeclarationKind {
+    let is_name = |node: &SyntaxNode| signal_name(node).is_some_and(|signal| signal == name);
+    let mentions = |node: &SyntaxNode| {
+        node.descendants()
+            .any(|descendant| descendant.kind() == ExpressionAtom && is_name(&descendant))
+    };
+
+    let operators = [
+        Assign,
+        EqualSignal,
+        RAssignSignal,
+        RAssignConstraintSignal,
+        LAssignSignal,
+        LAssignContraintSignal,
+    ];
+    let (mut assigned, mut constrained) = (false, false);
+    for operator in assign_operators(template.syntax(), &operators) {
+        let (Some(lhs), Some(rhs)) = (
+            non_trivial_sibling(&operator, false),
+            non_trivial_sibling(&operator, true),
+        ) else {
+            continue;
+        };
+
+        match operator.kind() {
+            Assign => assigned |= is_name(&lhs),
+            EqualSignal => constrained |= mentions(&lhs) || mentions(&rhs),
+            kind => {
+                let (target, value) = match kind {
+                    LAssignSignal | LAssignContraintSignal => (rhs, lhs),
+                    _ => (lhs, rhs),
+                };
+                if is_name(&target) {
+                    return DeclarationKind::Signal;
+                }
+                constrained |= mentions(&value);
+            }
+        }
+    }
+
+    if constrained && !assigned {
+        DeclarationKind::InputSignal
+    } else {
+        DeclarationKind::Variable
+    }
+}
+
+/// names used in a template without a declaration, templates and functions aside
+pub fn undeclared_names(template: &AstTemplateDef) -> Vec<UndeclaredName> {
+    let scope = Scope::new(template);
+    let body = template.statements().map(|body| body.syntax().clone());
+    let mut result: Vec<UndeclaredName> = Vec::new();
+
+    for atom in template.syntax().descendants() {
+        let Some(identifier) = AstExpressionAtom::cast(atom.clone())
+            .and_then(|atom| atom.identifier())
+            .map(|identifier| identifier.syntax().clone())
+        else {
+            continue;
+        };
+        if is_callee(&atom) || atom.ancestors().any(|ancestor| ancestor.kind() == Error) {
+            continue;
+        }
+        let name = identifier.text().to_string();
+        if scope.symbol(&name).is_some() {
+            continue;
+        }
+
+        let indexed = is_indexed(&atom);
+        match result.iter_mut().find(|undeclared| undeclared.name == name) {
+            Some(undeclared) => {
+                undeclared.uses.push(identifier);
+                undeclared.indexed |= indexed;
+            }
+            None => {
+                let statement = atom.ancestors().find(|ancestor| ancestor.parent() == body);
+                result.push(UndeclaredName {
+                    kind: declaration_kind(template, &name),
+                    name,
+                    uses: vec![identifier],
+                    indexed,
+                    statement,
+                });
+            }
+        }
+    }
+
+    result
+}
+
+pub fn undeclared_name_diagnostic(file: &FileDB, identifier: &SyntaxNode) -> Diagnostic {
+    error(
+        trimmed_range(file, identifier),
+        format!("`{}` is not declared", identifier.text()),
+    )
+}
+
+/**
+ * names which are not declared
+ * - signals and variables used in a template
+ * - templates and functions used in the file, only checked with `libs_loaded`
+ *   (all the files included, directly or not, are known)
+ *
+ * the template of `component main` is checked by `check_main_component`
+ */
+pub fn check_undeclared(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    libs: &[(Url, AstCircomProgram)],
+    libs_loaded: bool,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for template in ast.template_list() {
+        for undeclared in undeclared_names(&template) {
+            for identifier in &undeclared.uses {
+                diagnostics.push(undeclared_name_diagnostic(file, identifier));
+            }
+        }
+    }
+
+    if libs_loaded {
+        for used in undeclared_callables(ast, libs) {
+            if used
+                .parent()
+                .is_some_and(|parent| parent.kind() == MainComponent)
+            {
+                continue;
+            }
+            diagnostics.push(undeclared_callable_diagnostic(file, &used));
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::{check_undeclared, undeclared_names, DeclarationKind};
+
+    const LIB: &str = r#"
+    template Num2Bits(n) {
+        signal input in;
+        signal output out[n];
+    }
+
+    function nbits(a) {
+        return a;
+    }"#;
+
+    fn ast(source: &str) -> AstCircomProgram {
+        AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap()
+    }
+
+    fn messages(source: &str, libs_loaded: bool) -> Vec<String> {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(source, url);
+        let lib_url = Url::from_file_path(Path::new("/tmp/lib.circom")).unwrap();
+        let libs = vec![(lib_url, ast(LIB))];
+
+        check_undeclared(&file, &ast(source), &libs, libs_loaded)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn declared_test() {
+        let source = r#"template A(n) {
+    signal input a;
+    signal output b[n];
+    var k = nbits(n);
+    component c = Num2Bits(n);
+    c.in <== a;
+    for (var i = 0; i < n; i++) {
+        b[i] <== c.out[i] * k;
+    }
+    signal d <== Num2Bits(1)(a);
+}
+
+component main = Unknown();"#;
+        assert!(messages(source, true).is_empty());
+    }
+
+    #[test]
+    fn undeclared_test() {
+        let source = r#"template A() {
+    signal input a;
+    x <== a * y;
+    component c = Num2Bit(2);
+    k = g(a) + k;
+}"#;
+        assert_eq!(
+            messages(source, true),
+            vec![
+                "`x` is not declared",
+                "`y` is not declared",
+                "`k` is not declared",
+                "`k` is not declared",
+                "template `Num2Bit` is not declared",
+                "template or function `g` is not declared",
+            ]
+        );
+        // the libs of the file may declare them
+        assert_eq!(messages(source, false).len(), 4);
+    }
+
+    #[test]
+    fn declaration_kind_test() {
+        let source = r#"template A() {
+    signal input a;
+    x <== a * y;
+    a === z;
+    k = 1;
+    w = out[0] + 1;
+    log(t);
+}"#;
+        let template = ast(source).template_list().remove(0);
+        let kinds: Vec<(String, DeclarationKind, bool)> = undeclared_names(&template)
+            .into_iter()
+            .map(|undeclared| (undeclared.name, undeclared.kind, undeclared.indexed))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("x".to_string(), DeclarationKind::Signal, false),
+                ("y".to_string(), DeclarationKind::InputSignal, false),
+                ("z".to_string(), DeclarationKind::InputSignal, false),
+                ("k".to_string(), DeclarationKind::Variable, false),
+                ("w".to_string(), DeclarationKind::Variable, false),
+                ("out".to_string(), DeclarationKind::Variable, true),
+                ("t".to_string(), DeclarationKind::Variable, false),
+            ]
+        );
+    }
+}
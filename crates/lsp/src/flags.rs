@@ -0,0 +1,161 @@
+#![allow(unreachable_pub)]
+
+use std::path::PathBuf;
+
+use crate::check::Severity;
+
+xflags::xflags! {
+    src "./src/flags.rs"
+
+    /// Circom language server, run without a subcommand to start the server.
+    cmd ccls {
+        /// Start the language server on stdin and stdout.
+        default cmd server {}
+
+        /// Estimate the number of constraints of the templates of a file.
+        cmd constraints {
+            /// Circom file, its includes are loaded too.
+            required path: PathBuf
+        }
+
+        /// Print the include graph and the template instantiation graph of a circuit.
+        cmd graph {
+            /// Entry Circom file, its includes are loaded too.
+            required path: PathBuf
+            /// Output format: `dot` (Graphviz, the default) or `json`.
+            optional --format format: GraphFormat
+        }
+
+        /// Report the diagnostics of Circom files, fails if one of them is severe enough.
+        cmd check {
+            /// Circom files, or directories searched recursively for `.circom` files.
+            repeated paths: PathBuf
+            /// Output format: `human` (the default), `json` or `sarif`.
+            optional --format format: CheckFormat
+            /// Least severe diagnostics reported: `error`, `warning`, `information` or `hint` (the default).
+            optional --severity severity: Severity
+            /// Least severe diagnostics which make the check fail, `error` by default.
+            optional --fail-on fail_on: Severity
+            /// Warn about entry files without `component main`.
+            optional --warn-missing-main
+        }
+
+        /// Format Circom files in place.
+        cmd fmt {
+            /// Circom files, or directories searched recursively for `.circom` files.
+            repeated paths: PathBuf
+            /// Only list the files which are not formatted, fails if there is one.
+            optional --check
+        }
+    }
+}
+// generated start
+// The following code is generated by `xflags` macro.
+// Run `env UPDATE_XFLAGS=1 cargo build` to regenerate.
+#[derive(Debug)]
+pub struct Ccls {
+    pub subcommand: CclsCmd,
+}
+
+#[derive(Debug)]
+pub enum CclsCmd {
+    Server(Server),
+    Constraints(Constraints),
+    Graph(Graph),
+    Check(Check),
+    Fmt(Fmt),
+}
+
+#[derive(Debug)]
+pub struct Server;
+
+#[derive(Debug)]
+pub struct Constraints {
+    pub path: PathBuf,
+}
+
+#[derive(Debug)]
+pub struct Graph {
+    pub path: PathBuf,
+
+    pub format: Option<GraphFormat>,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub paths: Vec<PathBuf>,
+
+    pub format: Option<CheckFormat>,
+    pub severity: Option<Severity>,
+    pub fail_on: Option<Severity>,
+    pub warn_missing_main: bool,
+}
+
+#[derive(Debug)]
+pub struct Fmt {
+    pub paths: Vec<PathBuf>,
+
+    pub check: bool,
+}
+
+impl Ccls {
+    #[allow(dead_code)]
+    pub fn from_env_or_exit() -> Self {
+        Self::from_env_or_exit_()
+    }
+
+    #[allow(dead_code)]
+    pub fn from_env() -> xflags::Result<Self> {
+        Self::from_env_()
+    }
+
+    #[allow(dead_code)]
+    pub fn from_vec(args: Vec<std::ffi::OsString>) -> xflags::Result<Self> {
+        Self::from_vec_(args)
+    }
+}
+// generated end
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    Json,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown graph format `{s}`, expected `dot` or `json`"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CheckFormat {
+    #[default]
+    Human,
+    Json,
+    Sarif,
+}
+
+impl std::str::FromStr for CheckFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            _ => Err(format!(
+                "unknown check format `{s}`, expected `human`, `json` or `sarif`"
+            )),
+        }
+    }
+}
@@ -1,29 +1,80 @@
-use std::{fs, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 use crate::{
     database::{FileDB, SemanticDB},
+    diagnostics::{
+        arity::check_arity,
+        dimension::check_dimensions,
+        main_component::check_main_component,
+        pragma::check_pragma,
+        quadratic::check_quadratic_constraints,
+        signal_assignment::check_signal_assignments,
+        signal_type::check_signal_types,
+        signal_usage::check_signal_usage,
+        syntax_error::check_syntax,
+        undeclared::{callable_names, check_undeclared},
+    },
     handler::goto_definition::lookup_node_wrap_token,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dashmap::DashMap;
-use lsp_server::{RequestId, Response};
+use lsp_server::{Notification, RequestId, Response};
 use lsp_types::{
-    DidChangeTextDocumentParams, DidOpenTextDocumentParams, GotoDefinitionParams,
-    GotoDefinitionResponse, Location, Url,
+    notification::{Notification as _, PublishDiagnostics},
+    CallHierarchyIncomingCallsParams, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+    CodeActionParams, CodeActionResponse, CodeLens, CodeLensParams, Diagnostic,
+    DidChangeTextDocumentParams, DidOpenTextDocumentParams, DocumentFormattingParams,
+    DocumentOnTypeFormattingParams, DocumentRangeFormattingParams, FormattingOptions,
+    GotoDefinitionParams, GotoDefinitionResponse, HoverParams, InlayHintParams, Location,
+    PublishDiagnosticsParams, Range, TextEdit, Url,
 };
 
+use parser::error::ParseError;
 use parser::token_kind::TokenKind;
 use rowan::ast::AstNode;
+use serde::Deserialize;
 use syntax::abstract_syntax_tree::AstCircomProgram;
+use syntax::const_eval::ConstEvaluator;
+use syntax::field::Prime;
+use syntax::reparsing::reparse;
 use syntax::syntax::SyntaxTreeBuilder;
 use syntax::syntax_node::SyntaxToken;
 
-use crate::handler::goto_definition::{lookup_definition, lookup_token_at_postion};
+use crate::handler::call_hierarchy::{
+    incoming_calls, item_definition, outgoing_calls, prepare_call_hierarchy,
+};
+use crate::handler::code_action::code_actions;
+use crate::handler::code_lens::{
+    code_lenses, resolve_code_lens, usages, used_name, LensData, LensKind, Usage,
+};
+use crate::handler::constraints::{
+    constraint_lenses, named_template_estimate, resolve_constraint_lens, template_estimates,
+    TemplateEstimate,
+};
+use crate::handler::formatting::formatting;
+use crate::handler::goto_definition::{
+    lookup_definition, lookup_public_signal, lookup_token_at_postion,
+};
+use crate::handler::hover::hover;
+use crate::handler::inlay_hint::inlay_hints;
+use crate::handler::instances::{template_instances, TemplateInstance};
+use crate::handler::on_type_formatting::on_type_formatting;
 
 #[derive(Debug)]
 pub struct TextDocument {
     text: String,
-    uri: Url,
+    pub uri: Url,
+}
+
+impl TextDocument {
+    pub fn new(uri: Url, text: String) -> Self {
+        Self { text, uri }
+    }
 }
 
 impl From<DidOpenTextDocumentParams> for TextDocument {
@@ -44,6 +95,33 @@ impl From<DidChangeTextDocumentParams> for TextDocument {
     }
 }
 
+/// settings of the client, sent as initialization options
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    /// align the `<==` of consecutive constraint assignments when typing `;`
+    pub align_assignments: bool,
+    /// directories of libraries (eg: node_modules/circomlib/circuits) whose templates and
+    /// functions can be included by a quick fix, relative to the workspace folder
+    pub library_paths: Vec<PathBuf>,
+    /// name of the prime of the circuits (`circom --prime <name>`) or the value of a custom
+    /// prime, bn128 by default
+    pub prime: Option<String>,
+    /// warn about an entrypoint without `component main`, off by default
+    /// since a library opened on its own has no main component either
+    pub warn_missing_main: bool,
+}
+
+impl Config {
+    /// the prime of the config, the default prime for an unknown name or a modulus below 2
+    pub fn prime(&self) -> Prime {
+        self.prime
+            .as_deref()
+            .and_then(Prime::from_setting)
+            .unwrap_or_default()
+    }
+}
+
 /// state of all (circom) source file
 pub struct GlobalState {
     /// file id - ast from that file content
@@ -52,8 +130,22 @@ pub struct GlobalState {
     /// file id - file content (+ end lines)
     pub file_map: DashMap<String, FileDB>,
 
+    /// file id - errors of the parser in that file content
+    pub parse_errors: DashMap<String, Vec<ParseError>>,
+
+    /// files of the library paths, parsed once when the server starts
+    pub library_files: Vec<(FileDB, AstCircomProgram)>,
+
+    /// file id of the files opened in the client, their text is not read from disk
+    pub open_files: HashSet<String>,
+
+    /// file id - modification time of the file when it was read from disk
+    pub disk_times: HashMap<String, SystemTime>,
+
     /// file id - database (template in4, function in4...)
     pub db: SemanticDB,
+
+    pub config: Config,
 }
 
 impl Default for GlobalState {
@@ -67,7 +159,12 @@ impl GlobalState {
         Self {
             ast_map: DashMap::new(),
             file_map: DashMap::new(),
+            parse_errors: DashMap::new(),
+            library_files: Vec::new(),
+            open_files: HashSet::new(),
+            disk_times: HashMap::new(),
             db: SemanticDB::new(),
+            config: Config::default(),
         }
     }
 
@@ -90,8 +187,12 @@ impl GlobalState {
         // continue looking up in libs
         let p = root.get_path();
 
+        // the template of the main component can also be declared in a lib
+        let public_signal = lookup_node_wrap_token(TokenKind::PublicSignalList, token).is_some();
+
         if lookup_node_wrap_token(TokenKind::ComponentDecl, token).is_some()
             || lookup_node_wrap_token(TokenKind::ComponentCall, token).is_some()
+            || lookup_node_wrap_token(TokenKind::MainComponent, token).is_some()
         {
             for lib in ast.libs() {
                 let lib_abs_path = PathBuf::from(lib.lib().unwrap().value());
@@ -100,7 +201,14 @@ impl GlobalState {
 
                 if let Some(file_lib) = self.file_map.get(&lib_url.to_string()) {
                     let ast_lib = self.ast_map.get(&lib_url.to_string()).unwrap();
-                    if let Some(semantic_data_lib) = self.db.semantic.get(&file_lib.file_id) {
+                    if public_signal {
+                        if let Some(main_component) = ast.main_component() {
+                            let lib_result =
+                                lookup_public_signal(&file_lib, &ast_lib, &main_component, token);
+                            result.extend(lib_result);
+                        }
+                    } else if let Some(semantic_data_lib) = self.db.semantic.get(&file_lib.file_id)
+                    {
                         let lib_result =
                             lookup_definition(&file_lib, &ast_lib, semantic_data_lib, token);
                         result.extend(lib_result);
@@ -145,51 +253,760 @@ impl GlobalState {
         }
     }
 
+    pub fn code_action_handler(&self, id: RequestId, params: CodeActionParams) -> Response {
+        let uri = params.text_document.uri;
+
+        let actions = match (
+            self.file_map.get(&uri.to_string()),
+            self.ast_map.get(&uri.to_string()),
+            self.parse_errors.get(&uri.to_string()),
+        ) {
+            (Some(file), Some(ast), Some(parse_errors)) => code_actions(
+                &file,
+                &ast,
+                &parse_errors,
+                &self.include_closure(&uri),
+                &self.includable_files(),
+                params.range,
+            ),
+            _ => Vec::new(),
+        };
+
+        let result: Option<CodeActionResponse> = Some(actions);
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn hover_handler(&self, id: RequestId, params: HoverParams) -> Response {
+        let uri = params.text_document_position_params.text_document.uri;
+
+        let result = match (
+            self.file_map.get(&uri.to_string()),
+            self.ast_map.get(&uri.to_string()),
+        ) {
+            (Some(file), Some(ast)) => {
+                let (mut evaluator, instances) = self.template_instances(&uri);
+                hover(
+                    &file,
+                    &ast,
+                    &mut evaluator,
+                    &instances,
+                    params.text_document_position_params.position,
+                )
+            }
+            _ => None,
+        };
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn inlay_hint_handler(&self, id: RequestId, params: InlayHintParams) -> Response {
+        let uri = params.text_document.uri;
+
+        let hints = match (
+            self.file_map.get(&uri.to_string()),
+            self.ast_map.get(&uri.to_string()),
+        ) {
+            (Some(file), Some(ast)) => {
+                let (mut evaluator, instances) = self.template_instances(&uri);
+                inlay_hints(&file, &ast, &mut evaluator, &instances, params.range)
+            }
+            _ => Vec::new(),
+        };
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(Some(hints)).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn code_lens_handler(&self, id: RequestId, params: CodeLensParams) -> Response {
+        let uri = params.text_document.uri;
+
+        let lenses = match (
+            self.file_map.get(&uri.to_string()),
+            self.ast_map.get(&uri.to_string()),
+        ) {
+            (Some(file), Some(ast)) => {
+                let mut lenses = code_lenses(&file, &ast);
+                lenses.extend(constraint_lenses(&file, &ast));
+                lenses
+            }
+            _ => Vec::new(),
+        };
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(Some(lenses)).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn code_lens_resolve_handler(&self, id: RequestId, lens: CodeLens) -> Response {
+        let data = lens
+            .data
+            .clone()
+            .and_then(|data| serde_json::from_value::<LensData>(data).ok());
+
+        let result = match data {
+            Some(data) if data.kind == LensKind::Constraints => {
+                let estimate = self.constraint_estimate(&data.uri, &data.name);
+                resolve_constraint_lens(lens, estimate.as_ref())
+            }
+            Some(data) => {
+                let usages = self.usages(&data.uri, &data.name);
+                resolve_code_lens(lens, &data, &usages)
+            }
+            None => lens,
+        };
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn prepare_call_hierarchy_handler(
+        &self,
+        id: RequestId,
+        params: CallHierarchyPrepareParams,
+    ) -> Response {
+        let uri = params.text_document_position_params.text_document.uri;
+        let files = self.included_files(&uri);
+        let result = prepare_call_hierarchy(&files, params.text_document_position_params.position)
+            .map(|item| vec![item]);
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn incoming_calls_handler(
+        &self,
+        id: RequestId,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Response {
+        let files = self.visible_files(&params.item.uri);
+        let result = Some(incoming_calls(&files, &params.item.name));
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn outgoing_calls_handler(
+        &self,
+        id: RequestId,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Response {
+        let files = self.included_files(&params.item.uri);
+        let result = files.first().and_then(|(file, ast)| {
+            let definition = item_definition(ast, file, &params.item)?;
+            Some(outgoing_calls(&files, file, &definition))
+        });
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn formatting_handler(&self, id: RequestId, params: DocumentFormattingParams) -> Response {
+        let result = self.format_document(&params.text_document.uri, &params.options, None);
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn range_formatting_handler(
+        &self,
+        id: RequestId,
+        params: DocumentRangeFormattingParams,
+    ) -> Response {
+        let result = self.format_document(
+            &params.text_document.uri,
+            &params.options,
+            Some(params.range),
+        );
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    pub fn on_type_formatting_handler(
+        &self,
+        id: RequestId,
+        params: DocumentOnTypeFormattingParams,
+    ) -> Response {
+        let uri = params.text_document_position.text_document.uri.to_string();
+        let result = self.file_map.get(&uri).and_then(|file| {
+            let ast = self.ast_map.get(&uri)?;
+            Some(on_type_formatting(
+                &file,
+                &ast,
+                params.text_document_position.position,
+                &params.ch,
+                &params.options,
+                self.config.align_assignments,
+            ))
+        });
+
+        Response {
+            id,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+        }
+    }
+
+    // the text of a file is the text of its lossless syntax tree
+    fn format_document(
+        &self,
+        url: &Url,
+        options: &FormattingOptions,
+        range: Option<Range>,
+    ) -> Option<Vec<TextEdit>> {
+        let text = self
+            .ast_map
+            .get(&url.to_string())?
+            .syntax()
+            .text()
+            .to_string();
+        formatting(&text, options, range)
+    }
+
     /// update a file of (circom) source code
     /// parse new code --> syntax tree
+    /// (only the edited block is re-parsed if the file was parsed before)
     /// remove old data of that file in semantic database
     /// add new data (circom_program_semantic) + related libs into database
+    /// (a lib is read from disk when it is not open and not loaded or changed on disk)
     /// update corresponding file-map and ast-map in global-state
     pub fn handle_update(&mut self, text_document: &TextDocument) -> Result<()> {
         let text = &text_document.text;
         let url = &text_document.uri.to_string();
+        self.open_files.insert(url.to_string());
+        self.disk_times.remove(url);
 
-        let syntax = SyntaxTreeBuilder::syntax_tree(text);
+        let (syntax, errors) = match (self.ast_map.get(url), self.parse_errors.get(url)) {
+            (Some(old_ast), Some(old_errors)) => reparse(old_ast.syntax(), &old_errors, text),
+            _ => SyntaxTreeBuilder::syntax_tree_with_errors(text),
+        };
         let file_db = FileDB::create(text, text_document.uri.clone());
         let file_id = file_db.file_id;
 
-        let p: PathBuf = file_db.get_path();
         if let Some(ast) = AstCircomProgram::cast(syntax) {
             self.db.semantic.remove(&file_id);
             self.db.circom_program_semantic(&file_db, &ast);
 
-            for lib in ast.libs() {
-                if let Some(lib_abs_path) = lib.lib() {
-                    let lib_path = p.parent().unwrap().join(lib_abs_path.value()).clone();
-                    let lib_url = Url::from_file_path(lib_path.clone()).unwrap();
-                    if let Ok(src) = fs::read_to_string(lib_path) {
-                        let text_doc = TextDocument {
-                            text: src,
-                            uri: lib_url.clone(),
-                        };
-                        let lib_file = FileDB::create(&text_doc.text, lib_url.clone());
-                        let syntax = SyntaxTreeBuilder::syntax_tree(&text_doc.text);
-
-                        if let Some(lib_ast) = AstCircomProgram::cast(syntax) {
-                            self.db.semantic.remove(&lib_file.file_id);
-                            self.db.circom_program_semantic(&lib_file, &lib_ast);
-                            self.ast_map.insert(lib_url.to_string(), lib_ast);
-                        }
+            // load the libs, and the libs of the libs...
+            let mut visited = HashSet::from([url.to_string()]);
+            let mut pending = lib_urls(&file_db, &ast);
+
+            while let Some(lib_url) = pending.pop() {
+                if !visited.insert(lib_url.to_string()) {
+                    continue;
+                }
+
+                // the text of the editor, or the same text as the last time
+                if self.open_files.contains(&lib_url.to_string()) || !self.changed_on_disk(&lib_url)
+                {
+                    pending.extend(self.loaded_lib_urls(&lib_url));
+                    continue;
+                }
 
-                        self.file_map.insert(lib_url.to_string(), lib_file);
+                let Ok(path) = lib_url.to_file_path() else {
+                    continue;
+                };
+                if let Ok(src) = fs::read_to_string(&path) {
+                    let lib_file = FileDB::create(&src, lib_url.clone());
+                    let (syntax, errors) = SyntaxTreeBuilder::syntax_tree_with_errors(&src);
+
+                    if let Some(lib_ast) = AstCircomProgram::cast(syntax) {
+                        self.db.semantic.remove(&lib_file.file_id);
+                        self.db.circom_program_semantic(&lib_file, &lib_ast);
+                        pending.extend(lib_urls(&lib_file, &lib_ast));
+                        self.ast_map.insert(lib_url.to_string(), lib_ast);
+                        self.parse_errors.insert(lib_url.to_string(), errors);
+                    }
+
+                    self.file_map.insert(lib_url.to_string(), lib_file);
+                    if let Ok(time) = fs::metadata(&path).and_then(|metadata| metadata.modified()) {
+                        self.disk_times.insert(lib_url.to_string(), time);
                     }
                 }
             }
             self.ast_map.insert(url.to_string(), ast);
+            self.parse_errors.insert(url.to_string(), errors);
         }
 
         self.file_map.insert(url.to_string(), file_db);
 
         Ok(())
     }
+
+    /// a file closed in the client is read from disk again when it is included
+    pub fn handle_close(&mut self, url: &Url) {
+        self.open_files.remove(&url.to_string());
+    }
+
+    // a lib which is not loaded, or was loaded before its last change on disk
+    fn changed_on_disk(&self, url: &Url) -> bool {
+        let time = url
+            .to_file_path()
+            .ok()
+            .and_then(|path| fs::metadata(path).ok())
+            .and_then(|metadata| metadata.modified().ok());
+        !self.ast_map.contains_key(&url.to_string())
+            || time.is_none()
+            || self.disk_times.get(&url.to_string()) != time.as_ref()
+    }
+
+    /// all loaded files reachable through the include statements of a file
+    /// (without the file itself)
+    pub fn include_closure(&self, url: &Url) -> Vec<(Url, AstCircomProgram)> {
+        let mut result = Vec::new();
+        let mut visited = HashSet::from([url.to_string()]);
+        let mut pending = self.loaded_lib_urls(url);
+
+        while let Some(lib_url) = pending.pop() {
+            if !visited.insert(lib_url.to_string()) {
+                continue;
+            }
+
+            if let Some(lib_ast) = self.ast_map.get(&lib_url.to_string()) {
+                pending.extend(self.loaded_lib_urls(&lib_url));
+                result.push((lib_url, lib_ast.clone()));
+            }
+        }
+
+        result
+    }
+
+    fn loaded_lib_urls(&self, url: &Url) -> Vec<Url> {
+        match (
+            self.file_map.get(&url.to_string()),
+            self.ast_map.get(&url.to_string()),
+        ) {
+            (Some(file), Some(ast)) => lib_urls(&file, &ast),
+            _ => Vec::new(),
+        }
+    }
+
+    /// programs of the circuit a file belongs to: the file with `component main`
+    /// (the file itself or a file which includes it) and its included libs,
+    /// the file and its libs if it is not part of a circuit
+    fn circuit_programs(&self, url: &Url) -> Vec<AstCircomProgram> {
+        let main_files: Vec<(String, AstCircomProgram)> = self
+            .ast_map
+            .iter()
+            .filter(|entry| entry.value().main_component().is_some())
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+
+        for (main_url, main_ast) in main_files {
+            let Ok(main_url) = Url::parse(&main_url) else {
+                continue;
+            };
+            let libs = self.include_closure(&main_url);
+            if main_url == *url || libs.iter().any(|(lib_url, _)| lib_url == url) {
+                return std::iter::once(main_ast)
+                    .chain(libs.into_iter().map(|(_, lib)| lib))
+                    .collect();
+            }
+        }
+
+        match self.ast_map.get(&url.to_string()) {
+            Some(ast) => std::iter::once(ast.clone())
+                .chain(self.include_closure(url).into_iter().map(|(_, lib)| lib))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// instances of the templates of the circuit a file belongs to,
+    /// with an evaluator for the functions of that circuit
+    fn template_instances(&self, url: &Url) -> (ConstEvaluator, Vec<TemplateInstance>) {
+        let programs = self.circuit_programs(url);
+        let programs: Vec<&AstCircomProgram> = programs.iter().collect();
+        (
+            ConstEvaluator::new(programs.iter().copied(), &self.config.prime()),
+            template_instances(&programs, &self.config.prime()),
+        )
+    }
+
+    /// estimated number of constraints of the templates of a file
+    pub fn constraint_estimates(&self, url: &Url) -> Vec<TemplateEstimate> {
+        let Some(ast) = self.ast_map.get(&url.to_string()).map(|ast| ast.clone()) else {
+            return Vec::new();
+        };
+        let programs = self.circuit_programs(url);
+        let programs: Vec<&AstCircomProgram> = programs.iter().collect();
+
+        let prime = self.config.prime();
+        template_estimates(
+            &ast,
+            &programs,
+            &template_instances(&programs, &prime),
+            &prime,
+        )
+    }
+
+    /// the estimate of the template `name` declared in a file
+    pub fn constraint_estimate(&self, url: &Url, name: &str) -> Option<TemplateEstimate> {
+        let ast = self.ast_map.get(&url.to_string()).map(|ast| ast.clone())?;
+        let programs = self.circuit_programs(url);
+        let programs: Vec<&AstCircomProgram> = programs.iter().collect();
+
+        let prime = self.config.prime();
+        named_template_estimate(
+            &ast,
+            &programs,
+            &template_instances(&programs, &prime),
+            &prime,
+            name,
+        )
+    }
+
+    /// the loaded files which can see the declarations of `url`:
+    /// the file itself and the files which include it
+    pub fn visible_files(&self, url: &Url) -> Vec<(FileDB, AstCircomProgram)> {
+        let files: Vec<(Url, FileDB)> = self
+            .file_map
+            .iter()
+            .map(|entry| (entry.file_path.clone(), entry.value().clone()))
+            .collect();
+
+        let mut result = Vec::new();
+        for (file_url, file) in files {
+            let Some(ast) = self
+                .ast_map
+                .get(&file_url.to_string())
+                .map(|ast| ast.clone())
+            else {
+                continue;
+            };
+            let sees_declaration = file_url == *url
+                || self
+                    .include_closure(&file_url)
+                    .iter()
+                    .any(|(lib_url, _)| lib_url == url);
+            if sees_declaration {
+                result.push((file, ast));
+            }
+        }
+        result
+    }
+
+    /// a file and the loaded files it includes
+    pub fn included_files(&self, url: &Url) -> Vec<(FileDB, AstCircomProgram)> {
+        let own = match (
+            self.file_map.get(&url.to_string()),
+            self.ast_map.get(&url.to_string()),
+        ) {
+            (Some(file), Some(ast)) => Some((file.clone(), ast.clone())),
+            _ => None,
+        };
+
+        let libs = self
+            .include_closure(url)
+            .into_iter()
+            .filter_map(|(lib_url, lib_ast)| {
+                let lib_file = self.file_map.get(&lib_url.to_string())?;
+                Some((lib_file.clone(), lib_ast))
+            });
+        own.into_iter().chain(libs).collect()
+    }
+
+    /// uses of the template or function `name` declared in `url`
+    pub fn usages(&self, url: &Url, name: &str) -> Vec<Usage> {
+        self.visible_files(url)
+            .iter()
+            .flat_map(|(file, ast)| usages(file, ast, name))
+            .collect()
+    }
+
+    /// the files included by a file, directly or not, are all loaded
+    pub fn libs_loaded(&self, url: &Url) -> bool {
+        std::iter::once(url.clone())
+            .chain(
+                self.include_closure(url)
+                    .into_iter()
+                    .map(|(lib_url, _)| lib_url),
+            )
+            .all(|file_url| {
+                self.loaded_lib_urls(&file_url)
+                    .iter()
+                    .all(|lib_url| self.ast_map.contains_key(&lib_url.to_string()))
+            })
+    }
+
+    /// every loaded file with its syntax tree
+    pub fn loaded_files(&self) -> Vec<(FileDB, AstCircomProgram)> {
+        self.file_map
+            .iter()
+            .filter_map(|entry| {
+                let ast = self.ast_map.get(entry.key())?;
+                Some((entry.value().clone(), ast.clone()))
+            })
+            .collect()
+    }
+
+    /**
+     * parse the `.circom` files of the library paths of the config,
+     * a relative library path starts at `root`
+     */
+    pub fn load_library_files(&mut self, root: Option<&Path>) {
+        let mut paths = Vec::new();
+        for library_path in &self.config.library_paths {
+            let library_path = match root {
+                Some(root) => root.join(library_path),
+                None => library_path.clone(),
+            };
+            // an unreadable directory has no files
+            let _ = circom_files(&library_path, &mut paths);
+        }
+
+        for path in paths {
+            let (Ok(text), Ok(url)) = (fs::read_to_string(&path), Url::from_file_path(&path))
+            else {
+                continue;
+            };
+            if let Some(ast) = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(&text)) {
+                self.library_files.push((FileDB::create(&text, url), ast));
+            }
+        }
+    }
+
+    /// the loaded files and the files of the library paths which are not loaded
+    pub fn includable_files(&self) -> Vec<(FileDB, AstCircomProgram)> {
+        let mut files = self.loaded_files();
+        for (library_file, library_ast) in &self.library_files {
+            if !self
+                .file_map
+                .contains_key(&library_file.file_path.to_string())
+            {
+                files.push((library_file.clone(), library_ast.clone()));
+            }
+        }
+        files
+    }
+
+    /**
+     * a file which is not included by any other loaded file,
+     * and whose templates are not used by any other loaded file
+     */
+    pub fn is_entrypoint(&self, url: &Url) -> bool {
+        let templates = match self.ast_map.get(&url.to_string()) {
+            Some(ast) => callable_names(&[&ast], false),
+            None => return false,
+        };
+        let uses_templates = |other: &AstCircomProgram| {
+            other
+                .syntax()
+                .descendants()
+                .filter_map(|node| used_name(&node))
+                .any(|used| templates.contains(&used.text().to_string()))
+        };
+
+        !self.file_map.iter().any(|entry| {
+            entry.key() != url.as_str()
+                && (self.loaded_lib_urls(&entry.file_path).contains(url)
+                    || self
+                        .ast_map
+                        .get(entry.key())
+                        .is_some_and(|other| uses_templates(&other)))
+        })
+    }
+
+    /// semantic diagnostics of a file
+    pub fn diagnostics(&self, url: &Url) -> Vec<Diagnostic> {
+        let (Some(file), Some(ast)) = (
+            self.file_map.get(&url.to_string()),
+            self.ast_map.get(&url.to_string()),
+        ) else {
+            return Vec::new();
+        };
+
+        let libs = self.include_closure(url);
+        let mut diagnostics = match self.parse_errors.get(&url.to_string()) {
+            Some(parse_errors) => check_syntax(&file, &parse_errors),
+            None => Vec::new(),
+        };
+        diagnostics.extend(check_pragma(&ast));
+        diagnostics.extend(check_main_component(
+            &file,
+            &ast,
+            &libs,
+            self.config.warn_missing_main
+                && ast.main_component().is_none()
+                && self.is_entrypoint(url),
+        ));
+        diagnostics.extend(check_signal_assignments(&file, &ast));
+        diagnostics.extend(check_quadratic_constraints(&file, &ast));
+        diagnostics.extend(check_signal_types(&file, &ast));
+        diagnostics.extend(check_arity(&file, &ast, &libs));
+        diagnostics.extend(check_dimensions(&file, &ast, &self.config.prime()));
+        diagnostics.extend(check_undeclared(&file, &ast, &libs, self.libs_loaded(url)));
+
+        if let Some(semantic) = self.db.semantic.get(&file.file_id) {
+            let lib_files: Vec<(FileDB, AstCircomProgram)> = libs
+                .into_iter()
+                .filter_map(|(lib_url, lib_ast)| {
+                    let lib_file = self.file_map.get(&lib_url.to_string())?;
+                    Some((lib_file.clone(), lib_ast))
+                })
+                .collect();
+            diagnostics.extend(check_signal_usage(&file, &ast, semantic, &lib_files));
+        }
+        diagnostics
+    }
+
+    /// notification which sends the diagnostics of a file to the client
+    pub fn publish_diagnostics(&self, url: &Url) -> Notification {
+        let params = PublishDiagnosticsParams {
+            uri: url.clone(),
+            diagnostics: self.diagnostics(url),
+            version: None,
+        };
+        Notification::new(PublishDiagnostics::METHOD.to_string(), params)
+    }
+}
+
+/// a file, or the `.circom` files of a directory and its subdirectories, sorted
+pub fn circom_files(path: &Path, result: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        result.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(path)
+        .with_context(|| format!("can not read {}", path.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir()
+            || entry
+                .extension()
+                .is_some_and(|extension| extension == "circom")
+        {
+            circom_files(&entry, result)?;
+        }
+    }
+    Ok(())
+}
+
+/// absolute url of the libs included in a file
+pub fn lib_urls(file: &FileDB, ast: &AstCircomProgram) -> Vec<Url> {
+    let p: PathBuf = file.get_path();
+    ast.libs()
+        .iter()
+        .filter_map(|lib| lib.lib())
+        .filter_map(|lib| Url::from_file_path(p.parent().unwrap().join(lib.value())).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::field::Prime;
+
+    use super::{Config, GlobalState, TextDocument};
+
+    #[test]
+    fn config_test() {
+        let config: Config =
+            serde_json::from_value(serde_json::json!({ "prime": "goldilocks" })).unwrap();
+        assert_eq!(config.prime(), Prime::Goldilocks);
+        assert_eq!(Config::default().prime(), Prime::Bn128);
+
+        let config: Config = serde_json::from_value(serde_json::json!({ "prime": "17" })).unwrap();
+        assert_eq!(config.prime().modulus().to_string(), "17");
+        let config: Config = serde_json::from_value(serde_json::json!({ "prime": "0" })).unwrap();
+        assert_eq!(config.prime(), Prime::Bn128);
+    }
+
+    #[test]
+    fn missing_main_test() {
+        let url = Url::from_file_path(std::env::temp_dir().join("bits.circom")).unwrap();
+        let source = "template Num2Bits(n) {\n    signal input in;\n}\n";
+        let missing_main = |global_state: &GlobalState| {
+            global_state
+                .diagnostics(&url)
+                .iter()
+                .any(|diagnostic| diagnostic.message.starts_with("no `component main`"))
+        };
+
+        // a library opened on its own
+        let mut global_state = GlobalState::new();
+        let document = TextDocument::new(url.clone(), source.to_string());
+        global_state.handle_update(&document).unwrap();
+        assert!(!missing_main(&global_state));
+
+        global_state.config.warn_missing_main = true;
+        assert!(missing_main(&global_state));
+    }
+
+    #[test]
+    fn open_lib_test() {
+        let directory = std::env::temp_dir().join("ccls_open_lib_test");
+        fs::create_dir_all(&directory).unwrap();
+        let main_path = directory.join("main circuit.circom");
+        let lib_path = directory.join("lib.circom");
+        fs::write(&lib_path, "template A() {}").unwrap();
+
+        let main_url = Url::from_file_path(&main_path).unwrap();
+        let lib_url = Url::from_file_path(&lib_path).unwrap();
+        let lib_text = |global_state: &GlobalState| {
+            global_state
+                .ast_map
+                .get(&lib_url.to_string())
+                .unwrap()
+                .syntax()
+                .text()
+                .to_string()
+        };
+
+        let mut global_state = GlobalState::new();
+        let main = TextDocument::new(main_url, "include \"lib.circom\";".to_string());
+        global_state.handle_update(&main).unwrap();
+        assert_eq!(lib_text(&global_state), "template A() {}");
+
+        // the unsaved text of an open lib is kept
+        let lib = TextDocument::new(lib_url.clone(), "template B() {}".to_string());
+        global_state.handle_update(&lib).unwrap();
+        global_state.handle_update(&main).unwrap();
+        assert_eq!(lib_text(&global_state), "template B() {}");
+
+        // a closed lib is read from disk again
+        global_state.handle_close(&lib_url);
+        global_state.handle_update(&main).unwrap();
+        assert_eq!(lib_text(&global_state), "template A() {}");
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
 }
@@ -0,0 +1,362 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use lsp_types::Url;
+use rowan::ast::AstNode;
+use serde::Serialize;
+use syntax::abstract_syntax_tree::{AstCall, AstCircomProgram, AstTemplateDef};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+use crate::global_state::lib_urls;
+use crate::handler::call_hierarchy::find_definition;
+use crate::handler::code_lens::used_name;
+
+/// node of `component main` in the instantiation graph
+pub const MAIN: &str = "main";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Include {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Template {
+    pub name: String,
+    pub file: String,
+}
+
+/// a template (or `component main`) which instantiates a template,
+/// eg: component c = Num2Bits(n + 1); in Main --> Main, Num2Bits, "n + 1"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Instantiation {
+    pub from: String,
+    pub to: String,
+    pub arguments: String,
+}
+
+/**
+ * include graph of a circuit and its template instantiation graph,
+ * files are relative to the directory of the entry file
+ */
+#[derive(Debug, Default, Serialize)]
+pub struct CircuitGraph {
+    pub files: Vec<String>,
+    pub includes: Vec<Include>,
+    pub templates: Vec<Template>,
+    pub instantiations: Vec<Instantiation>,
+}
+
+// eg: /circuits/lib/bits.circom --> lib/bits.circom with /circuits/main.circom as entry
+fn display_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+// eg: `n + 1, 2` in Num2Bits(n + 1, 2), on one line
+fn arguments_text(node: &SyntaxNode) -> String {
+    let call = AstCall::cast(node.clone()).or_else(|| node.children().find_map(AstCall::cast));
+    let arguments: Vec<String> = call
+        .map(|call| call.arguments())
+        .unwrap_or_default()
+        .iter()
+        .map(|argument| {
+            let text = argument.syntax().text().to_string();
+            text.split_whitespace().collect::<Vec<&str>>().join(" ")
+        })
+        .collect();
+    arguments.join(", ")
+}
+
+// templates instantiated inside a node, with the text of their arguments
+fn instantiations(
+    files: &[(FileDB, AstCircomProgram)],
+    node: &SyntaxNode,
+) -> Vec<(String, String)> {
+    node.descendants()
+        .filter_map(|descendant| {
+            let name = used_name(&descendant)?.text().to_string();
+            let (_, definition) = find_definition(files, &name)?;
+            AstTemplateDef::cast(definition)?;
+            Some((name, arguments_text(&descendant)))
+        })
+        .collect()
+}
+
+/**
+ * graph of the circuit of an entry file, `files` are the entry file and the files it includes
+ * the instantiation graph starts at `component main`,
+ * or at the templates of the entry file if it has no main component
+ */
+pub fn circuit_graph(entry: &Url, files: &[(FileDB, AstCircomProgram)]) -> CircuitGraph {
+    let mut graph = CircuitGraph::default();
+    let Some((entry_file, entry_ast)) = files.iter().find(|(file, _)| file.file_path == *entry)
+    else {
+        return graph;
+    };
+    let root: PathBuf = entry_file
+        .get_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    for (file, ast) in files {
+        let from = display_path(&root, &file.get_path());
+        for lib_url in lib_urls(file, ast) {
+            let Ok(lib_path) = lib_url.to_file_path() else {
+                continue;
+            };
+            let include = Include {
+                from: from.clone(),
+                to: display_path(&root, &lib_path),
+            };
+            if !graph.includes.contains(&include) {
+                graph.includes.push(include);
+            }
+        }
+        graph.files.push(from);
+    }
+
+    let mut pending: VecDeque<(String, Vec<(String, String)>)> = VecDeque::new();
+    match entry_ast.main_component() {
+        Some(main_component) => {
+            pending.push_back((
+                MAIN.to_string(),
+                instantiations(files, main_component.syntax()),
+            ));
+        }
+        None => {
+            for template in entry_ast.template_list() {
+                let Some(name) = template.name().map(|name| name.syntax().text().to_string())
+                else {
+                    continue;
+                };
+                pending.push_back((name, Vec::new()));
+            }
+        }
+    }
+
+    while let Some((from, known)) = pending.pop_front() {
+        let used = if from == MAIN {
+            known
+        } else {
+            if graph.templates.iter().any(|template| template.name == from) {
+                continue;
+            }
+            let Some((file, definition)) = find_definition(files, &from) else {
+                continue;
+            };
+            graph.templates.push(Template {
+                name: from.clone(),
+                file: display_path(&root, &file.get_path()),
+            });
+            instantiations(files, &definition)
+        };
+
+        for (to, arguments) in used {
+            let instantiation = Instantiation {
+                from: from.clone(),
+                to: to.clone(),
+                arguments,
+            };
+            if !graph.instantiations.contains(&instantiation) {
+                graph.instantiations.push(instantiation);
+            }
+            pending.push_back((to, Vec::new()));
+        }
+    }
+
+    graph
+}
+
+// a quoted Graphviz id
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl CircuitGraph {
+    /// Graphviz graph with a cluster for the includes and one for the instantiations
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph circuit {".to_string()];
+
+        lines.push("    subgraph cluster_includes {".to_string());
+        lines.push("        label = \"includes\";".to_string());
+        for file in &self.files {
+            lines.push(format!(
+                "        {} [label = {}, shape = note];",
+                quote(&format!("file:{file}")),
+                quote(file)
+            ));
+        }
+        for include in &self.includes {
+            lines.push(format!(
+                "        {} -> {};",
+                quote(&format!("file:{}", include.from)),
+                quote(&format!("file:{}", include.to))
+            ));
+        }
+        lines.push("    }".to_string());
+
+        lines.push("    subgraph cluster_templates {".to_string());
+        lines.push("        label = \"templates\";".to_string());
+        if self.instantiations.iter().any(|edge| edge.from == MAIN) {
+            lines.push(format!(
+                "        {} [label = {}, shape = doubleoctagon];",
+                quote(&format!("template:{MAIN}")),
+                quote(MAIN)
+            ));
+        }
+        for template in &self.templates {
+            lines.push(format!(
+                "        {} [label = {}, tooltip = {}, shape = box];",
+                quote(&format!("template:{}", template.name)),
+                quote(&template.name),
+                quote(&template.file)
+            ));
+        }
+        for instantiation in &self.instantiations {
+            lines.push(format!(
+                "        {} -> {} [label = {}];",
+                quote(&format!("template:{}", instantiation.from)),
+                quote(&format!("template:{}", instantiation.to)),
+                quote(&instantiation.arguments)
+            ));
+        }
+        lines.push("    }".to_string());
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::{circuit_graph, CircuitGraph};
+
+    const LIB: &str = r#"template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+}
+
+template Unused() {
+    signal input in;
+}"#;
+
+    const SOURCE: &str = r#"include "lib/bits.circom";
+
+template Bits2() {
+    signal input in;
+    component b = Num2Bits(2);
+}
+
+template Main(n) {
+    signal input in;
+    component first = Num2Bits(n  +
+        1);
+    component second[2];
+    second[0] = Num2Bits(2 * n);
+    component bits = Bits2();
+    signal out[1] <== Num2Bits(1)(in);
+}
+
+component main = Main(8);"#;
+
+    fn graph(source: &str) -> CircuitGraph {
+        let files: Vec<_> = [
+            ("/tmp/circuit/main.circom", source),
+            ("/tmp/circuit/lib/bits.circom", LIB),
+        ]
+        .into_iter()
+        .map(|(path, source)| {
+            let url = Url::from_file_path(Path::new(path)).unwrap();
+            (
+                FileDB::create(source, url),
+                AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap(),
+            )
+        })
+        .collect();
+        let entry = files[0].0.file_path.clone();
+        circuit_graph(&entry, &files)
+    }
+
+    #[test]
+    fn circuit_graph_test() {
+        let graph = graph(SOURCE);
+        assert_eq!(graph.files, vec!["main.circom", "lib/bits.circom"]);
+        assert_eq!(graph.includes.len(), 1);
+        assert_eq!(graph.includes[0].to, "lib/bits.circom");
+
+        let templates: Vec<(&str, &str)> = graph
+            .templates
+            .iter()
+            .map(|template| (template.name.as_str(), template.file.as_str()))
+            .collect();
+        assert_eq!(
+            templates,
+            vec![
+                ("Main", "main.circom"),
+                ("Num2Bits", "lib/bits.circom"),
+                ("Bits2", "main.circom"),
+            ]
+        );
+
+        let edges: Vec<(&str, &str, &str)> = graph
+            .instantiations
+            .iter()
+            .map(|edge| {
+                (
+                    edge.from.as_str(),
+                    edge.to.as_str(),
+                    edge.arguments.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("main", "Main", "8"),
+                ("Main", "Num2Bits", "n + 1"),
+                ("Main", "Num2Bits", "2 * n"),
+                ("Main", "Bits2", ""),
+                ("Main", "Num2Bits", "1"),
+                ("Bits2", "Num2Bits", "2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn without_main_test() {
+        let source = SOURCE.replace("component main = Main(8);", "");
+        let graph = graph(&source);
+        let templates: Vec<&str> = graph
+            .templates
+            .iter()
+            .map(|template| template.name.as_str())
+            .collect();
+        assert_eq!(templates, vec!["Bits2", "Main", "Num2Bits"]);
+    }
+
+    #[test]
+    fn dot_test() {
+        let dot = graph(SOURCE).to_dot();
+        assert!(dot.starts_with("digraph circuit {"));
+        assert!(dot.contains("\"file:main.circom\" -> \"file:lib/bits.circom\";"));
+        assert!(dot.contains("\"template:Main\" -> \"template:Num2Bits\" [label = \"n + 1\"];"));
+        assert!(dot.contains("\"template:main\" [label = \"main\", shape = doubleoctagon];"));
+    }
+}
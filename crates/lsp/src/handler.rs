@@ -1 +1,10 @@
+pub mod call_hierarchy;
+pub mod code_action;
+pub mod code_lens;
+pub mod constraints;
+pub mod formatting;
 pub mod goto_definition;
+pub mod hover;
+pub mod inlay_hint;
+pub mod instances;
+pub mod on_type_formatting;
@@ -0,0 +1,288 @@
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, Position, Range,
+    SymbolKind,
+};
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{
+    AstCircomProgram, AstFunctionDef, AstMainComponent, AstTemplateDef,
+};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+use crate::diagnostics::signal_assignment::trimmed_range;
+
+use super::code_lens::used_name;
+use super::goto_definition::lookup_token_at_postion;
+
+// name and kind of a template, a function or `component main`
+fn definition_name(node: &SyntaxNode) -> Option<(String, SyntaxNode, SymbolKind)> {
+    if let Some(template) = AstTemplateDef::cast(node.clone()) {
+        let name = template.name()?.syntax().clone();
+        return Some((name.text().to_string(), name, SymbolKind::CLASS));
+    }
+    if let Some(function) = AstFunctionDef::cast(node.clone()) {
+        let name = function.function_name()?.syntax().clone();
+        return Some((name.text().to_string(), name, SymbolKind::FUNCTION));
+    }
+    AstMainComponent::cast(node.clone())?;
+    Some(("main".to_string(), node.clone(), SymbolKind::OBJECT))
+}
+
+// the template, function or `component main` around a node
+fn container(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.ancestors()
+        .find(|ancestor| definition_name(ancestor).is_some())
+}
+
+/// a template, a function or `component main` as a node of the hierarchy
+pub fn call_hierarchy_item(file: &FileDB, node: &SyntaxNode) -> Option<CallHierarchyItem> {
+    let (name, name_node, kind) = definition_name(node)?;
+    Some(CallHierarchyItem {
+        name,
+        kind,
+        tags: None,
+        detail: file
+            .get_path()
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string()),
+        uri: file.file_path.clone(),
+        range: trimmed_range(file, node),
+        selection_range: trimmed_range(file, &name_node),
+        data: None,
+    })
+}
+
+/// the template or function `name` declared in one of the files
+pub fn find_definition(
+    files: &[(FileDB, AstCircomProgram)],
+    name: &str,
+) -> Option<(FileDB, SyntaxNode)> {
+    for (file, ast) in files {
+        let definitions = ast
+            .template_list()
+            .into_iter()
+            .map(|template| template.syntax().clone())
+            .chain(
+                ast.function_list()
+                    .into_iter()
+                    .map(|function| function.syntax().clone()),
+            );
+        for definition in definitions {
+            if definition_name(&definition).is_some_and(|(found, _, _)| found == name) {
+                return Some((file.clone(), definition));
+            }
+        }
+    }
+    None
+}
+
+/// the node of an item in its file
+pub fn item_definition(
+    ast: &AstCircomProgram,
+    file: &FileDB,
+    item: &CallHierarchyItem,
+) -> Option<SyntaxNode> {
+    ast.syntax().descendants().find(|node| {
+        definition_name(node).is_some_and(|(name, name_node, _)| {
+            name == item.name && trimmed_range(file, &name_node) == item.selection_range
+        })
+    })
+}
+
+/**
+ * the template or function named at a position, eg: `Num2Bits` in its declaration
+ * or in component c = Num2Bits(8);
+ * `files` are the file of the position and the files it includes
+ */
+pub fn prepare_call_hierarchy(
+    files: &[(FileDB, AstCircomProgram)],
+    position: Position,
+) -> Option<CallHierarchyItem> {
+    let (file, ast) = files.first()?;
+    let token = lookup_token_at_postion(file, ast, position)?;
+
+    let (file, definition) = find_definition(files, token.text())?;
+    call_hierarchy_item(&file, &definition)
+}
+
+/**
+ * templates, functions and `component main` which use a template or a function:
+ * component declarations and calls
+ * `files` are the files which can see its declaration
+ */
+pub fn incoming_calls(
+    files: &[(FileDB, AstCircomProgram)],
+    name: &str,
+) -> Vec<CallHierarchyIncomingCall> {
+    let mut result: Vec<CallHierarchyIncomingCall> = Vec::new();
+
+    for (file, ast) in files {
+        for node in ast.syntax().descendants() {
+            let Some(used) = used_name(&node).filter(|used| used.text() == name) else {
+                continue;
+            };
+            let Some(from) = container(&node).and_then(|caller| call_hierarchy_item(file, &caller))
+            else {
+                continue;
+            };
+            let range = trimmed_range(file, &used);
+
+            match result
+                .iter_mut()
+                .find(|call| call.from.uri == from.uri && call.from.range == from.range)
+            {
+                Some(call) => call.from_ranges.push(range),
+                None => result.push(CallHierarchyIncomingCall {
+                    from,
+                    from_ranges: vec![range],
+                }),
+            }
+        }
+    }
+
+    result
+}
+
+/**
+ * templates and functions used by a template, a function or `component main`
+ * `files` are its file and the files it includes
+ */
+pub fn outgoing_calls(
+    files: &[(FileDB, AstCircomProgram)],
+    file: &FileDB,
+    definition: &SyntaxNode,
+) -> Vec<CallHierarchyOutgoingCall> {
+    let mut result: Vec<CallHierarchyOutgoingCall> = Vec::new();
+
+    for node in definition.descendants() {
+        let Some(used) = used_name(&node) else {
+            continue;
+        };
+        let name = used.text().to_string();
+        let range: Range = trimmed_range(file, &used);
+
+        if let Some(call) = result.iter_mut().find(|call| call.to.name == name) {
+            call.from_ranges.push(range);
+            continue;
+        }
+        let Some(to) = find_definition(files, &name)
+            .and_then(|(callee_file, callee)| call_hierarchy_item(&callee_file, &callee))
+        else {
+            continue;
+        };
+        result.push(CallHierarchyOutgoingCall {
+            to,
+            from_ranges: vec![range],
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{Position, Url};
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::{incoming_calls, item_definition, outgoing_calls, prepare_call_hierarchy};
+
+    const LIB: &str = r#"template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+}
+
+function nbits(a) {
+    return a;
+}"#;
+
+    const SOURCE: &str = r#"include "lib.circom";
+
+template Bits2() {
+    signal input in;
+    component b = Num2Bits(2);
+    b.in <== in;
+}
+
+template Main(n) {
+    signal input in;
+    component first = Num2Bits(nbits(n));
+    component second[2];
+    second[0] = Num2Bits(3);
+    component bits = Bits2();
+}
+
+component main = Main(8);"#;
+
+    fn files() -> Vec<(FileDB, AstCircomProgram)> {
+        [("/tmp/main.circom", SOURCE), ("/tmp/lib.circom", LIB)]
+            .into_iter()
+            .map(|(path, source)| {
+                let url = Url::from_file_path(Path::new(path)).unwrap();
+                (
+                    FileDB::create(source, url),
+                    AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn prepare_test() {
+        let files = files();
+        // `Num2Bits` in `component b = Num2Bits(2);`
+        let item = prepare_call_hierarchy(&files, Position::new(4, 20)).unwrap();
+        assert_eq!(item.name, "Num2Bits");
+        assert_eq!(item.uri.path(), "/tmp/lib.circom");
+        assert_eq!(item.selection_range.start, Position::new(0, 9));
+
+        // a signal
+        assert!(prepare_call_hierarchy(&files, Position::new(3, 18)).is_none());
+    }
+
+    #[test]
+    fn incoming_calls_test() {
+        let calls: Vec<(String, usize)> = incoming_calls(&files(), "Num2Bits")
+            .into_iter()
+            .map(|call| (call.from.name, call.from_ranges.len()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![("Bits2".to_string(), 1), ("Main".to_string(), 2)]
+        );
+
+        let calls: Vec<String> = incoming_calls(&files(), "Main")
+            .into_iter()
+            .map(|call| call.from.name)
+            .collect();
+        assert_eq!(calls, vec!["main"]);
+    }
+
+    #[test]
+    fn outgoing_calls_test() {
+        let files = files();
+        let item = prepare_call_hierarchy(&files, Position::new(8, 10)).unwrap();
+        let (file, ast) = &files[0];
+        let definition = item_definition(ast, file, &item).unwrap();
+
+        let calls: Vec<(String, String, usize)> = outgoing_calls(&files, file, &definition)
+            .into_iter()
+            .map(|call| {
+                let file_name = call.to.detail.unwrap();
+                (call.to.name, file_name, call.from_ranges.len())
+            })
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("Num2Bits".to_string(), "lib.circom".to_string(), 2),
+                ("nbits".to_string(), "lib.circom".to_string(), 1),
+                ("Bits2".to_string(), "main.circom".to_string(), 1),
+            ]
+        );
+    }
+}
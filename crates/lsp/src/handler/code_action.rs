@@ -0,0 +1,565 @@
+use std::collections::HashMap;
+use std::path::{Component, Path};
+
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, Position, Range, TextEdit, Url,
+    WorkspaceEdit,
+};
+use parser::error::ParseError;
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::AstCircomProgram;
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+use crate::diagnostics::pragma::check_pragma;
+use crate::diagnostics::signal_assignment::{
+    trimmed_range, unconstrained_assignment_diagnostic, unconstrained_assignments,
+};
+use crate::diagnostics::syntax_error::{missing_semicolon, syntax_error_diagnostic};
+use crate::diagnostics::undeclared::{
+    callable_names, expects_template, undeclared_callable_diagnostic, undeclared_callables,
+    undeclared_name_diagnostic, undeclared_names,
+};
+
+/// version of the added `pragma circom` when no included file has one
+const DEFAULT_VERSION: &str = "2.1.9";
+
+fn intersects(a: &Range, b: &Range) -> bool {
+    let before = |x: &Position, y: &Position| (x.line, x.character) < (y.line, y.character);
+    !before(&a.end, &b.start) && !before(&b.end, &a.start)
+}
+
+fn quick_fix(
+    file: &FileDB,
+    title: String,
+    diagnostics: Vec<Diagnostic>,
+    edit: TextEdit,
+    is_preferred: bool,
+) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(diagnostics),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(file.file_path.clone(), vec![edit])])),
+            ..Default::default()
+        }),
+        is_preferred: Some(is_preferred),
+        ..Default::default()
+    })
+}
+
+fn insertion(position: Position, text: String) -> TextEdit {
+    TextEdit::new(Range::new(position, position), text)
+}
+
+// eg: lib/bits.circom or ../bits.circom, relative to the directory of the including file
+fn include_path(from: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from
+        .parent()
+        .map(|directory| directory.components().collect())
+        .unwrap_or_default();
+    let to: Vec<Component> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to)
+        .take_while(|(from, to)| from == to)
+        .count();
+
+    let mut parts = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+// after the last include, or after the pragma, or at the start of the file
+fn include_edit(file: &FileDB, ast: &AstCircomProgram, path: &str) -> TextEdit {
+    let include = format!("include \"{path}\";");
+    let previous = match ast.libs().last() {
+        Some(lib) => Some(lib.syntax().clone()),
+        None => ast.pragma().map(|pragma| pragma.syntax().clone()),
+    };
+    match previous {
+        Some(previous) => insertion(trimmed_range(file, &previous).end, format!("\n{include}")),
+        None => insertion(Position::default(), format!("{include}\n")),
+    }
+}
+
+// number of inserted, removed or replaced characters which turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let replaced = previous[j] + usize::from(a_char != *b_char);
+            current.push(replaced.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// the name closest to a misspelled one, at most a third of its characters differ
+fn closest_name<'a>(name: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+// a declaration on its own line before a statement, or before it on its line
+fn declaration_edit(file: &FileDB, statement: &SyntaxNode, declaration: &str) -> TextEdit {
+    let start = trimmed_range(file, statement).start;
+    let line_start = Position::new(start.line, 0);
+    let root = statement
+        .ancestors()
+        .last()
+        .unwrap_or_else(|| statement.clone());
+    let before = root
+        .text()
+        .slice(file.off_set(line_start)..file.off_set(start))
+        .to_string();
+
+    if before.trim().is_empty() {
+        insertion(line_start, format!("{before}{declaration}\n"))
+    } else {
+        insertion(start, format!("{declaration} "))
+    }
+}
+
+// `<--` of an unconstrained signal --> `<==`, if the value is quadratic
+fn replace_assign_operators(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    range: Range,
+) -> Vec<CodeActionOrCommand> {
+    let mut result = Vec::new();
+
+    for assignment in unconstrained_assignments(ast) {
+        if !assignment.quadratic {
+            continue;
+        }
+
+        let diagnostic = unconstrained_assignment_diagnostic(file, &assignment);
+        if !intersects(&diagnostic.range, &range) {
+            continue;
+        }
+
+        let operator = assignment.operator.text().to_string();
+        let new_operator = assignment
+            .constraint_operator()
+            .describe()
+            .trim_matches('`');
+        let edit = TextEdit::new(
+            trimmed_range(file, &assignment.operator),
+            new_operator.to_string(),
+        );
+
+        result.push(quick_fix(
+            file,
+            format!("Replace `{operator}` with `{new_operator}`"),
+            vec![diagnostic],
+            edit,
+            true,
+        ));
+    }
+
+    result
+}
+
+// a `;` reported as missing by the parser
+fn insert_semicolons(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    parse_errors: &[ParseError],
+    range: Range,
+) -> Vec<CodeActionOrCommand> {
+    parse_errors
+        .iter()
+        .filter_map(|parse_error| {
+            let diagnostic = syntax_error_diagnostic(file, parse_error);
+            if !intersects(&diagnostic.range, &range) {
+                return None;
+            }
+            let position = file.position(missing_semicolon(ast, parse_error)?);
+            Some(quick_fix(
+                file,
+                "Insert missing `;`".to_string(),
+                vec![diagnostic],
+                insertion(position, ";".to_string()),
+                true,
+            ))
+        })
+        .collect()
+}
+
+// an undeclared template or function --> include a file which declares it, or fix its name
+fn fix_callables(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    libs: &[(Url, AstCircomProgram)],
+    files: &[(FileDB, AstCircomProgram)],
+    range: Range,
+) -> Vec<CodeActionOrCommand> {
+    let mut result = Vec::new();
+    let programs: Vec<&AstCircomProgram> = std::iter::once(ast)
+        .chain(libs.iter().map(|(_, lib)| lib))
+        .collect();
+
+    for used in undeclared_callables(ast, libs) {
+        let diagnostic = undeclared_callable_diagnostic(file, &used);
+        if !intersects(&diagnostic.range, &range) {
+            continue;
+        }
+        let name = used.text().to_string();
+        let functions = !expects_template(&used);
+
+        let mut paths: Vec<String> = Vec::new();
+        for (other_file, other_ast) in files {
+            if other_file.file_path == file.file_path
+                || !callable_names(&[other_ast], functions).contains(&name)
+            {
+                continue;
+            }
+            let path = include_path(&file.get_path(), &other_file.get_path());
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        let single = paths.len() == 1;
+        for path in paths {
+            result.push(quick_fix(
+                file,
+                format!("Add `include \"{path}\";`"),
+                vec![diagnostic.clone()],
+                include_edit(file, ast, &path),
+                single,
+            ));
+        }
+
+        if let Some(candidate) = closest_name(&name, &callable_names(&programs, functions)) {
+            result.push(quick_fix(
+                file,
+                format!("Change to `{candidate}`"),
+                vec![diagnostic],
+                TextEdit::new(trimmed_range(file, &used), candidate.to_string()),
+                false,
+            ));
+        }
+    }
+
+    result
+}
+
+// an undeclared signal or variable --> declared before the statement of its first use
+fn declare_names(file: &FileDB, ast: &AstCircomProgram, range: Range) -> Vec<CodeActionOrCommand> {
+    let mut result = Vec::new();
+
+    for template in ast.template_list() {
+        for undeclared in undeclared_names(&template) {
+            let diagnostics: Vec<Diagnostic> = undeclared
+                .uses
+                .iter()
+                .map(|identifier| undeclared_name_diagnostic(file, identifier))
+                .filter(|diagnostic| intersects(&diagnostic.range, &range))
+                .collect();
+            let Some(statement) = &undeclared.statement else {
+                continue;
+            };
+            if diagnostics.is_empty() || undeclared.indexed {
+                continue;
+            }
+
+            let declaration = format!("{} {};", undeclared.kind.keyword(), undeclared.name);
+            result.push(quick_fix(
+                file,
+                format!("Declare `{declaration}`"),
+                diagnostics,
+                declaration_edit(file, statement, &declaration),
+                true,
+            ));
+        }
+    }
+
+    result
+}
+
+// no `pragma circom` --> the version of an included file, before the first item
+fn add_pragma(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    libs: &[(Url, AstCircomProgram)],
+    range: Range,
+) -> Option<CodeActionOrCommand> {
+    let diagnostic = check_pragma(ast).pop()?;
+    if !intersects(&diagnostic.range, &range) {
+        return None;
+    }
+
+    let version = libs
+        .iter()
+        .find_map(|(_, lib)| lib.pragma()?.version())
+        .map(|version| version.syntax().text().to_string())
+        .unwrap_or_else(|| DEFAULT_VERSION.to_string());
+    let line = ast
+        .syntax()
+        .children()
+        .find(|child| !child.kind().is_trivial())
+        .map(|item| trimmed_range(file, &item).start.line)
+        .unwrap_or(0);
+
+    let pragma = format!("pragma circom {version};");
+    Some(quick_fix(
+        file,
+        format!("Add `{pragma}`"),
+        vec![diagnostic],
+        insertion(Position::new(line, 0), format!("{pragma}\n\n")),
+        true,
+    ))
+}
+
+/**
+ * quick fixes for the diagnostics inside a range
+ * - `<--` of an unconstrained signal --> `<==`, if the value is quadratic
+ * - a `;` missing for the parser --> inserted after the previous token
+ * - an undeclared template or function --> an `include` of a loaded or library file
+ *   which declares it, or the closest declared name
+ * - an undeclared signal or variable --> `signal`, `signal input` or `var` declaration,
+ *   depending on how it is used
+ * - no `pragma circom` --> the version of an included file
+ *
+ * `parse_errors` are the errors of the parser in the file,
+ * `libs` the files included by the file, `files` all the loaded files
+ * and the files of the library paths
+ */
+pub fn code_actions(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    parse_errors: &[ParseError],
+    libs: &[(Url, AstCircomProgram)],
+    files: &[(FileDB, AstCircomProgram)],
+    range: Range,
+) -> Vec<CodeActionOrCommand> {
+    let mut result = replace_assign_operators(file, ast, range);
+    result.extend(insert_semicolons(file, ast, parse_errors, range));
+    result.extend(fix_callables(file, ast, libs, files, range));
+    result.extend(declare_names(file, ast, range));
+    result.extend(add_pragma(file, ast, libs, range));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{CodeAction, CodeActionOrCommand, Position, Range, TextEdit, Url};
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::{closest_name, code_actions, include_path};
+
+    const SOURCE: &str = r#"pragma circom 2.0.0;
+template A() {
+    signal input a;
+    signal input b;
+    signal output c;
+    signal output d;
+    c <-- a * b;
+    d <-- a / b;
+}"#;
+
+    const LIB: &str = r#"pragma circom 2.1.6;
+template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+}"#;
+
+    fn line(line: u32) -> Range {
+        Range::new(Position::new(line, 0), Position::new(line, 20))
+    }
+
+    // the files are /tmp/circuit/main.circom and /tmp/circuit/lib/bits.circom
+    fn actions(source: &str, included: bool, range: Range) -> Vec<CodeAction> {
+        let files: Vec<(FileDB, AstCircomProgram)> = [
+            ("/tmp/circuit/main.circom", source),
+            ("/tmp/circuit/lib/bits.circom", LIB),
+        ]
+        .into_iter()
+        .map(|(path, source)| {
+            let url = Url::from_file_path(Path::new(path)).unwrap();
+            (
+                FileDB::create(source, url),
+                AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap(),
+            )
+        })
+        .collect();
+        let (_, errors) = SyntaxTreeBuilder::syntax_tree_with_errors(source);
+        let libs: Vec<(Url, AstCircomProgram)> = if included {
+            vec![(files[1].0.file_path.clone(), files[1].1.clone())]
+        } else {
+            Vec::new()
+        };
+
+        code_actions(&files[0].0, &files[0].1, &errors, &libs, &files, range)
+            .into_iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => action,
+                CodeActionOrCommand::Command(_) => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn edit(action: &CodeAction) -> TextEdit {
+        let url = Url::from_file_path(Path::new("/tmp/circuit/main.circom")).unwrap();
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&url];
+        assert_eq!(edits.len(), 1);
+        edits[0].clone()
+    }
+
+    fn at(line: u32, character: u32) -> Range {
+        Range::new(
+            Position::new(line, character),
+            Position::new(line, character),
+        )
+    }
+
+    #[test]
+    fn replace_assign_operator_test() {
+        let actions_of = |line_number| actions(SOURCE, false, line(line_number));
+
+        let actions = actions_of(6);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].title, "Replace `<--` with `<==`");
+        let edit = edit(&actions[0]);
+        assert_eq!(edit.new_text, "<==");
+        assert_eq!(
+            edit.range,
+            Range::new(Position::new(6, 6), Position::new(6, 9))
+        );
+
+        // a / b is not quadratic, `<==` can not be used
+        assert!(actions_of(7).is_empty());
+        assert!(actions_of(2).is_empty());
+    }
+
+    #[test]
+    fn insert_semicolon_test() {
+        let source = "pragma circom 2.0.0;\ntemplate A() {\n    signal input a\n}";
+        let actions = actions(source, false, line(3));
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].title, "Insert missing `;`");
+        assert_eq!(edit(&actions[0]), TextEdit::new(at(2, 18), ";".to_string()));
+    }
+
+    #[test]
+    fn include_test() {
+        let source = r#"pragma circom 2.0.0;
+template A() {
+    signal input a;
+    component c = Num2Bits(2);
+}"#;
+        let actions = actions(source, false, line(3));
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].title, "Add `include \"lib/bits.circom\";`");
+        assert_eq!(
+            edit(&actions[0]),
+            TextEdit::new(at(0, 20), "\ninclude \"lib/bits.circom\";".to_string())
+        );
+
+        assert_eq!(
+            include_path(
+                Path::new("/circuits/main/main.circom"),
+                Path::new("/circuits/lib/bits.circom")
+            ),
+            "../lib/bits.circom"
+        );
+    }
+
+    #[test]
+    fn misspelled_template_test() {
+        let source = r#"include "lib/bits.circom";
+template A() {
+    signal input a;
+    component c = Num2bit(2);
+}"#;
+        let actions = actions(source, true, line(3));
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].title, "Change to `Num2Bits`");
+        let edit = edit(&actions[0]);
+        assert_eq!(edit.new_text, "Num2Bits");
+        assert_eq!(
+            edit.range,
+            Range::new(Position::new(3, 18), Position::new(3, 25))
+        );
+
+        let names = vec!["Num2Bits".to_string(), "Bits2Num".to_string()];
+        assert_eq!(closest_name("Num2Bit", &names), Some("Num2Bits"));
+        assert_eq!(closest_name("Adder", &names), None);
+    }
+
+    #[test]
+    fn declare_test() {
+        let source = r#"pragma circom 2.0.0;
+template A() {
+    signal input a;
+    for (var i = 0; i < 2; i++) {
+        x <== a * y;
+    }
+    k = 3;
+    out[0] <== a;
+}"#;
+        let titles = |line_number| -> Vec<String> {
+            actions(source, false, line(line_number))
+                .into_iter()
+                .map(|action| action.title)
+                .collect()
+        };
+        assert_eq!(
+            titles(4),
+            vec!["Declare `signal x;`", "Declare `signal input y;`"]
+        );
+        assert_eq!(titles(6), vec!["Declare `var k;`"]);
+        // the dimensions of out are unknown
+        assert!(titles(7).is_empty());
+
+        // before the loop, at the top level of the template
+        let fixes = actions(source, false, line(4));
+        assert_eq!(
+            edit(&fixes[0]),
+            TextEdit::new(at(3, 0), "    signal x;\n".to_string())
+        );
+
+        let source = "pragma circom 2.0.0;\ntemplate A() { k = 1; }";
+        let fixes = actions(source, false, line(1));
+        assert_eq!(
+            edit(&fixes[0]),
+            TextEdit::new(at(1, 15), "var k; ".to_string())
+        );
+    }
+
+    #[test]
+    fn pragma_test() {
+        let source = "// license\ntemplate A() {}";
+        let fixes = actions(source, true, line(0));
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].title, "Add `pragma circom 2.1.6;`");
+        assert_eq!(
+            edit(&fixes[0]),
+            TextEdit::new(at(1, 0), "pragma circom 2.1.6;\n\n".to_string())
+        );
+
+        // no included file has a pragma
+        let fixes = actions(source, false, line(0));
+        assert_eq!(fixes[0].title, "Add `pragma circom 2.1.9;`");
+    }
+}
@@ -0,0 +1,271 @@
+use lsp_types::{CodeLens, Command, Location, Url};
+use rowan::ast::AstNode;
+use serde::{Deserialize, Serialize};
+use syntax::abstract_syntax_tree::{
+    AstCall, AstCircomProgram, AstComponentDecl, AstExpr, AstFunctionDef, AstMainComponent,
+    AstTemplateDef,
+};
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+use crate::diagnostics::signal_assignment::trimmed_range;
+
+/// command of the editor extension which opens the references at a position
+pub const SHOW_REFERENCES: &str = "circom.showReferences";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LensKind {
+    References,
+    Instantiations,
+    /// the estimated constraints of a template, see `resolve_constraint_lens`
+    Constraints,
+}
+
+/// what a code lens shows, its command is computed by `codeLens/resolve`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LensData {
+    pub uri: Url,
+    pub name: String,
+    pub kind: LensKind,
+}
+
+/// a use of a template or a function: a component declaration, a call or `component main`
+#[derive(Debug, Clone)]
+pub struct Usage {
+    pub location: Location,
+    /// the template or function it is used in, None for `component main`
+    pub container: Option<String>,
+}
+
+// name of the template or function around a node
+fn container(node: &SyntaxNode) -> Option<String> {
+    node.ancestors().find_map(|ancestor| {
+        if let Some(template) = AstTemplateDef::cast(ancestor.clone()) {
+            return Some(template.name()?.syntax().text().to_string());
+        }
+        let function = AstFunctionDef::cast(ancestor)?;
+        Some(function.function_name()?.syntax().text().to_string())
+    })
+}
+
+/**
+ * the template or function used by a node, eg: `T` in component c = T(1);
+ * a component declaration, `component main` or a call (of a template or a function)
+ */
+pub fn used_name(node: &SyntaxNode) -> Option<SyntaxNode> {
+    if let Some(declaration) = AstComponentDecl::cast(node.clone()) {
+        return Some(declaration.template()?.syntax().clone());
+    }
+    if let Some(main_component) = AstMainComponent::cast(node.clone()) {
+        return Some(main_component.template()?.syntax().clone());
+    }
+    match AstCall::cast(node.clone())?.callee()? {
+        AstExpr::Atom(callee) => Some(callee.identifier()?.syntax().clone()),
+        _ => None,
+    }
+}
+
+/// uses of the template or function `name` in a file
+pub fn usages(file: &FileDB, ast: &AstCircomProgram, name: &str) -> Vec<Usage> {
+    let mut result = Vec::new();
+
+    for node in ast.syntax().descendants() {
+        if let Some(used) = used_name(&node).filter(|used| used.text() == name) {
+            result.push(Usage {
+                location: Location::new(file.file_path.clone(), trimmed_range(file, &used)),
+                container: container(&node),
+            });
+        }
+    }
+
+    result
+}
+
+pub fn lens(file: &FileDB, node: &SyntaxNode, name: String, kind: LensKind) -> CodeLens {
+    let data = LensData {
+        uri: file.file_path.clone(),
+        name,
+        kind,
+    };
+    CodeLens {
+        range: trimmed_range(file, node),
+        command: None,
+        data: Some(serde_json::to_value(data).unwrap()),
+    }
+}
+
+/**
+ * unresolved code lenses above the templates and functions of a file
+ * - templates: references and the templates which instantiate them
+ * - functions: references
+ */
+pub fn code_lenses(file: &FileDB, ast: &AstCircomProgram) -> Vec<CodeLens> {
+    let mut result = Vec::new();
+
+    for template in ast.template_list() {
+        let Some(name) = template.name().map(|name| name.syntax().text().to_string()) else {
+            continue;
+        };
+        result.push(lens(
+            file,
+            template.syntax(),
+            name.clone(),
+            LensKind::References,
+        ));
+        result.push(lens(
+            file,
+            template.syntax(),
+            name,
+            LensKind::Instantiations,
+        ));
+    }
+
+    for function in ast.function_list() {
+        let Some(name) = function
+            .function_name()
+            .map(|name| name.syntax().text().to_string())
+        else {
+            continue;
+        };
+        result.push(lens(file, function.syntax(), name, LensKind::References));
+    }
+
+    result
+}
+
+/// the title and command of a code lens, from the uses of its template or function
+pub fn resolve_code_lens(mut lens: CodeLens, data: &LensData, usages: &[Usage]) -> CodeLens {
+    let title = match data.kind {
+        LensKind::References => match usages.len() {
+            1 => "1 reference".to_string(),
+            count => format!("{count} references"),
+        },
+        LensKind::Instantiations => {
+            let mut containers: Vec<&str> = Vec::new();
+            for usage in usages {
+                let container = usage.container.as_deref().unwrap_or("main");
+                if !containers.contains(&container) {
+                    containers.push(container);
+                }
+            }
+            if containers.is_empty() {
+                "not instantiated".to_string()
+            } else {
+                format!("instantiated by: {}", containers.join(", "))
+            }
+        }
+        // resolved with the estimate of the template, see `resolve_constraint_lens`
+        LensKind::Constraints => return lens,
+    };
+
+    let locations: Vec<Location> = usages.iter().map(|usage| usage.location.clone()).collect();
+    lens.command = Some(Command {
+        title,
+        command: SHOW_REFERENCES.to_string(),
+        arguments: Some(vec![
+            serde_json::to_value(&data.uri).unwrap(),
+            serde_json::to_value(lens.range.start).unwrap(),
+            serde_json::to_value(locations).unwrap(),
+        ]),
+    });
+    lens
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::{code_lenses, resolve_code_lens, usages, LensData, LensKind};
+
+    const SOURCE: &str = r#"template Bits(n) {
+    signal input in;
+    signal output out[n];
+}
+
+function square(x) {
+    return x * x;
+}
+
+template A() {
+    signal input in;
+    component b = Bits(square(2));
+    component c[2];
+    c[0] = Bits(2);
+}
+
+template B() {
+    signal input in;
+    signal output out[1] <== Bits(1)(in);
+}
+
+component main = Bits(3);"#;
+
+    fn resolved_titles(source: &str) -> Vec<String> {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+
+        code_lenses(&file, &ast)
+            .into_iter()
+            .map(|lens| {
+                let data: LensData = serde_json::from_value(lens.data.clone().unwrap()).unwrap();
+                let usages = usages(&file, &ast, &data.name);
+                resolve_code_lens(lens, &data, &usages)
+                    .command
+                    .unwrap()
+                    .title
+            })
+            .collect()
+    }
+
+    #[test]
+    fn code_lens_test() {
+        assert_eq!(
+            resolved_titles(SOURCE),
+            vec![
+                "4 references",
+                "instantiated by: A, B, main",
+                "0 references",
+                "not instantiated",
+                "0 references",
+                "not instantiated",
+                "1 reference",
+            ]
+        );
+    }
+
+    #[test]
+    fn usages_test() {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(SOURCE, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(SOURCE)).unwrap();
+
+        let lines: Vec<(u32, Option<String>)> = usages(&file, &ast, "Bits")
+            .into_iter()
+            .map(|usage| (usage.location.range.start.line, usage.container))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (11, Some("A".to_string())),
+                (13, Some("A".to_string())),
+                (18, Some("B".to_string())),
+                (21, None),
+            ]
+        );
+
+        let data: Vec<LensKind> = code_lenses(&file, &ast)
+            .into_iter()
+            .map(|lens| serde_json::from_value::<LensData>(lens.data.unwrap()).unwrap())
+            .map(|data| data.kind)
+            .collect();
+        assert_eq!(data[..2], [LensKind::References, LensKind::Instantiations]);
+    }
+}
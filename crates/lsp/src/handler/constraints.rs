@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+
+use lsp_types::{CodeLens, Command};
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{AstCircomProgram, AstTemplateDef};
+use syntax::const_eval::{ConstEvaluator, Value};
+use syntax::field::Prime;
+
+use crate::database::FileDB;
+
+use super::code_lens::{lens, LensKind};
+use super::instances::{find_template, instance_name, TemplateInstance};
+
+/// command of the editor extension which shows the estimate of each instance of a template
+pub const SHOW_ESTIMATES: &str = "circom.showConstraintEstimates";
+
+// distinct instances run for the estimates of a file, eg: Num2Bits(1) ... Num2Bits(254)
+const MAX_RUNS: usize = 1_000;
+
+/**
+ * number of constraints of a template instance: the `<==`, `==>` and `===`
+ * executed by its body (loops are unrolled) and those of its components, recursively
+ * linear constraints which the compiler removes are counted too
+ */
+pub struct ConstraintEstimator<'a> {
+    programs: &'a [&'a AstCircomProgram],
+    evaluator: ConstEvaluator,
+    // eg: Num2Bits(8) --> 8, None if unknown or being estimated (a recursive template)
+    estimates: HashMap<String, Option<usize>>,
+}
+
+impl<'a> ConstraintEstimator<'a> {
+    pub fn new(programs: &'a [&'a AstCircomProgram], prime: &Prime) -> Self {
+        Self {
+            programs,
+            evaluator: ConstEvaluator::new(programs.iter().copied(), prime),
+            estimates: HashMap::new(),
+        }
+    }
+
+    /// None if a loop bound, a condition or the arguments of a component are not known
+    pub fn estimate(&mut self, template: &str, arguments: Vec<Value>) -> Option<usize> {
+        let name = instance_name(template, &arguments);
+        if let Some(estimate) = self.estimates.get(&name) {
+            return *estimate;
+        }
+        if self.estimates.len() >= MAX_RUNS {
+            return None;
+        }
+
+        self.estimates.insert(name.clone(), None);
+        let estimate = self.run(template, arguments);
+        self.estimates.insert(name, estimate);
+        estimate
+    }
+
+    fn run(&mut self, template: &str, arguments: Vec<Value>) -> Option<usize> {
+        let definition = find_template(self.programs, template)?;
+        let run = self.evaluator.run_template(&definition, arguments)?;
+
+        let mut total = run.constraints?;
+        for instantiation in run.instantiations {
+            let constraints = self.estimate(&instantiation.template, instantiation.arguments)?;
+            total = total.checked_add(constraints)?;
+        }
+        Some(total)
+    }
+}
+
+/// estimated constraints of a template declared in a file
+pub struct TemplateEstimate {
+    pub template: AstTemplateDef,
+    /// instance, eg: Multiplier(3) --> constraints, None if unknown
+    pub instances: Vec<(String, Option<usize>)>,
+}
+
+impl TemplateEstimate {
+    /// eg: ≈ 12 constraints, ≈ 3–4 constraints (2 instances), constraints: unknown
+    pub fn describe(&self) -> String {
+        let known: Option<Vec<usize>> = self.instances.iter().map(|(_, count)| *count).collect();
+        let (Some(min), Some(max)) = (
+            known.iter().flatten().min().copied(),
+            known.iter().flatten().max().copied(),
+        ) else {
+            return "constraints: unknown".to_string();
+        };
+
+        let unit = if max == 1 {
+            "constraint"
+        } else {
+            "constraints"
+        };
+        if min == max {
+            format!("≈ {min} {unit}")
+        } else {
+            format!("≈ {min}–{max} {unit} ({} instances)", self.instances.len())
+        }
+    }
+}
+
+/**
+ * estimates of the templates of a file,
+ * a template with parameters is estimated for each of its known instances
+ * (see `template_instances`), a template without parameters is estimated alone
+ */
+pub fn template_estimates(
+    ast: &AstCircomProgram,
+    programs: &[&AstCircomProgram],
+    instances: &[TemplateInstance],
+    prime: &Prime,
+) -> Vec<TemplateEstimate> {
+    let mut estimator = ConstraintEstimator::new(programs, prime);
+    ast.template_list()
+        .into_iter()
+        .filter_map(|template| template_estimate(&mut estimator, template, instances))
+        .collect()
+}
+
+/// the estimate of the template `name` of a file, see `template_estimates`
+pub fn named_template_estimate(
+    ast: &AstCircomProgram,
+    programs: &[&AstCircomProgram],
+    instances: &[TemplateInstance],
+    prime: &Prime,
+    name: &str,
+) -> Option<TemplateEstimate> {
+    let template = ast.template_list().into_iter().find(|template| {
+        template
+            .name()
+            .is_some_and(|other| other.syntax().text() == name)
+    })?;
+    let mut estimator = ConstraintEstimator::new(programs, prime);
+    template_estimate(&mut estimator, template, instances)
+}
+
+fn template_estimate(
+    estimator: &mut ConstraintEstimator,
+    template: AstTemplateDef,
+    instances: &[TemplateInstance],
+) -> Option<TemplateEstimate> {
+    let name = template.name()?.syntax().text().to_string();
+    let has_parameters = template
+        .parameter_list()
+        .is_some_and(|parameters| !parameters.parameters().is_empty());
+
+    let arguments: Vec<Vec<Value>> = if has_parameters {
+        instances
+            .iter()
+            .filter(|instance| instance.template == name)
+            .map(|instance| instance.arguments.clone())
+            .collect()
+    } else {
+        vec![Vec::new()]
+    };
+
+    let instances = arguments
+        .into_iter()
+        .map(|arguments| {
+            let instance = instance_name(&name, &arguments);
+            (instance, estimator.estimate(&name, arguments))
+        })
+        .collect();
+    Some(TemplateEstimate {
+        template,
+        instances,
+    })
+}
+
+/// unresolved lenses above the templates of a file, estimated by `codeLens/resolve`
+pub fn constraint_lenses(file: &FileDB, ast: &AstCircomProgram) -> Vec<CodeLens> {
+    ast.template_list()
+        .into_iter()
+        .filter_map(|template| {
+            let name = template.name()?.syntax().text().to_string();
+            Some(lens(file, template.syntax(), name, LensKind::Constraints))
+        })
+        .collect()
+}
+
+/// the title of a constraint lens, its command shows the estimate of each instance
+pub fn resolve_constraint_lens(
+    mut lens: CodeLens,
+    estimate: Option<&TemplateEstimate>,
+) -> CodeLens {
+    let title = estimate.map_or("constraints: unknown".to_string(), |estimate| {
+        estimate.describe()
+    });
+    let instances: Vec<String> = estimate
+        .iter()
+        .flat_map(|estimate| &estimate.instances)
+        .map(|(instance, count)| match count {
+            Some(count) => format!("{instance}: ≈ {count}"),
+            None => format!("{instance}: unknown"),
+        })
+        .collect();
+
+    lens.command = Some(Command {
+        title,
+        command: SHOW_ESTIMATES.to_string(),
+        arguments: Some(vec![serde_json::to_value(instances).unwrap()]),
+    });
+    lens
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::Url;
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, field::Prime, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+    use crate::handler::code_lens::{LensData, LensKind};
+    use crate::handler::instances::template_instances;
+
+    use super::{
+        constraint_lenses, named_template_estimate, resolve_constraint_lens, template_estimates,
+        SHOW_ESTIMATES,
+    };
+
+    fn estimates(source: &str) -> Vec<String> {
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        let programs = [&ast];
+        let instances = template_instances(&programs, &Prime::default());
+
+        template_estimates(&ast, &programs, &instances, &Prime::default())
+            .iter()
+            .map(|estimate| estimate.describe())
+            .collect()
+    }
+
+    #[test]
+    fn estimate_test() {
+        let source = r#"
+template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+    var sum = 0;
+    var e = 1;
+    for (var i = 0; i < n; i++) {
+        out[i] <-- (in >> i) & 1;
+        out[i] * (out[i] - 1) === 0;
+        sum += out[i] * e;
+        e = e + e;
+    }
+    sum === in;
+}
+
+template IsZero() {
+    signal input in;
+    signal output out;
+    signal inv;
+    inv <-- in != 0 ? 1 / in : 0;
+    out <== -in * inv + 1;
+    in * out === 0;
+}
+
+template Main(n) {
+    signal input a[n];
+    component bits[n];
+    for (var i = 0; i < n; i++) {
+        bits[i] = Num2Bits(i + 1);
+        bits[i].in <== a[i];
+    }
+    signal output zero <== IsZero()(a[0]);
+}
+
+template Loop() {
+    signal input in;
+    var k = 0;
+    while (k < in) {
+        in === k;
+        k++;
+    }
+}
+
+component main = Main(3);"#;
+
+        // Num2Bits(1) + Num2Bits(2) + Num2Bits(3) = 2 + 3 + 4,
+        // 3 inputs, IsZero() = 2 and its output
+        assert_eq!(
+            estimates(source),
+            vec![
+                "≈ 2–4 constraints (3 instances)",
+                "≈ 2 constraints",
+                "≈ 15 constraints",
+                "constraints: unknown",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_test() {
+        let source = r#"
+template A(n) {
+    signal input in;
+    signal output out;
+    out <== in * n;
+}
+
+template B() {
+    signal input in;
+    component a = A(in);
+    a.in <== in;
+}"#;
+        // no main component, and the argument of A depends on a signal
+        assert_eq!(
+            estimates(source),
+            vec!["constraints: unknown", "constraints: unknown"]
+        );
+    }
+
+    #[test]
+    fn constraint_lens_test() {
+        let source = r#"
+template Square(n) {
+    signal input in;
+    signal output out;
+    out <== in * in;
+}
+
+component main = Square(2);"#;
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+
+        // the estimate is not computed before the lens is resolved
+        let lenses = constraint_lenses(&file, &ast);
+        assert_eq!(lenses.len(), 1);
+        assert!(lenses[0].command.is_none());
+        let data: LensData = serde_json::from_value(lenses[0].data.clone().unwrap()).unwrap();
+        assert_eq!(data.kind, LensKind::Constraints);
+        assert_eq!(data.name, "Square");
+
+        let programs = [&ast];
+        let instances = template_instances(&programs, &Prime::default());
+        let estimate =
+            named_template_estimate(&ast, &programs, &instances, &Prime::default(), &data.name);
+        let command = resolve_constraint_lens(lenses[0].clone(), estimate.as_ref())
+            .command
+            .unwrap();
+        assert_eq!(command.title, "≈ 1 constraint");
+        assert_eq!(command.command, SHOW_ESTIMATES);
+        assert_eq!(
+            command.arguments,
+            Some(vec![serde_json::json!(["Square(2): ≈ 1"])])
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use lsp_types::{FormattingOptions, Position, Range, TextEdit};
+use syntax::format::{format, FormatOptions};
+
+// changed lines compared one by one above this size, instead of a line diff
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// indentation of the editor: `tab_size` spaces, or a tab
+pub fn format_options(options: &FormattingOptions) -> FormatOptions {
+    FormatOptions {
+        indent: if options.insert_spaces {
+            " ".repeat(options.tab_size as usize)
+        } else {
+            "\t".to_string()
+        },
+    }
+}
+
+// the old lines [start, end) are replaced by `lines`
+struct Hunk<'a> {
+    start: usize,
+    end: usize,
+    lines: Vec<&'a str>,
+}
+
+// changed lines between two texts, with a longest common subsequence of lines
+fn hunks<'a>(old: &[&str], new: &[&'a str]) -> Vec<Hunk<'a>> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+    if old_middle.is_empty() && new_middle.is_empty() {
+        return Vec::new();
+    }
+
+    let (rows, columns) = (old_middle.len(), new_middle.len());
+    if rows.saturating_mul(columns) > MAX_DIFF_CELLS {
+        return vec![Hunk {
+            start: prefix,
+            end: prefix + rows,
+            lines: new_middle.to_vec(),
+        }];
+    }
+
+    // common[i][j]: length of the common subsequence of old_middle[i..] and new_middle[j..]
+    let mut common = vec![vec![0usize; columns + 1]; rows + 1];
+    for i in (0..rows).rev() {
+        for j in (0..columns).rev() {
+            common[i][j] = if old_middle[i] == new_middle[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut result: Vec<Hunk> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < rows || j < columns {
+        if i < rows && j < columns && old_middle[i] == new_middle[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let removed = j == columns || (i < rows && common[i + 1][j] >= common[i][j + 1]);
+        let line = prefix + i;
+        let hunk = match result.last_mut() {
+            Some(hunk) if hunk.end == line => hunk,
+            _ => {
+                result.push(Hunk {
+                    start: line,
+                    end: line,
+                    lines: Vec::new(),
+                });
+                result.last_mut().unwrap()
+            }
+        };
+        if removed {
+            hunk.end += 1;
+            i += 1;
+        } else {
+            hunk.lines.push(new_middle[j]);
+            j += 1;
+        }
+    }
+
+    // reindented lines are replaced one by one
+    result
+        .into_iter()
+        .flat_map(|hunk| {
+            if hunk.end - hunk.start != hunk.lines.len() {
+                return vec![hunk];
+            }
+            (hunk.start..hunk.end)
+                .zip(hunk.lines)
+                .map(|(line, new_line)| Hunk {
+                    start: line,
+                    end: line + 1,
+                    lines: vec![new_line],
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/**
+ * edits which turn `old` into `new`, one for each block of changed lines,
+ * so that the cursor and the unchanged lines stay in place
+ */
+pub fn line_edits(old: &str, new: &str) -> Vec<(Range, String)> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    // the end of the last line, when it is not followed by a line break
+    let position = |line: usize| {
+        if line < old_lines.len() || old.ends_with('\n') || old.is_empty() {
+            Position::new(line as u32, 0)
+        } else {
+            let last = old_lines[old_lines.len() - 1];
+            Position::new(line as u32 - 1, last.encode_utf16().count() as u32)
+        }
+    };
+
+    hunks(&old_lines, &new_lines)
+        .into_iter()
+        .map(|hunk| {
+            (
+                Range::new(position(hunk.start), position(hunk.end)),
+                hunk.lines.concat(),
+            )
+        })
+        .collect()
+}
+
+/**
+ * edits which format a document, None if it has syntax errors
+ * with a range, only the changed lines which touch that range are formatted
+ */
+pub fn formatting(
+    text: &str,
+    options: &FormattingOptions,
+    range: Option<Range>,
+) -> Option<Vec<TextEdit>> {
+    let formatted = format(text, &format_options(options))?;
+
+    let edits = line_edits(text, &formatted)
+        .into_iter()
+        .filter(|(edit_range, _)| {
+            let Some(range) = range else {
+                return true;
+            };
+            // the last replaced line, the line of an insertion
+            let (start, mut last) = (edit_range.start.line, edit_range.end.line);
+            if edit_range.end.character == 0 && last > start {
+                last -= 1;
+            }
+            start <= range.end.line && range.start.line <= last
+        })
+        .map(|(range, new_text)| TextEdit { range, new_text })
+        .collect();
+    Some(edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{FormattingOptions, Position, Range};
+
+    use super::{formatting, line_edits};
+
+    // apply edits which do not overlap, from the last one
+    fn apply(text: &str, edits: &[(Range, String)]) -> String {
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        let offset = |position: Position| {
+            lines[..position.line as usize]
+                .iter()
+                .map(|line| line.len())
+                .sum::<usize>()
+                + position.character as usize
+        };
+        let mut result = text.to_string();
+        for (range, new_text) in edits.iter().rev() {
+            result.replace_range(offset(range.start)..offset(range.end), new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn line_edits_test() {
+        let old = "a\nb\nc\nd\ne";
+        let new = "a\nB\nc\nx\nd\nE\n";
+        let edits = line_edits(old, new);
+        assert_eq!(edits.len(), 3);
+        assert_eq!(
+            edits[0].0,
+            Range::new(Position::new(1, 0), Position::new(2, 0))
+        );
+        assert_eq!(
+            edits[1].0,
+            Range::new(Position::new(3, 0), Position::new(3, 0))
+        );
+        assert_eq!(
+            edits[2].0,
+            Range::new(Position::new(4, 0), Position::new(4, 1))
+        );
+        assert_eq!(apply(old, &edits), new);
+
+        assert!(line_edits(new, new).is_empty());
+    }
+
+    #[test]
+    fn range_formatting_test() {
+        let source = "template A() {\nsignal input a;\n    signal output b;\nb<==a;\n}\n";
+        let options = FormattingOptions {
+            tab_size: 2,
+            insert_spaces: true,
+            ..Default::default()
+        };
+
+        let edits = formatting(source, &options, None).unwrap();
+        let edits: Vec<(Range, String)> = edits
+            .into_iter()
+            .map(|edit| (edit.range, edit.new_text))
+            .collect();
+        assert_eq!(
+            apply(source, &edits),
+            "template A() {\n  signal input a;\n  signal output b;\n  b <== a;\n}\n"
+        );
+
+        // the last statement only
+        let range = Range::new(Position::new(3, 0), Position::new(3, 2));
+        let edits = formatting(source, &options, Some(range)).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].new_text, "  b <== a;\n");
+
+        assert!(formatting("template {", &options, None).is_none());
+    }
+}
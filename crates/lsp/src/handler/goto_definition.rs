@@ -8,6 +8,7 @@ use rowan::SyntaxText;
 
 use syntax::abstract_syntax_tree::AstComponentCall;
 use syntax::abstract_syntax_tree::AstInclude;
+use syntax::abstract_syntax_tree::AstMainComponent;
 use syntax::abstract_syntax_tree::AstTemplateDef;
 use syntax::abstract_syntax_tree::AstTemplateName;
 use syntax::abstract_syntax_tree::{AstCircomProgram, AstComponentDecl};
@@ -66,7 +67,7 @@ pub fn lookup_component(template: &AstTemplateDef, text: SyntaxText) -> Option<A
 // if token in an include statement
 // add lib path (location of source code of that library) into result
 pub fn jump_to_lib(file: &FileDB, token: &SyntaxToken) -> Vec<Location> {
-    if let Some(include_lib) = lookup_node_wrap_token(TokenKind::IncludeKw, token) {
+    if let Some(include_lib) = lookup_node_wrap_token(TokenKind::Include, token) {
         if let Some(ast_include) = AstInclude::cast(include_lib) {
             if let Some(abs_lib_ans) = ast_include.lib() {
                 let lib_path = file
@@ -84,6 +85,27 @@ pub fn jump_to_lib(file: &FileDB, token: &SyntaxToken) -> Vec<Location> {
     Vec::new()
 }
 
+// if token is a public signal of the main component
+// eg: a in component main {public [a]} = Multiplier(2);
+// jump to the input signal declaration in the instantiated template
+pub fn lookup_public_signal(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    main_component: &AstMainComponent,
+    token: &SyntaxToken,
+) -> Vec<Location> {
+    if let Some(template_name) = main_component.template() {
+        if let Some(template) = ast.get_template_by_name(&template_name) {
+            if let Some(input_signal) = template.find_input_signal(token.text()) {
+                let range = file.range(input_signal.syntax());
+                return vec![Location::new(file.file_path.clone(), range)];
+            }
+        }
+    }
+
+    Vec::new()
+}
+
 pub fn lookup_definition(
     file: &FileDB,
     ast: &AstCircomProgram,
@@ -99,6 +121,13 @@ pub fn lookup_definition(
         return jump_to_lib(file, token);
     }
 
+    if lookup_node_wrap_token(TokenKind::PublicSignalList, token).is_some() {
+        return match ast.main_component() {
+            Some(main_component) => lookup_public_signal(file, ast, &main_component, token),
+            None => Vec::new(),
+        };
+    }
+
     // signal from other template
     // eg: in1, in2 from component call mul(in1, in2)
     let mut signal_outside = false;
@@ -194,16 +223,17 @@ mod tests {
         syntax::SyntaxTreeBuilder,
     };
 
-    use crate::{database::FileDB, handler::goto_definition::lookup_node_wrap_token};
+    use crate::{
+        database::{FileDB, SemanticDB},
+        handler::goto_definition::lookup_node_wrap_token,
+    };
 
-    use super::lookup_token_at_postion;
+    use super::{lookup_definition, lookup_token_at_postion};
 
     fn get_source_from_path(file_path: &str) -> String {
         let crate_path = std::env::var("CARGO_MANIFEST_DIR").unwrap();
         let full_path = format!("{}{}", crate_path, file_path);
-        let source = std::fs::read_to_string(&full_path).expect(&full_path);
-
-        source
+        std::fs::read_to_string(&full_path).expect(&full_path)
     }
 
     #[test]
@@ -238,6 +268,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn goto_main_component_test() {
+        let file_path = "/src/test_files/handler/main_component.circom";
+        let source = get_source_from_path(file_path);
+        let file = FileDB::create(&source, Url::from_file_path(Path::new("/tmp")).unwrap());
+
+        let syntax_node = SyntaxTreeBuilder::syntax_tree(&source);
+        let program_ast = AstCircomProgram::cast(syntax_node).unwrap();
+
+        let mut db = SemanticDB::new();
+        db.circom_program_semantic(&file, &program_ast);
+        let semantic_data = db.semantic.get(&file.file_id).unwrap();
+
+        let main_component = program_ast.main_component().unwrap();
+        let template = program_ast.template_list()[0].clone();
+
+        let definition_at = |offset| {
+            let token =
+                lookup_token_at_postion(&file, &program_ast, file.position(offset)).unwrap();
+            lookup_definition(&file, &program_ast, semantic_data, &token)
+        };
+
+        // public signal -> input signal of the template
+        let public_signals = main_component.public_signals();
+        assert_eq!(public_signals.len(), 2);
+        for (signal, name) in public_signals.iter().zip(["a", "b"]) {
+            let locations = definition_at(signal.syntax().text_range().start());
+            let input_signal = template.find_input_signal(name).unwrap();
+            assert_eq!(locations.len(), 1);
+            assert_eq!(locations[0].range, file.range(input_signal.syntax()));
+        }
+
+        // template name -> template definition
+        let template_name = main_component.template().unwrap();
+        let locations = definition_at(template_name.syntax().text_range().start());
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].range, file.range(template.syntax()));
+    }
+
     #[test]
     fn url_test() {
         let url = Url::from_file_path(Path::new("/hello/abc.tx"));
@@ -0,0 +1,184 @@
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position};
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{AstCircomProgram, AstExpr, AstTemplateDef};
+use syntax::const_eval::ConstEvaluator;
+use syntax::syntax_node::SyntaxNode;
+
+use crate::database::FileDB;
+use crate::diagnostics::signal_assignment::trimmed_range;
+
+use super::goto_definition::lookup_token_at_postion;
+use super::instances::TemplateInstance;
+
+// whitespace and line breaks of an expression --> single spaces
+fn expression_text(node: &SyntaxNode) -> String {
+    node.text()
+        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// `subject = value` once if every instance agrees, else one line per instance
+fn value_lines(subject: &str, values: &[(String, Option<String>)]) -> Vec<String> {
+    let known: Vec<&(String, Option<String>)> =
+        values.iter().filter(|(_, value)| value.is_some()).collect();
+    if known.is_empty() {
+        return Vec::new();
+    }
+    if known.len() == values.len() && known.iter().all(|(_, value)| *value == known[0].1) {
+        return vec![format!("{subject} = {}", known[0].1.as_ref().unwrap())];
+    }
+    known
+        .iter()
+        .map(|(instance, value)| format!("{subject} = {}  // {instance}", value.as_ref().unwrap()))
+        .collect()
+}
+
+/**
+ * values of a parameter or variable and of the expression around it,
+ * for every known instance of the template
+ * eg: with component main = Multiplier(3);
+ * N = 3
+ * 2**N - 1 = 7
+ */
+pub fn hover(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    evaluator: &mut ConstEvaluator,
+    instances: &[TemplateInstance],
+    position: Position,
+) -> Option<Hover> {
+    let token = lookup_token_at_postion(file, ast, position)?;
+    let template = token.parent_ancestors().find_map(AstTemplateDef::cast)?;
+    let template_name = template.name()?.syntax().text().to_string();
+
+    let instances: Vec<&TemplateInstance> = instances
+        .iter()
+        .filter(|instance| instance.template == template_name)
+        .collect();
+    if instances.is_empty() {
+        return None;
+    }
+
+    let name = token.text().to_string();
+    let mut lines = value_lines(
+        &name,
+        &instances
+            .iter()
+            .map(|instance| {
+                let value = instance.bindings.get(&name).map(|value| value.to_string());
+                (instance.describe(), value)
+            })
+            .collect::<Vec<_>>(),
+    );
+    if lines.is_empty() {
+        return None;
+    }
+
+    // the largest expression around the name which has a value, eg: `2**N - 1` for N
+    let expressions: Vec<AstExpr> = token
+        .parent_ancestors()
+        .skip(1)
+        .map_while(AstExpr::cast)
+        // parentheses have the value of the expression inside
+        .filter(|expr| !matches!(expr, AstExpr::Expression(_)))
+        .collect();
+    for expr in expressions.iter().rev() {
+        let values: Vec<(String, Option<String>)> = instances
+            .iter()
+            .map(|instance| {
+                let value = evaluator
+                    .evaluate(expr, &instance.bindings)
+                    .map(|value| value.to_string());
+                (instance.describe(), value)
+            })
+            .collect();
+        if values.iter().any(|(_, value)| value.is_some()) {
+            let text = expression_text(expr.syntax());
+            if text != name {
+                lines.extend(value_lines(&text, &values));
+            }
+            break;
+        }
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("```circom\n{}\n```", lines.join("\n")),
+        }),
+        range: Some(trimmed_range(file, &token.parent()?)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{HoverContents, Position, Url};
+    use rowan::ast::AstNode;
+    use syntax::{
+        abstract_syntax_tree::AstCircomProgram, const_eval::ConstEvaluator, field::Prime,
+        syntax::SyntaxTreeBuilder,
+    };
+
+    use crate::database::FileDB;
+    use crate::handler::instances::template_instances;
+
+    use super::hover;
+
+    const SOURCE: &str = r#"template Bits(n) {
+    signal input in;
+    signal output out[n];
+    var max = 2**n - 1;
+}
+
+template Multiplier(N) {
+    signal input in[N];
+    signal output out;
+    component high = Bits(N + 1);
+    component low = Bits(N);
+    out <== in[0] * (2**N - 1);
+}
+
+component main = Multiplier(3);"#;
+
+    fn hover_text(line: u32, character: u32) -> Option<String> {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(SOURCE, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(SOURCE)).unwrap();
+        let mut evaluator = ConstEvaluator::new([&ast], &Prime::default());
+        let instances = template_instances(&[&ast], &Prime::default());
+
+        let hover = hover(
+            &file,
+            &ast,
+            &mut evaluator,
+            &instances,
+            Position::new(line, character),
+        )?;
+        let HoverContents::Markup(markup) = hover.contents else {
+            unreachable!()
+        };
+        Some(markup.value)
+    }
+
+    #[test]
+    fn hover_test() {
+        // `N` in `2**N - 1`, the product with a signal has no value
+        assert_eq!(
+            hover_text(11, 24).unwrap(),
+            "```circom\nN = 3\n2**N - 1 = 7\n```"
+        );
+        // a parameter
+        assert_eq!(hover_text(6, 20).unwrap(), "```circom\nN = 3\n```");
+        // the two instances of a nested component
+        assert_eq!(
+            hover_text(3, 17).unwrap(),
+            "```circom\nn = 4  // Bits(4)\nn = 3  // Bits(3)\n2**n - 1 = 15  // Bits(4)\n2**n - 1 = 7  // Bits(3)\n```"
+        );
+        // a signal has no value
+        assert_eq!(hover_text(7, 17), None);
+    }
+}
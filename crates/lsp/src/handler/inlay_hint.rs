@@ -0,0 +1,171 @@
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, Position, Range};
+use parser::token_kind::TokenKind::*;
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{AstCircomProgram, AstComplexIdentifier, AstExpr};
+use syntax::const_eval::ConstEvaluator;
+
+use crate::database::FileDB;
+use crate::diagnostics::signal_assignment::trimmed_range;
+
+use super::instances::TemplateInstance;
+
+fn contains(range: &Range, position: &Position) -> bool {
+    let key = |position: &Position| (position.line, position.character);
+    key(&range.start) <= key(position) && key(position) <= key(&range.end)
+}
+
+fn is_literal(dimension: &AstExpr) -> bool {
+    matches!(dimension, AstExpr::Atom(atom) if atom.number().is_some())
+}
+
+/**
+ * concrete dimensions of the signals and components of a template
+ * under its known instances, eg: `[3]` after `signal input in[N]`
+ * with component main = Multiplier(3);
+ * instances with different dimensions are separated by `|`
+ */
+pub fn inlay_hints(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    evaluator: &mut ConstEvaluator,
+    instances: &[TemplateInstance],
+    range: Range,
+) -> Vec<InlayHint> {
+    let mut result = Vec::new();
+
+    for template in ast.template_list() {
+        let Some(name) = template.name().map(|name| name.syntax().text().to_string()) else {
+            continue;
+        };
+        let instances: Vec<&TemplateInstance> = instances
+            .iter()
+            .filter(|instance| instance.template == name)
+            .collect();
+        if instances.is_empty() {
+            continue;
+        }
+
+        let declarations = template.syntax().descendants().filter(|node| {
+            matches!(
+                node.kind(),
+                SignalDecl | InputSignalDecl | OutputSignalDecl | ComponentDecl
+            )
+        });
+        for identifier in declarations
+            .flat_map(|declaration| declaration.children())
+            .filter_map(AstComplexIdentifier::cast)
+        {
+            let dimensions: Vec<AstExpr> = identifier
+                .dimensions()
+                .iter()
+                .filter_map(|dimension| dimension.expr())
+                .collect();
+            if dimensions.iter().all(is_literal) {
+                continue;
+            }
+
+            let mut labels: Vec<String> = Vec::new();
+            for instance in &instances {
+                let sizes: Option<Vec<String>> = dimensions
+                    .iter()
+                    .map(|dimension| {
+                        let value = evaluator.evaluate(dimension, &instance.bindings)?;
+                        Some(format!("[{value}]"))
+                    })
+                    .collect();
+                let Some(sizes) = sizes else {
+                    labels.clear();
+                    break;
+                };
+                let label = sizes.concat();
+                if !labels.contains(&label) {
+                    labels.push(label);
+                }
+            }
+            if labels.is_empty() {
+                continue;
+            }
+
+            let position = trimmed_range(file, identifier.syntax()).end;
+            if !contains(&range, &position) {
+                continue;
+            }
+            result.push(InlayHint {
+                position,
+                label: InlayHintLabel::String(labels.join(" | ")),
+                kind: Some(InlayHintKind::TYPE),
+                text_edits: None,
+                tooltip: None,
+                padding_left: Some(true),
+                padding_right: None,
+                data: None,
+            });
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{InlayHintLabel, Position, Range, Url};
+    use rowan::ast::AstNode;
+    use syntax::{
+        abstract_syntax_tree::AstCircomProgram, const_eval::ConstEvaluator, field::Prime,
+        syntax::SyntaxTreeBuilder,
+    };
+
+    use crate::database::FileDB;
+    use crate::handler::instances::template_instances;
+
+    use super::inlay_hints;
+
+    #[test]
+    fn inlay_hints_test() {
+        let source = r#"template Bits(n) {
+    signal input in;
+    signal output out[n];
+}
+
+template Multiplier(N) {
+    signal input in[N][2];
+    signal output out[2];
+    component bits[N - 1];
+    component high = Bits(N + 1);
+    component low = Bits(N);
+}
+
+template Unused(M) {
+    signal input in[M];
+}
+
+component main = Multiplier(3);"#;
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        let mut evaluator = ConstEvaluator::new([&ast], &Prime::default());
+        let instances = template_instances(&[&ast], &Prime::default());
+        let range = Range::new(Position::new(0, 0), Position::new(20, 0));
+
+        let hints: Vec<(u32, u32, String)> =
+            inlay_hints(&file, &ast, &mut evaluator, &instances, range)
+                .into_iter()
+                .map(|hint| {
+                    let InlayHintLabel::String(label) = hint.label else {
+                        unreachable!()
+                    };
+                    (hint.position.line, hint.position.character, label)
+                })
+                .collect();
+        assert_eq!(
+            hints,
+            vec![
+                (2, 24, "[4] | [3]".to_string()),
+                (6, 25, "[3][2]".to_string()),
+                (8, 25, "[2]".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+
+use rowan::ast::AstNode;
+use syntax::abstract_syntax_tree::{AstCircomProgram, AstTemplateDef};
+use syntax::const_eval::{Bindings, ConstEvaluator, Value};
+use syntax::field::Prime;
+
+// templates are instantiated with many different arguments in big circuits (eg: Num2Bits(i))
+const MAX_INSTANCES: usize = 64;
+
+/// a template with known parameters, eg: Multiplier(3)
+#[derive(Debug)]
+pub struct TemplateInstance {
+    pub template: String,
+    pub arguments: Vec<Value>,
+    /// values of the parameters and variables inside the template
+    pub bindings: Bindings,
+}
+
+impl TemplateInstance {
+    /// eg: Multiplier(3)
+    pub fn describe(&self) -> String {
+        instance_name(&self.template, &self.arguments)
+    }
+}
+
+/// eg: Multiplier(3)
+pub fn instance_name(template: &str, arguments: &[Value]) -> String {
+    let arguments: Vec<String> = arguments
+        .iter()
+        .map(|argument| argument.to_string())
+        .collect();
+    format!("{template}({})", arguments.join(", "))
+}
+
+pub fn find_template(programs: &[&AstCircomProgram], name: &str) -> Option<AstTemplateDef> {
+    programs
+        .iter()
+        .flat_map(|program| program.template_list())
+        .find(|template| {
+            template
+                .name()
+                .is_some_and(|template_name| template_name.syntax().text() == name)
+        })
+}
+
+/**
+ * instances of the templates of a circuit: the template of `component main`
+ * and the components it creates, recursively
+ * eg: component main = A(3); with `component b = B(n + 1)` in A --> A(3), B(4)
+ *
+ * `programs` is the file with the main component and its included libs,
+ * instances whose arguments are not known at compile time are ignored
+ */
+pub fn template_instances(programs: &[&AstCircomProgram], prime: &Prime) -> Vec<TemplateInstance> {
+    let mut result: Vec<TemplateInstance> = Vec::new();
+
+    let Some(main_component) = programs.iter().find_map(|program| program.main_component()) else {
+        return result;
+    };
+    let (Some(template), Some(call)) = (main_component.template(), main_component.call()) else {
+        return result;
+    };
+
+    let mut evaluator = ConstEvaluator::new(programs.iter().copied(), prime);
+    let mut arguments = Vec::new();
+    for argument in call.arguments() {
+        let Some(value) = evaluator.evaluate(&argument, &Bindings::new()) else {
+            return result;
+        };
+        arguments.push(value);
+    }
+
+    let mut pending = VecDeque::from([(template.syntax().text().to_string(), arguments)]);
+    while let Some((name, arguments)) = pending.pop_front() {
+        if result.len() >= MAX_INSTANCES {
+            break;
+        }
+        if result
+            .iter()
+            .any(|instance| instance.template == name && instance.arguments == arguments)
+        {
+            continue;
+        }
+        let Some(template) = find_template(programs, &name) else {
+            continue;
+        };
+        let Some(run) = evaluator.run_template(&template, arguments.clone()) else {
+            continue;
+        };
+
+        pending.extend(
+            run.instantiations
+                .into_iter()
+                .map(|instantiation| (instantiation.template, instantiation.arguments)),
+        );
+        result.push(TemplateInstance {
+            template: name,
+            arguments,
+            bindings: run.bindings,
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, field::Prime, syntax::SyntaxTreeBuilder};
+
+    use super::template_instances;
+
+    #[test]
+    fn template_instances_test() {
+        let source = r#"
+template Bits(n) {
+    signal input in;
+    signal output out[n];
+}
+
+template Multiplier(N) {
+    signal input in[N];
+    component bits[N];
+    for (var i = 0; i < N; i++) {
+        bits[i] = Bits(i % 2 + 1);
+    }
+    component square = Bits(N * N);
+}
+
+component main = Multiplier(3);"#;
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+
+        let instances: Vec<String> = template_instances(&[&ast], &Prime::default())
+            .iter()
+            .map(|instance| instance.describe())
+            .collect();
+        assert_eq!(
+            instances,
+            vec!["Multiplier(3)", "Bits(1)", "Bits(2)", "Bits(9)"]
+        );
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::{BTreeMap, HashMap};
+
+use lsp_types::{FormattingOptions, Position, Range, TextEdit};
+use parser::token_kind::TokenKind::*;
+use rowan::ast::AstNode;
+use rowan::TextSize;
+use syntax::abstract_syntax_tree::AstCircomProgram;
+use syntax::syntax_node::{SyntaxNode, SyntaxToken};
+
+use crate::database::FileDB;
+
+use super::formatting::format_options;
+
+fn is_trivia(kind: parser::token_kind::TokenKind) -> bool {
+    matches!(kind, WhiteSpace | EndLine | CommentLine | BlockComment)
+}
+
+// `{` and `}` of a block, not of a public signal list
+fn is_block_brace(token: &SyntaxToken) -> bool {
+    matches!(token.kind(), LCurly | RCurly)
+        && token
+            .parent()
+            .and_then(|wrapper| wrapper.parent())
+            .is_some_and(|parent| parent.kind() == Block)
+}
+
+// indentation level of a line which starts with a token
+fn depth(token: &SyntaxToken) -> usize {
+    let blocks = token
+        .parent_ancestors()
+        .filter(|node| node.kind() == Block)
+        .count();
+    if is_block_brace(token) {
+        blocks.saturating_sub(1)
+    } else {
+        blocks
+    }
+}
+
+// the statement or the top level item of a token
+fn statement(token: &SyntaxToken) -> Option<SyntaxNode> {
+    token.parent_ancestors().find(|node| {
+        node.parent()
+            .is_some_and(|parent| matches!(parent.kind(), StatementList | CircomProgram))
+    })
+}
+
+// the statement ended by a `;`, which is its next sibling
+fn terminated(semicolon: &SyntaxToken) -> Option<SyntaxNode> {
+    let wrapper = semicolon.parent()?;
+    if wrapper
+        .parent()
+        .map_or(true, |parent| parent.kind() != StatementList)
+    {
+        return statement(semicolon);
+    }
+    std::iter::successors(wrapper.prev_sibling(), |node| node.prev_sibling())
+        .find(|node| !is_trivia(node.kind()))
+}
+
+fn first_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| !is_trivia(token.kind()))
+}
+
+// the lines of a text without their line break, with the offset of their start
+struct Lines<'a> {
+    lines: Vec<(usize, &'a str)>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut offset = 0;
+        let lines = text
+            .split_inclusive('\n')
+            .map(|line| {
+                let start = offset;
+                offset += line.len();
+                (start, line.trim_end_matches(['\r', '\n']))
+            })
+            .collect();
+        Self { lines }
+    }
+
+    fn line_of(&self, offset: TextSize) -> usize {
+        let offset = usize::from(offset);
+        self.lines
+            .partition_point(|(start, _)| *start <= offset)
+            .saturating_sub(1)
+    }
+
+    fn content(&self, line: usize) -> &'a str {
+        self.lines.get(line).map_or("", |(_, content)| content)
+    }
+
+    fn start(&self, line: usize) -> usize {
+        self.lines.get(line).map_or(0, |(start, _)| *start)
+    }
+}
+
+fn leading_whitespace(content: &str) -> &str {
+    &content[..content.len() - content.trim_start().len()]
+}
+
+// the lines [first, last] with a new indentation, by line
+fn reindent(
+    root: &SyntaxNode,
+    lines: &Lines,
+    first: usize,
+    last: usize,
+    indent: &str,
+) -> BTreeMap<usize, String> {
+    let mut result = BTreeMap::new();
+    // lines which start a statement --> (old indentation, new indentation)
+    let mut shifts: HashMap<usize, (String, String)> = HashMap::new();
+
+    for line in first..=last {
+        let content = lines.content(line);
+        let old = leading_whitespace(content);
+        if old.len() == content.len() {
+            continue;
+        }
+        let offset = TextSize::from((lines.start(line) + old.len()) as u32);
+        // a line inside a block comment does not start with a token
+        let Some(token) = root
+            .token_at_offset(offset)
+            .right_biased()
+            .filter(|token| token.text_range().start() == offset)
+        else {
+            continue;
+        };
+
+        let start = statement(&token).and_then(|statement| first_token(&statement));
+        let new = if is_trivia(token.kind())
+            || is_block_brace(&token)
+            || start.as_ref() == Some(&token)
+        {
+            let new = indent.repeat(depth(&token));
+            shifts.insert(line, (old.to_string(), new.clone()));
+            new
+        } else {
+            // the next lines of a statement move with its first line
+            let Some((statement_old, statement_new)) = start
+                .map(|start| lines.line_of(start.text_range().start()))
+                .and_then(|start_line| shifts.get(&start_line))
+            else {
+                continue;
+            };
+            let Some(rest) = old.strip_prefix(statement_old.as_str()) else {
+                continue;
+            };
+            format!("{statement_new}{rest}")
+        };
+        result.insert(line, format!("{new}{}", &content[old.len()..]));
+    }
+
+    result
+}
+
+// a constraint assignment on one line of a template, eg: out <== a * b;
+fn alignable(statement: &SyntaxNode, lines: &Lines) -> Option<(usize, SyntaxNode)> {
+    statement
+        .ancestors()
+        .find(|node| node.kind() == TemplateDef)?;
+    let operator = statement
+        .children()
+        .find(|child| child.kind() == RAssignConstraintSignal)?;
+    let start = statement.text_range().start();
+    let line = lines.line_of(start);
+    let content = lines.content(line);
+    // alone on its line
+    if lines.start(line) + leading_whitespace(content).len() != usize::from(start)
+        || lines.line_of(statement.text_range().end()) != line
+    {
+        return None;
+    }
+    Some((line, operator))
+}
+
+/**
+ * `<==` of the consecutive one line constraint assignments around a statement,
+ * at the same column, eg:
+ *   out <== a;
+ *   carry <== b;
+ * -->
+ *   out   <== a;
+ *   carry <== b;
+ */
+fn align(
+    statement: &SyntaxNode,
+    lines: &Lines,
+    reindented: &BTreeMap<usize, String>,
+) -> BTreeMap<usize, String> {
+    let mut result = BTreeMap::new();
+    let Some(list) = statement
+        .parent()
+        .filter(|list| list.kind() == StatementList)
+    else {
+        return result;
+    };
+    let statements: Vec<SyntaxNode> = list
+        .children()
+        .filter(|child| !is_trivia(child.kind()) && child.kind() != Semicolon)
+        .collect();
+    let Some(index) = statements.iter().position(|node| node == statement) else {
+        return result;
+    };
+    let Some((line, _)) = alignable(statement, lines) else {
+        return result;
+    };
+
+    // the run of alignable statements on consecutive lines
+    let mut run = vec![(line, index)];
+    for step in [-1isize, 1] {
+        let (mut line, mut index) = (line as isize, index as isize);
+        loop {
+            index += step;
+            line += step;
+            let Some(found) = usize::try_from(index)
+                .ok()
+                .and_then(|index| statements.get(index))
+                .and_then(|statement| alignable(statement, lines))
+            else {
+                break;
+            };
+            if found.0 as isize != line {
+                break;
+            }
+            run.push((found.0, index as usize));
+        }
+    }
+    if run.len() < 2 {
+        return result;
+    }
+
+    let rows: Vec<(usize, String, String, String)> = run
+        .iter()
+        .filter_map(|(line, index)| {
+            let (_, operator) = alignable(&statements[*index], lines)?;
+            let content = lines.content(*line);
+            let start = lines.start(*line);
+            let operator_start = usize::from(operator.text_range().start()) - start;
+            let operator_end = usize::from(operator.text_range().end()) - start;
+
+            let old = leading_whitespace(content);
+            let indentation = reindented
+                .get(line)
+                .map_or(old, |content| leading_whitespace(content))
+                .to_string();
+            let left = content[old.len()..operator_start].trim_end().to_string();
+            let right = content[operator_end..].trim_start().to_string();
+            Some((*line, indentation, left, right))
+        })
+        .collect();
+    let width = rows
+        .iter()
+        .map(|(_, _, left, _)| left.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for (line, indentation, left, right) in rows {
+        let padding = " ".repeat(width - left.chars().count());
+        result.insert(line, format!("{indentation}{left}{padding} <== {right}"));
+    }
+    result
+}
+
+/**
+ * edits after typing `;` or `}`:
+ * - `;`: the lines of the statement it ends are indented by the number of blocks around it
+ * - `}`: the lines of the block it closes are indented the same way
+ * - with `align`, the `<==` of consecutive constraint assignments are aligned after `;`
+ *
+ * the next lines of a multi-line statement keep their indentation relative to its first line
+ */
+pub fn on_type_formatting(
+    file: &FileDB,
+    ast: &AstCircomProgram,
+    position: Position,
+    character: &str,
+    options: &FormattingOptions,
+    align_assignments: bool,
+) -> Vec<TextEdit> {
+    let root = ast.syntax();
+    let text = root.text().to_string();
+    let lines = Lines::new(&text);
+    let offset = file.off_set(position);
+
+    let expected = match character {
+        ";" => Semicolon,
+        "}" => RCurly,
+        _ => return Vec::new(),
+    };
+    let Some(token) = root
+        .token_at_offset(offset)
+        .left_biased()
+        .filter(|token| token.kind() == expected)
+    else {
+        return Vec::new();
+    };
+
+    let token_line = lines.line_of(token.text_range().start());
+    let (first, last) = if expected == Semicolon {
+        let Some(start) = terminated(&token).and_then(|statement| first_token(&statement)) else {
+            return Vec::new();
+        };
+        (lines.line_of(start.text_range().start()), token_line)
+    } else {
+        let Some(block) = is_block_brace(&token)
+            .then(|| token.parent()?.parent())
+            .flatten()
+        else {
+            return Vec::new();
+        };
+        let open_line = lines.line_of(block.text_range().start());
+        ((open_line + 1).min(token_line), token_line)
+    };
+
+    let indent = format_options(options).indent;
+    let mut new_lines = reindent(root, &lines, first, last, &indent);
+    if align_assignments && expected == Semicolon {
+        if let Some(statement) = terminated(&token) {
+            let aligned = align(&statement, &lines, &new_lines);
+            new_lines.extend(aligned);
+        }
+    }
+
+    new_lines
+        .into_iter()
+        .filter_map(|(line, new)| {
+            let content = lines.content(line);
+            if new == content {
+                return None;
+            }
+            Some(TextEdit {
+                range: Range::new(
+                    Position::new(line as u32, 0),
+                    Position::new(line as u32, content.encode_utf16().count() as u32),
+                ),
+                new_text: new,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use lsp_types::{FormattingOptions, Position, Url};
+    use rowan::ast::AstNode;
+    use syntax::{abstract_syntax_tree::AstCircomProgram, syntax::SyntaxTreeBuilder};
+
+    use crate::database::FileDB;
+
+    use super::on_type_formatting;
+
+    // the source after the edits of typing `character` before `position`
+    fn typed(source: &str, position: Position, character: &str, align: bool) -> String {
+        let url = Url::from_file_path(Path::new("/tmp/main.circom")).unwrap();
+        let file = FileDB::create(source, url);
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        let options = FormattingOptions {
+            tab_size: 4,
+            insert_spaces: true,
+            ..Default::default()
+        };
+
+        let mut lines: Vec<String> = source.lines().map(|line| line.to_string()).collect();
+        for edit in on_type_formatting(&file, &ast, position, character, &options, align) {
+            assert_eq!(edit.range.start.line, edit.range.end.line);
+            lines[edit.range.start.line as usize] = edit.new_text;
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn semicolon_test() {
+        let source = r#"template A(n) {
+    signal input a;
+        signal output b;
+    for (var i = 0; i < n; i++) {
+  b <== a *
+      2;
+    }
+}"#;
+        // after `signal output b;`
+        assert_eq!(
+            typed(source, Position::new(2, 24), ";", false),
+            r#"template A(n) {
+    signal input a;
+    signal output b;
+    for (var i = 0; i < n; i++) {
+  b <== a *
+      2;
+    }
+}"#
+        );
+        // after `2;`, the second line keeps its offset
+        assert_eq!(
+            typed(source, Position::new(5, 8), ";", false),
+            r#"template A(n) {
+    signal input a;
+        signal output b;
+    for (var i = 0; i < n; i++) {
+        b <== a *
+            2;
+    }
+}"#
+        );
+    }
+
+    #[test]
+    fn brace_test() {
+        let source = r#"template A() {
+    signal input a;
+    if (a == 0) {
+  a === 0;
+            // comment
+      } else {
+a === 1;
+}
+}"#;
+        // after the `}` of the else block
+        assert_eq!(
+            typed(source, Position::new(7, 1), "}", false),
+            r#"template A() {
+    signal input a;
+    if (a == 0) {
+  a === 0;
+            // comment
+      } else {
+        a === 1;
+    }
+}"#
+        );
+        // after the `}` of the template
+        assert_eq!(
+            typed(source, Position::new(8, 1), "}", false),
+            r#"template A() {
+    signal input a;
+    if (a == 0) {
+        a === 0;
+        // comment
+    } else {
+        a === 1;
+    }
+}"#
+        );
+    }
+
+    #[test]
+    fn align_test() {
+        let source = r#"template A() {
+    signal input a;
+    signal output out;
+    signal output carry;
+    out <== a;
+    carry <== a * a;
+    signal output c <== 1;
+
+    out[0]<==a;
+}"#;
+        let aligned = r#"template A() {
+    signal input a;
+    signal output out;
+    signal output carry;
+    out             <== a;
+    carry           <== a * a;
+    signal output c <== 1;
+
+    out[0]<==a;
+}"#;
+        assert_eq!(typed(source, Position::new(5, 20), ";", true), aligned);
+        assert_eq!(typed(source, Position::new(5, 20), ";", false), source);
+        // alone
+        assert_eq!(typed(source, Position::new(8, 15), ";", true), source);
+    }
+}
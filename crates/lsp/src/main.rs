@@ -1,19 +1,42 @@
 use global_state::GlobalState;
 use std::error::Error;
 
-use lsp_types::notification::{DidChangeTextDocument, DidOpenTextDocument};
-use lsp_types::{request::GotoDefinition, InitializeParams, ServerCapabilities};
+use lsp_types::notification::{DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument};
+use lsp_types::request::{
+    CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
+    CodeActionRequest, CodeLensRequest, CodeLensResolve, Formatting, GotoDefinition, HoverRequest,
+    InlayHintRequest, OnTypeFormatting, RangeFormatting,
+};
+use lsp_types::{
+    CallHierarchyServerCapability, CodeActionProviderCapability, CodeLensOptions,
+    DocumentOnTypeFormattingOptions, HoverProviderCapability, InitializeParams, ServerCapabilities,
+};
 use lsp_types::{OneOf, TextDocumentSyncCapability, TextDocumentSyncKind};
 
 use lsp_server::{Connection, ExtractError, Message, Notification, Request, RequestId};
 
 use crate::global_state::TextDocument;
 
+pub mod check;
+pub mod cli;
 pub mod database;
+pub mod diagnostics;
+pub mod flags;
 pub mod global_state;
+pub mod graph;
 pub mod handler;
 
 fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
+    match flags::Ccls::from_env_or_exit().subcommand {
+        flags::CclsCmd::Server(_) => run_server(),
+        flags::CclsCmd::Constraints(cmd) => Ok(cmd.run()?),
+        flags::CclsCmd::Graph(cmd) => Ok(cmd.run()?),
+        flags::CclsCmd::Check(cmd) => Ok(cmd.run()?),
+        flags::CclsCmd::Fmt(cmd) => Ok(cmd.run()?),
+    }
+}
+
+fn run_server() -> Result<(), Box<dyn Error + Sync + Send>> {
     // Note that  we must have our logging only write out to stderr.
     eprintln!("starting generic LSP server");
 
@@ -25,6 +48,19 @@ fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
     let server_capabilities = serde_json::to_value(ServerCapabilities {
         text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
         definition_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        inlay_hint_provider: Some(OneOf::Left(true)),
+        code_lens_provider: Some(CodeLensOptions {
+            resolve_provider: Some(true),
+        }),
+        call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
+        document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+            first_trigger_character: ";".to_string(),
+            more_trigger_character: Some(vec!["}".to_string()]),
+        }),
         ..Default::default()
     })
     .unwrap();
@@ -50,9 +86,19 @@ fn main_loop(
     connection: Connection,
     params: serde_json::Value,
 ) -> Result<(), Box<dyn Error + Sync + Send>> {
-    let _params: InitializeParams = serde_json::from_value(params).unwrap();
+    let params: InitializeParams = serde_json::from_value(params).unwrap();
 
     let mut global_state = GlobalState::new();
+    global_state.config = params
+        .initialization_options
+        .and_then(|options| serde_json::from_value(options).ok())
+        .unwrap_or_default();
+    let root = params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .and_then(|folder| folder.uri.to_file_path().ok());
+    global_state.load_library_files(root.as_deref());
 
     for msg in &connection.receiver {
         match msg {
@@ -60,7 +106,7 @@ fn main_loop(
                 if connection.handle_shutdown(&req)? {
                     return Ok(());
                 }
-                match cast::<GotoDefinition>(req) {
+                let req = match cast::<GotoDefinition>(req) {
                     Ok((id, params)) => {
                         let resp = global_state.goto_definition_handler(id, params);
                         connection.sender.send(Message::Response(resp))?;
@@ -69,13 +115,126 @@ fn main_loop(
                     Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
                     Err(ExtractError::MethodMismatch(req)) => req,
                 };
+
+                let req = match cast::<CodeActionRequest>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.code_action_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<HoverRequest>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.hover_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<InlayHintRequest>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.inlay_hint_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<CodeLensRequest>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.code_lens_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<CodeLensResolve>(req) {
+                    Ok((id, lens)) => {
+                        let resp = global_state.code_lens_resolve_handler(id, lens);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<CallHierarchyPrepare>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.prepare_call_hierarchy_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<CallHierarchyIncomingCalls>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.incoming_calls_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<CallHierarchyOutgoingCalls>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.outgoing_calls_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<Formatting>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.formatting_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                let req = match cast::<RangeFormatting>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.range_formatting_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
+
+                match cast::<OnTypeFormatting>(req) {
+                    Ok((id, params)) => {
+                        let resp = global_state.on_type_formatting_handler(id, params);
+                        connection.sender.send(Message::Response(resp))?;
+                        continue;
+                    }
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(req)) => req,
+                };
             }
 
             Message::Response(_resp) => {}
             Message::Notification(not) => {
                 match cast_notification::<DidOpenTextDocument>(not.clone()) {
                     Ok(params) => {
-                        global_state.handle_update(&TextDocument::from(params))?;
+                        let text_document = TextDocument::from(params);
+                        global_state.handle_update(&text_document)?;
+                        let diagnostics = global_state.publish_diagnostics(&text_document.uri);
+                        connection.sender.send(Message::Notification(diagnostics))?;
                     }
                     Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
                     Err(ExtractError::MethodMismatch(_not)) => (),
@@ -83,11 +242,20 @@ fn main_loop(
 
                 match cast_notification::<DidChangeTextDocument>(not.clone()) {
                     Ok(params) => {
-                        global_state.handle_update(&TextDocument::from(params))?;
+                        let text_document = TextDocument::from(params);
+                        global_state.handle_update(&text_document)?;
+                        let diagnostics = global_state.publish_diagnostics(&text_document.uri);
+                        connection.sender.send(Message::Notification(diagnostics))?;
                     }
                     Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
                     Err(ExtractError::MethodMismatch(_)) => {}
                 }
+
+                match cast_notification::<DidCloseTextDocument>(not.clone()) {
+                    Ok(params) => global_state.handle_close(&params.text_document.uri),
+                    Err(err @ ExtractError::JsonError { .. }) => panic!("{err:?}"),
+                    Err(ExtractError::MethodMismatch(_)) => {}
+                }
             }
         }
     }
@@ -0,0 +1,10 @@
+pragma circom 2.0.0;
+
+template Multiplier(n) {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a * b;
+}
+
+component main {public [a, b]} = Multiplier(2);
@@ -0,0 +1,127 @@
+use std::{fmt, ops::Range};
+
+use serde::Serialize;
+
+use crate::token_kind::TokenKind;
+
+/// a syntax error found while parsing,
+/// `range` is the byte range of the `found` token in the source
+/// and `message` names the grammar rule being parsed,
+/// eg: expected `;` after statement, found `}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseError {
+    pub range: Range<usize>,
+    pub expected: Vec<TokenKind>,
+    pub found: TokenKind,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(range: Range<usize>, expected: Vec<TokenKind>, found: TokenKind) -> Self {
+        Self::with_context(range, expected, found, None)
+    }
+
+    /// `context` is appended to the expected tokens, eg: "in template parameters"
+    pub fn with_context(
+        range: Range<usize>,
+        expected: Vec<TokenKind>,
+        found: TokenKind,
+        context: Option<&str>,
+    ) -> Self {
+        let context = context
+            .map(|context| format!(" {context}"))
+            .unwrap_or_default();
+        let message = match expected.as_slice() {
+            [] => format!("unexpected {}{context}", found.describe()),
+            [kind] => format!(
+                "expected {}{context}, found {}",
+                kind.describe(),
+                found.describe()
+            ),
+            kinds => {
+                let kinds: Vec<&str> = kinds.iter().map(|kind| kind.describe()).collect();
+                format!(
+                    "expected one of {}{context}, found {}",
+                    kinds.join(", "),
+                    found.describe()
+                )
+            }
+        };
+
+        Self {
+            range,
+            expected,
+            found,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{input::Input, parser::Parser};
+
+    fn test(source: &str, snapshot_name: &str) {
+        let input = Input::new(source);
+        let (_, errors) = Parser::parsing(&input);
+
+        insta::assert_yaml_snapshot!(snapshot_name, errors);
+    }
+
+    #[test]
+    fn test_valid_program() {
+        let source = r#"
+    pragma circom 2.0.0;
+
+    template Multiplier2() {
+        signal input a;
+        signal input b;
+        signal output c;
+        c <== a * b;
+    }"#;
+        test(source, "test_valid_program");
+    }
+
+    #[test]
+    fn test_missing_semicolon() {
+        let source = r#"
+    template Multiplier2() {
+        signal input a;
+        signal output c
+    }"#;
+        test(source, "test_missing_semicolon");
+    }
+
+    #[test]
+    fn test_invalid_item() {
+        let source = r#"
+    pragma circom 2.0.0;
+    signal input a;
+    "#;
+        test(source, "test_invalid_item");
+    }
+
+    #[test]
+    fn test_lookahead_without_progress() {
+        let input = Input::new("template A() {}");
+        let mut p = Parser::new(&input);
+
+        // looking at a token does not count as being stuck
+        for _ in 0..1000 {
+            p.current();
+        }
+        assert!(p.errors.is_empty());
+
+        // a grammar loop which never consumes the token ends up skipping it
+        for _ in 0..257 {
+            p.ensure_progress();
+        }
+        assert_eq!(p.errors.len(), 1);
+    }
+}
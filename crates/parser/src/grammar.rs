@@ -13,35 +13,69 @@ mod statement;
 mod template;
 
 /**
- * parse circom program
+ * recovery set at item level: a token that starts a new template, function,
+ * include, pragma or main component.
+ * `template`/`function` only count when followed by `<name> (` so that
+ * a misplaced keyword inside a block does not end that block.
  */
+fn at_item_start(p: &mut Parser) -> bool {
+    match p.current() {
+        TemplateKw | FunctionKw => p.nth(1) == Identifier && p.nth(2) == LParen,
+        IncludeKw => p.nth(1) == CircomString,
+        ComponentKw => p.nth(1) == MainKw,
+        PragmaKw => true,
+        _ => false,
+    }
+}
 
-pub mod entry {
+// recovery set at statement level: `;`, `}` or a new item
+fn at_statement_end(p: &mut Parser) -> bool {
+    p.at_any(&[Semicolon, RCurly]) || at_item_start(p)
+}
 
-    use crate::token_kind::TokenKind;
+/**
+ * `;` which terminates a statement/declaration, eg: context "after statement".
+ * if it is missing, skip the rest of the broken statement
+ * (up to `;`, `}` or a new item) instead of parsing it as new statements
+ */
+fn expect_semicolon(p: &mut Parser, context: &str) {
+    if p.eat(Semicolon) {
+        return;
+    }
+
+    // the next statement starts on a new line, only `;` is missing
+    if p.eof() || p.at(RCurly) || at_item_start(p) || p.at_new_line() {
+        p.expect_in(Semicolon, context);
+        return;
+    }
+
+    p.recover_until(&[Semicolon], context, at_statement_end);
+    p.eat(Semicolon);
+}
+
+/**
+ * parse circom program
+ */
+pub mod entry {
 
     use super::*;
 
     pub fn circom_program(p: &mut Parser) {
         let m = p.open();
 
-        while p.at_any(&[
-            TokenKind::BlockComment,
-            TokenKind::CommentLine,
-            TokenKind::EndLine,
-            TokenKind::WhiteSpace,
-        ]) {
-            p.skip();
-        }
-
         while !p.eof() {
+            p.ensure_progress();
             match p.current() {
                 PragmaKw => pragma::pragma(p),
                 TemplateKw => template::template(p),
                 IncludeKw => include::include(p),
                 ComponentKw => main_component::main_component(p),
                 FunctionKw => function::function_parse(p),
-                _ => p.advance_with_error("invalid token"),
+                _ => p.recover_until(
+                    &[PragmaKw, IncludeKw, TemplateKw, FunctionKw, ComponentKw],
+                    "at top level",
+                    at_item_start,
+                ),
             }
         }
         p.close(m, CircomProgram);
@@ -9,30 +9,35 @@ use super::*;
 }
 */
 pub fn block(p: &mut Parser) {
-    p.inc_rcurly();
-
-    // TODO: why do not use expect for { and }
     if !p.at(LCurly) {
-        p.advance_with_error("Miss {");
+        if p.eof() || at_item_start(p) {
+            p.expect_in(LCurly, "at the start of block");
+        } else {
+            p.advance_with_error_in(&[LCurly], "at the start of block");
+        }
     } else {
+        p.inc_rcurly();
+
         let m = p.open();
         p.expect(LCurly);
 
         let stmt_marker = p.open();
-        while !p.at(RCurly) && !p.eof() {
+        // an unclosed block ends at the next template/function/include...
+        while !p.at(RCurly) && !p.eof() && !at_item_start(p) {
+            p.ensure_progress();
             let kind = p.current();
             match kind {
                 SignalKw => {
                     declaration::signal_declaration(p);
-                    p.expect(Semicolon);
+                    expect_semicolon(p, "after declaration");
                 }
                 VarKw => {
                     declaration::var_declaration(p);
-                    p.expect(Semicolon);
+                    expect_semicolon(p, "after declaration");
                 }
                 ComponentKw => {
                     declaration::component_declaration(p);
-                    p.expect(Semicolon);
+                    expect_semicolon(p, "after declaration");
                 }
                 _ => statement::statement(p),
             }
@@ -40,7 +45,7 @@ pub fn block(p: &mut Parser) {
 
         p.close(stmt_marker, StatementList);
 
-        p.expect(RCurly);
+        p.expect_in(RCurly, "at the end of block");
         p.close(m, Block);
 
         p.dec_rcurly();
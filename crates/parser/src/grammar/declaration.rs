@@ -12,7 +12,7 @@ fn array(p: &mut Parser) -> bool {
     while p.at(LBracket) {
         p.expect(LBracket);
         expression(p);
-        p.expect(RBracket);
+        p.expect_in(RBracket, "in array dimension");
     }
 
     is_array
@@ -25,7 +25,7 @@ pub(crate) fn complex_identifier(p: &mut Parser) {
     let open_marker = p.open();
 
     // name
-    p.expect(Identifier);
+    p.expect_in(Identifier, "in declaration");
 
     // eg: [N - 1][M]
     array(p);
@@ -106,7 +106,7 @@ pub(super) fn var_declaration(p: &mut Parser) {
     // tuple of variables
     // eg: var (in1, in2, in3) = (1, 2, 3);
     if p.at(LParen) {
-        tuple_identifier(p);
+        tuple_identifier(p, "in variable tuple");
         if p.at_var_assign() {
             p.advance();
             expression(p);
@@ -116,7 +116,7 @@ pub(super) fn var_declaration(p: &mut Parser) {
         // var in1[N], in2 = 5;
         var_init(p);
         while p.at(Comma) && !p.eof() {
-            p.skip();
+            p.advance();
             var_init(p);
         }
     }
@@ -133,7 +133,7 @@ intermediate and outputs signals right after their declaration
 pub(super) fn signal_declaration(p: &mut Parser) {
     // TODO: can we remove that?
     if !p.at(SignalKw) {
-        p.advance_with_error("Signal error");
+        p.advance_with_error_in(&[SignalKw], "in declaration");
         return;
     }
 
@@ -144,7 +144,7 @@ pub(super) fn signal_declaration(p: &mut Parser) {
     // tuple of signal
     // eg: signal (in1, in2, in3) <== tuple_value;
     if p.at(LParen) {
-        tuple_identifier(p);
+        tuple_identifier(p, "in signal tuple");
         // can not assign for input signal
         if assign_able && p.at_inline_assign_signal() {
             p.advance();
@@ -155,7 +155,7 @@ pub(super) fn signal_declaration(p: &mut Parser) {
         // signal in1[N], in2 <== signal_value;
         signal_init(p, assign_able);
         while p.at(Comma) && !p.eof() {
-            p.skip();
+            p.advance();
             signal_init(p, assign_able);
         }
     }
@@ -185,23 +185,31 @@ pub(super) fn component_declaration(p: &mut Parser) {
     if p.at(Assign) {
         p.expect(Assign);
 
-        // TODO: support `parallel` tag
-        // eg: component comp = parallel NameTemplate(...){...}
-
-        // template name
-        let m_c = p.open();
-        p.expect(Identifier);
-        p.close(m_c, TemplateName);
-
-        // template params
-        let parameter_marker = p.open();
-        tuple_expression(p);
-        p.close(parameter_marker, Call);
+        template_instance(p);
     }
 
     p.close(m, ComponentDecl);
 }
 
+/*
+* template instantiation, eg: Multiplier(2, N)
+* used in component declarations and in the main component
+*/
+pub(super) fn template_instance(p: &mut Parser) {
+    // TODO: support `parallel` tag
+    // eg: component comp = parallel NameTemplate(...){...}
+
+    // template name
+    let m_c = p.open();
+    p.expect_in(Identifier, "in template instance");
+    p.close(m_c, TemplateName);
+
+    // template params
+    let parameter_marker = p.open();
+    tuple_expression(p);
+    p.close(parameter_marker, Call);
+}
+
 pub(super) fn declaration(p: &mut Parser) {
     match p.current() {
         SignalKw => signal_declaration(p),
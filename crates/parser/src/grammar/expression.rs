@@ -34,8 +34,11 @@ fn circom_expression(p: &mut Parser) {
 */
 pub fn tenary_conditional_statement(p: &mut Parser, lhs: Marker) {
     // <condition>
-    let open_marker = p.open_before(lhs);
-    p.close(open_marker, Condition);
+    let condition_marker = p.open_before(lhs);
+    let condition_marker = p.close(condition_marker, Condition);
+
+    // wrap <condition> and the two branches
+    let open_marker = p.open_before(condition_marker);
 
     // <condition> ?
     p.expect(MarkQuestion);
@@ -46,7 +49,7 @@ pub fn tenary_conditional_statement(p: &mut Parser, lhs: Marker) {
     p.close(first_expression, Expression);
 
     // <condition> ? <expression-1> :
-    p.expect(Colon);
+    p.expect_in(Colon, "in ternary expression");
 
     // <condition> ? <expression-1> : <expression-2>
     let last_expression = p.open();
@@ -81,6 +84,7 @@ pub fn expression_rec(p: &mut Parser, pb: u16) -> Option<Marker> {
     let mut lhs = parse_able.unwrap();
 
     while !p.eof() {
+        p.ensure_progress();
         let kind = p.current();
 
         if let Some((lp, rp)) = kind.infix() {
@@ -119,7 +123,7 @@ pub fn expression_rec(p: &mut Parser, pb: u16) -> Option<Marker> {
                     let open_marker = p.open_before(lhs);
                     p.expect(LBracket);
                     expression(p);
-                    p.expect(RBracket);
+                    p.expect_in(RBracket, "in array index");
                     p.close(open_marker, ArrayQuery);
                 }
                 Dot => {
@@ -127,7 +131,7 @@ pub fn expression_rec(p: &mut Parser, pb: u16) -> Option<Marker> {
                     // abc[N - 1].def OR abc.def --> component call
                     let open_marker = p.open_before(lhs);
                     p.expect(Dot);
-                    p.expect(Identifier);
+                    p.expect_in(Identifier, "after `.`");
                     p.close(open_marker, ComponentCall);
                 }
                 UnitDec | UnitInc => {
@@ -138,7 +142,10 @@ pub fn expression_rec(p: &mut Parser, pb: u16) -> Option<Marker> {
                 }
                 _ => {
                     // not a postfix token
-                    p.advance_with_error(&format!("Expect a postfix token, but found {:?}", kind));
+                    p.advance_with_error_in(
+                        &[LParen, LBracket, Dot, UnitDec, UnitInc],
+                        "in expression",
+                    );
                     break;
                 }
             };
@@ -170,12 +177,17 @@ fn expression_atom(p: &mut Parser) -> Option<Marker> {
             let open_marker = p.open();
             p.expect(LParen);
             expression_rec(p, 0);
-            p.expect(RParen);
+            p.expect_in(RParen, "in expression");
             let m_close = p.close(open_marker, Expression);
             Some(m_close)
         }
+        // do not consume the end of the statement, eg: a + ;
+        Semicolon | RCurly | EOF => {
+            p.expect_any_in(&[Number, Identifier, LParen], "in expression");
+            None
+        }
         _ => {
-            p.advance_with_error("Invalid Token");
+            p.advance_with_error_in(&[Number, Identifier, LParen], "in expression");
             None
         }
     }
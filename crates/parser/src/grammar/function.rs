@@ -9,11 +9,11 @@ pub fn function_parse(p: &mut Parser) {
     p.expect(FunctionKw);
 
     let fn_name_marker = p.open();
-    p.expect(Identifier);
+    p.expect_in(Identifier, "after `function`");
     p.close(fn_name_marker, FunctionName);
 
     let parameter_marker = p.open();
-    tuple_identifier(p);
+    tuple_identifier(p, "in function parameters");
     p.close(parameter_marker, ParameterList);
 
     block::block(p);
@@ -5,7 +5,7 @@ pub(super) fn include(p: &mut Parser) {
 
     let m = p.open();
     p.expect(IncludeKw);
-    p.expect(CircomString);
-    p.expect(Semicolon);
-    p.close(m, IncludeKw);
+    p.expect_in(CircomString, "after `include`");
+    p.expect_in(Semicolon, "after include");
+    p.close(m, Include);
 }
@@ -6,10 +6,11 @@ use crate::grammar::{expression::expression, *};
  */
 pub(super) fn tuple_expression(p: &mut Parser) {
     // let m = p.open();
-    p.expect(LParen);
+    p.expect_in(LParen, "in arguments");
 
     // expression-1, expression-2,..., expression-n)
     while !p.at(RParen) && !p.eof() {
+        p.ensure_progress();
         expression(p);
 
         // there are no expressions remaining
@@ -18,7 +19,7 @@ pub(super) fn tuple_expression(p: &mut Parser) {
         }
     }
 
-    p.expect(RParen);
+    p.expect_in(RParen, "in arguments");
 
     // p.close(m, ExpressionList);
 }
@@ -26,10 +27,11 @@ pub(super) fn tuple_expression(p: &mut Parser) {
 /**
  * grammar: "(iden1, iden2,..., idenn)"
  * can be an empty ()
+ * context is used in errors, eg: "in template parameters"
  */
-pub(super) fn tuple_identifier(p: &mut Parser) {
+pub(super) fn tuple_identifier(p: &mut Parser, context: &str) {
     // let m = p.open();
-    p.expect(LParen);
+    p.expect_in(LParen, context);
 
     // iden1, iden2, iden3
     while p.at(Identifier) && !p.eof() {
@@ -40,7 +42,7 @@ pub(super) fn tuple_identifier(p: &mut Parser) {
         }
     }
 
-    p.expect(RParen);
+    p.expect_in(RParen, context);
     // p.close(m, IdentifierList);
 }
 
@@ -51,7 +53,7 @@ pub(super) fn tuple_identifier(p: &mut Parser) {
  */
 pub(super) fn list_identifier(p: &mut Parser) {
     // let m = p.open();
-    p.expect(LBracket);
+    p.expect_in(LBracket, "in main component");
 
     // iden1, iden2, iden3
     while p.at(Identifier) && !p.eof() {
@@ -62,6 +64,6 @@ pub(super) fn list_identifier(p: &mut Parser) {
         }
     }
 
-    p.expect(RBracket);
+    p.expect_in(RBracket, "in public signals");
     // p.close(m, IdentifierList);
 }
@@ -8,17 +8,22 @@ component main {public [signal_list]} = tempid(v1,...,vn);
 {public [signal_list]} is optional
 */
 pub fn main_component(p: &mut Parser) {
+    let m = p.open();
     p.expect(ComponentKw);
-    p.expect(MainKw);
+    p.expect_in(MainKw, "after `component`");
 
     if p.at(LCurly) {
+        let public_marker = p.open();
         p.expect(LCurly);
-        p.expect(PublicKw);
-        p.expect(LBracket);
+        p.expect_in(PublicKw, "in main component");
         list_identifier(p);
-        p.expect(RBracket);
+        p.expect_in(RCurly, "after public signals");
+        p.close(public_marker, PublicSignalList);
     }
 
-    p.expect(Assign);
-    expression::expression(p);
+    p.expect_in(Assign, "in main component");
+    declaration::template_instance(p);
+    expect_semicolon(p, "after main component");
+
+    p.close(m, MainComponent);
 }
@@ -5,12 +5,11 @@ use super::*;
  * grammar:
  *      pragma circom <version>;
  */
-
 pub fn pragma(p: &mut Parser) {
     let m = p.open();
     p.expect(PragmaKw);
-    p.expect(Circom);
-    p.expect(Version);
-    p.expect(Semicolon);
+    p.expect_in(Circom, "in pragma");
+    p.expect_in(Version, "in pragma");
+    p.expect_in(Semicolon, "after pragma");
     p.close(m, Pragma);
 }
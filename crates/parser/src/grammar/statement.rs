@@ -20,9 +20,9 @@ fn if_statement(p: &mut Parser) {
 
     // if (<condition>) <statement>
     p.expect(IfKw);
-    p.expect(LParen);
+    p.expect_in(LParen, "in if condition");
     expression(p);
-    p.expect(RParen);
+    p.expect_in(RParen, "in if condition");
     statement(p);
 
     // else <statement>
@@ -44,20 +44,20 @@ fn statement_no_condition(p: &mut Parser) {
         WhileKw => while_statement(p),
         ReturnKw => {
             return_statement(p);
-            p.expect(Semicolon);
+            expect_semicolon(p, "after statement");
         }
         LCurly => block(p),
         LogKw => {
             log_statement(p);
-            p.expect(Semicolon);
+            expect_semicolon(p, "after statement");
         }
         AssertKw => {
             assert_statement(p);
-            p.expect(Semicolon);
+            expect_semicolon(p, "after statement");
         }
         _ => {
             assignment_statement(p);
-            p.expect(Semicolon);
+            expect_semicolon(p, "after statement");
         }
     }
 }
@@ -71,7 +71,7 @@ fn for_statement(p: &mut Parser) {
 
     // for (
     p.expect(ForKw);
-    p.expect(LParen);
+    p.expect_in(LParen, "in for loop header");
 
     if p.current().is_declaration_kw() {
         // for (var i = 1
@@ -80,15 +80,15 @@ fn for_statement(p: &mut Parser) {
         // for (i = 1
         assignment_statement(p);
     }
-    p.expect(Semicolon);
+    p.expect_in(Semicolon, "in for loop header");
 
     // for (i = 1; i < N;
     expression::expression(p);
-    p.expect(Semicolon);
+    p.expect_in(Semicolon, "in for loop header");
 
     // for (i = 1; i < N; i++)
     assignment_statement(p);
-    p.expect(RParen);
+    p.expect_in(RParen, "in for loop header");
 
     // for (i = 1; i < N; i++) { <statements> }
     statement(p);
@@ -104,9 +104,9 @@ fn while_statement(p: &mut Parser) {
     let open_marker = p.open();
 
     p.expect(WhileKw);
-    p.expect(LParen);
+    p.expect_in(LParen, "in while condition");
     expression(p);
-    p.expect(RParen);
+    p.expect_in(RParen, "in while condition");
     statement(p);
 
     p.close(open_marker, WhileLoop);
@@ -119,9 +119,9 @@ fn assert_statement(p: &mut Parser) {
     let open_marker = p.open();
 
     p.expect(AssertKw);
-    p.expect(LParen);
+    p.expect_in(LParen, "in assert");
     expression(p);
-    p.expect(RParen);
+    p.expect_in(RParen, "in assert");
 
     p.close(open_marker, AssertStatement);
 }
@@ -133,10 +133,11 @@ fn log_statement(p: &mut Parser) {
     let open_marker = p.open();
 
     p.expect(LogKw);
-    p.expect(LParen);
+    p.expect_in(LParen, "in log");
 
     // list circom string/expression
     while !p.eof() {
+        p.ensure_progress();
         match p.current() {
             RParen => break,
             CircomString => p.advance(),
@@ -148,7 +149,7 @@ fn log_statement(p: &mut Parser) {
         }
     }
 
-    p.expect(RParen);
+    p.expect_in(RParen, "in log");
 
     p.close(open_marker, LogStatement);
 }
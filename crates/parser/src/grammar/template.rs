@@ -13,11 +13,11 @@ pub fn template(p: &mut Parser) {
     p.expect(TemplateKw);
 
     let name_marker = p.open();
-    p.expect(Identifier);
+    p.expect_in(Identifier, "after `template`");
     p.close(name_marker, TemplateName);
 
     let parameter_marker = p.open();
-    tuple_identifier(p);
+    tuple_identifier(p, "in template parameters");
     p.close(parameter_marker, ParameterList);
 
     block::block(p);
@@ -86,7 +86,7 @@ mod tests {
     use super::Input;
 
     fn test(source: &str, snapshot_name: &str) {
-        let input = Input::new(&source);
+        let input = Input::new(source);
 
         insta::assert_yaml_snapshot!(snapshot_name, input);
     }
@@ -124,6 +124,15 @@ mod tests {
         test(source, "test_pragma");
     }
 
+    #[test]
+    fn test_number_literals() {
+        let source = r#"
+        0 42 0xFFFF 0XdeadBEEF 0b1011
+        21888242871839275222246405745257275088548364400416034343698204186575808495617
+    "#;
+        test(source, "test_number_literals");
+    }
+
     #[test]
     fn test_function() {
         let source = r#"
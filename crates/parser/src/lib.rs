@@ -1,3 +1,4 @@
+pub mod error;
 pub mod event;
 pub mod grammar;
 pub mod parser;
@@ -1,7 +1,8 @@
-use std::cell::Cell;
+use std::{cell::Cell, ops::Range};
 
 use crate::{
-    event::Event, grammar::entry::Scope, input::Input, output::Output, token_kind::TokenKind,
+    error::ParseError, event::Event, grammar::entry::Scope, input::Input, output::Output,
+    token_kind::TokenKind,
 };
 
 pub struct Context {
@@ -14,6 +15,7 @@ pub struct Parser<'a> {
     pos: usize,
     fuel: Cell<u32>,
     pub(crate) events: Vec<Event>,
+    pub(crate) errors: Vec<ParseError>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -32,7 +34,7 @@ impl<'a> Parser<'a> {
         loop {
             let kind = self.input.kind_of(self.pos);
 
-            if kind.is_trivial() == false {
+            if !kind.is_trivial() {
                 return kind;
             }
 
@@ -43,7 +45,7 @@ impl<'a> Parser<'a> {
     }
 
     pub fn open(&mut self) -> Marker {
-        if self.events.len() > 0 {
+        if !self.events.is_empty() {
             self.wrap_trivial_tokens();
         }
 
@@ -98,15 +100,65 @@ impl<'a> Parser<'a> {
         }
     }
 
-    pub fn advance_with_error(&mut self, _error: &str) {
+    pub fn advance_with_error(&mut self, expected: &[TokenKind]) {
+        self.advance_with_error_context(expected, None);
+    }
+
+    /// `context` is the grammar rule being parsed, eg: "in expression"
+    pub fn advance_with_error_in(&mut self, expected: &[TokenKind], context: &str) {
+        self.advance_with_error_context(expected, Some(context));
+    }
+
+    fn advance_with_error_context(&mut self, expected: &[TokenKind], context: Option<&str>) {
+        let found = self.current();
+        let error =
+            ParseError::with_context(self.current_range(), expected.to_vec(), found, context);
+        self.errors.push(error);
+
         let m = self.open();
-        // TODO: Error reporting.
         if !self.eof() {
             self.advance();
         }
         self.close(m, TokenKind::Error);
     }
 
+    /**
+     * report an error at the current token, then wrap the following tokens
+     * in an Error node until `at_recovery` holds outside of any nested { }
+     * the first token is always consumed so the parser makes progress
+     */
+    pub fn recover_until(
+        &mut self,
+        expected: &[TokenKind],
+        context: &str,
+        at_recovery: fn(&mut Parser) -> bool,
+    ) {
+        let found = self.current();
+        let error = ParseError::with_context(
+            self.current_range(),
+            expected.to_vec(),
+            found,
+            Some(context),
+        );
+        self.errors.push(error);
+
+        let depth = self.context.r_curly_count;
+        let m = self.open();
+        while !self.eof() {
+            match self.current() {
+                TokenKind::LCurly => self.inc_rcurly(),
+                TokenKind::RCurly if self.context.r_curly_count > depth => self.dec_rcurly(),
+                _ => {}
+            }
+            self.advance();
+
+            if self.context.r_curly_count == depth && at_recovery(self) {
+                break;
+            }
+        }
+        self.close(m, TokenKind::Error);
+    }
+
     pub fn error_report(&mut self, error: String) {
         let m = self.open();
 
@@ -125,6 +177,7 @@ impl<'a> Parser<'a> {
             context: Context { r_curly_count: 0 },
             fuel: Cell::new(256),
             events: Vec::new(),
+            errors: Vec::new(),
         }
     }
 
@@ -140,11 +193,39 @@ impl<'a> Parser<'a> {
         self.wrap_trivial_tokens()
     }
 
-    pub fn next(&mut self) -> TokenKind {
-        if self.fuel.get() == 0 {
-            panic!("parser is stuck");
+    /**
+     * called once per iteration of a grammar loop which may not consume any token:
+     * after too many iterations on the same token, that token becomes an error
+     * so that the parser moves on instead of looping forever
+     */
+    pub fn ensure_progress(&mut self) {
+        if self.fuel.get() > 0 {
+            self.fuel.set(self.fuel.get() - 1);
+            return;
+        }
+        self.advance_with_error(&[]);
+        self.fuel.set(256);
+    }
+
+    // range of the current token, an empty range at the end of source for EOF
+    pub fn current_range(&mut self) -> Range<usize> {
+        self.current();
+        match self.input.position_of(self.pos) {
+            Some(range) => range,
+            None => {
+                let end = self
+                    .input
+                    .size()
+                    .checked_sub(1)
+                    .and_then(|last| self.input.position_of(last))
+                    .map_or(0, |range| range.end);
+                end..end
+            }
         }
-        self.fuel.set(self.fuel.get() - 1);
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> TokenKind {
         if self.pos < self.input.size() {
             self.pos += 1;
             return self.input.kind_of(self.pos);
@@ -153,6 +234,45 @@ impl<'a> Parser<'a> {
         TokenKind::EOF
     }
 
+    // kind of the n-th non trivial token from the current one, nth(0) == current()
+    pub fn nth(&mut self, n: usize) -> TokenKind {
+        self.current();
+
+        let mut pos = self.pos;
+        let mut remaining = n;
+        loop {
+            let kind = self.input.kind_of(pos);
+            if kind == TokenKind::EOF {
+                return kind;
+            }
+            if !kind.is_trivial() {
+                if remaining == 0 {
+                    return kind;
+                }
+                remaining -= 1;
+            }
+            pos += 1;
+        }
+    }
+
+    // whether a line break separates the current token from the previous one
+    pub fn at_new_line(&mut self) -> bool {
+        self.current();
+
+        let mut pos = self.pos;
+        while pos > 0 {
+            pos -= 1;
+            let kind = self.input.kind_of(pos);
+            if kind == TokenKind::EndLine {
+                return true;
+            }
+            if !kind.is_trivial() {
+                return false;
+            }
+        }
+        false
+    }
+
     pub fn at(&mut self, kind: TokenKind) -> bool {
         self.current() == kind
     }
@@ -197,21 +317,31 @@ impl<'a> Parser<'a> {
     }
 
     pub fn expect_any(&mut self, kinds: &[TokenKind]) {
-        let kind = self.current();
-        if kinds.contains(&kind) {
-            self.advance();
-        } else {
-            let error = format!("expect {:?} but got {:?}", kinds, kind);
-            self.error_report(error);
-        }
+        self.expect_any_with_context(kinds, None);
+    }
+
+    pub fn expect_any_in(&mut self, kinds: &[TokenKind], context: &str) {
+        self.expect_any_with_context(kinds, Some(context));
     }
 
     pub fn expect(&mut self, kind: TokenKind) {
-        if self.at(kind) {
+        self.expect_any_with_context(&[kind], None);
+    }
+
+    /// `context` is the grammar rule being parsed, eg: "in template parameters"
+    pub fn expect_in(&mut self, kind: TokenKind, context: &str) {
+        self.expect_any_with_context(&[kind], Some(context));
+    }
+
+    fn expect_any_with_context(&mut self, kinds: &[TokenKind], context: Option<&str>) {
+        let kind = self.current();
+        if kinds.contains(&kind) {
             self.advance();
         } else {
-            let error = format!("expect {:?} but got {:?}", kind, self.current());
-            self.error_report(error);
+            let error =
+                ParseError::with_context(self.current_range(), kinds.to_vec(), kind, context);
+            self.error_report(error.message.clone());
+            self.errors.push(error);
         }
     }
 
@@ -221,13 +351,13 @@ impl<'a> Parser<'a> {
 }
 
 impl Parser<'_> {
-    pub fn parsing_with_scope(input: &Input, scope: Scope) -> Output {
+    pub fn parsing_with_scope(input: &Input, scope: Scope) -> (Output, Vec<ParseError>) {
         let mut p = Parser::new(input);
         scope.parse(&mut p);
-        Output::from(p.events)
+        (Output::from(p.events), p.errors)
     }
 
-    pub fn parsing(input: &Input) -> Output {
+    pub fn parsing(input: &Input) -> (Output, Vec<ParseError>) {
         let c = Scope::CircomProgram;
         Parser::parsing_with_scope(input, c)
     }
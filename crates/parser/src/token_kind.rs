@@ -29,7 +29,8 @@ pub enum TokenKind {
     #[regex("2.[0-9].[0-9]")]
     Version,
     // Literals
-    #[regex("[0-9]+")]
+    // decimal, hexadecimal (0xFF) and binary (0b101) integers
+    #[regex("[0-9]+|0[xX][0-9a-fA-F]+|0[bB][01]+")]
     Number,
     #[regex("[$_]*[a-zA-Z][a-zA-Z0-9_$]*")]
     Identifier,
@@ -189,28 +190,42 @@ pub enum TokenKind {
     ReturnKw,
     #[token("assert")]
     AssertKw,
+    // generated start
+    // Node kinds, generated by `cargo xtask codegen` from circom.ungram.
+    // Program
+    // items may appear in any order
+    CircomProgram,
+    // Include
+    // eg: include "circomlib/poseidon.circom";
+    Include,
+    // Template
+    TemplateDef,
+    TemplateName,
+    // Function
+    FunctionDef,
+    FunctionName,
+    // Complex token kind
+    ParameterList,
+    Block,
+    StatementList,
     // Statements
     IfStatement,
+    // for (<init>; <condition>; <step>) <body>
+    ForLoop,
+    WhileLoop,
     AssertStatement,
     LogStatement,
     ReturnStatement,
+    // eg: out <== a * b, i++
     AssignStatement,
-    ForLoop,
-    WhileLoop,
-    // Program
-    CircomProgram,
-    // Function
-    FunctionDef,
-    FunctionName,
-    // Template
-    TemplateDef,
-    TemplateName,
     // ComplexIdentifier, which will replace:
     // ___ SignalIdentifier,
     // ___ VarIdentifier,
     // ___ ComponentIdentifier,
+    // eg: a, a[N], a[N][M - 1]
     ComplexIdentifier,
     // Signal
+    // signal input {tag} a, b[N];
     SignalDecl,
     InputSignalDecl,
     OutputSignalDecl,
@@ -218,21 +233,26 @@ pub enum TokenKind {
     // Variable
     VarDecl,
     // Component
+    // component hash = Poseidon(2);
     ComponentDecl,
+    // the signal of a component (AstSignalOfComponent), eg: comp[i].out
     ComponentCall,
-    SignalOfComponent,
+    // Main component
+    // eg: component main {public [a, b]} = Multiplier(2);
+    MainComponent,
+    PublicSignalList,
     // Expression
     ExpressionAtom,
     Expression,
-    // Complex token kind
-    Block,
-    ParameterList,
+    // <callee>(<arguments>), eg: f(a, b)
+    // the template name of a component is outside of the call: T(a, b)
     Call,
+    // <condition> ? <expression-1> : <expression-2>
     TenaryConditional,
     Condition,
-    Statement,
-    StatementList,
+    // eg: a[i]
     ArrayQuery,
+    // generated end
     ParserError,
     BlockComment,
     EOF,
@@ -398,6 +418,30 @@ impl TokenKind {
         )
     }
 
+    // <==, <--, ==>, -->
+    pub fn is_signal_assign_token(self) -> bool {
+        matches!(
+            self,
+            Self::LAssignSignal
+                | Self::LAssignContraintSignal
+                | Self::RAssignSignal
+                | Self::RAssignConstraintSignal
+        )
+    }
+
+    // <==, ==>, ===
+    pub fn is_constraint_token(self) -> bool {
+        matches!(
+            self,
+            Self::LAssignContraintSignal | Self::RAssignConstraintSignal | Self::EqualSignal
+        )
+    }
+
+    // =, +=, -=, ...
+    pub fn is_var_assign_token(self) -> bool {
+        self.is_assign_token() && !self.is_signal_assign_token() && self != Self::EqualSignal
+    }
+
     pub fn is_inline_assign_signal(self) -> bool {
         matches!(
             self,
@@ -415,4 +459,97 @@ impl TokenKind {
             Self::WhiteSpace | Self::EndLine | Self::CommentLine | Self::BlockComment | Self::Error
         )
     }
+
+    // human readable name, used in error messages
+    // eg: expected `;`, found `}`
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Error => "invalid token",
+            Self::CommentLine | Self::BlockComment => "comment",
+            Self::CommentBlockOpen => "`/*`",
+            Self::CommentBlockClose => "`*/`",
+            Self::WhiteSpace => "whitespace",
+            Self::EndLine => "end of line",
+            Self::PragmaKw => "`pragma`",
+            Self::Circom => "`circom`",
+            Self::Version => "version",
+            Self::Number => "number",
+            Self::Identifier => "identifier",
+            Self::CircomString => "string",
+            Self::LParen => "`(`",
+            Self::RParen => "`)`",
+            Self::LCurly => "`{`",
+            Self::RCurly => "`}`",
+            Self::LBracket => "`[`",
+            Self::RBracket => "`]`",
+            Self::Semicolon => "`;`",
+            Self::Comma => "`,`",
+            Self::Dot => "`.`",
+            Self::BoolAnd => "`&&`",
+            Self::BoolOr => "`||`",
+            Self::Not => "`!`",
+            Self::Equal => "`==`",
+            Self::NotEqual => "`!=`",
+            Self::LessThan => "`<`",
+            Self::GreaterThan => "`>`",
+            Self::LessThanAndEqual => "`<=`",
+            Self::GreaterThanAndEqual => "`>=`",
+            Self::Add => "`+`",
+            Self::Sub => "`-`",
+            Self::Mul => "`*`",
+            Self::Power => "`**`",
+            Self::Div => "`/`",
+            Self::IntDiv => "`\\`",
+            Self::Mod => "`%`",
+            Self::AddAssign => "`+=`",
+            Self::SubAssign => "`-=`",
+            Self::MulAssign => "`*=`",
+            Self::PowerAssign => "`**=`",
+            Self::DivAssign => "`/=`",
+            Self::IntDivAssign => "`\\=`",
+            Self::ModAssign => "`%=`",
+            Self::UnitInc => "`++`",
+            Self::UnitDec => "`--`",
+            Self::BitAnd => "`&`",
+            Self::BitOr => "`|`",
+            Self::BitNot => "`~`",
+            Self::BitXor => "`^`",
+            Self::ShiftR => "`>>`",
+            Self::ShiftL => "`<<`",
+            Self::BitAndAssign => "`&=`",
+            Self::BitOrAssign => "`|=`",
+            Self::BitNotAssign => "`~=`",
+            Self::BitXorAssign => "`^=`",
+            Self::ShiftRAssign => "`>>=`",
+            Self::ShiftLAssign => "`<<=`",
+            Self::Assign => "`=`",
+            Self::EqualSignal => "`===`",
+            Self::LAssignSignal => "`-->`",
+            Self::LAssignContraintSignal => "`==>`",
+            Self::RAssignSignal => "`<--`",
+            Self::RAssignConstraintSignal => "`<==`",
+            Self::MarkQuestion => "`?`",
+            Self::Colon => "`:`",
+            Self::TemplateKw => "`template`",
+            Self::FunctionKw => "`function`",
+            Self::ComponentKw => "`component`",
+            Self::MainKw => "`main`",
+            Self::PublicKw => "`public`",
+            Self::SignalKw => "`signal`",
+            Self::VarKw => "`var`",
+            Self::IncludeKw => "`include`",
+            Self::InputKw => "`input`",
+            Self::OutputKw => "`output`",
+            Self::LogKw => "`log`",
+            Self::IfKw => "`if`",
+            Self::ElseKw => "`else`",
+            Self::ForKw => "`for`",
+            Self::WhileKw => "`while`",
+            Self::ReturnKw => "`return`",
+            Self::AssertKw => "`assert`",
+            Self::EOF => "end of file",
+            // node kinds are never produced by the lexer
+            _ => "syntax node",
+        }
+    }
 }
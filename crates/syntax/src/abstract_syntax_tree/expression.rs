@@ -0,0 +1,430 @@
+use parser::token_kind::TokenKind::*;
+
+use crate::syntax_node::CircomLanguage;
+use crate::syntax_node::SyntaxNode;
+use parser::token_kind::TokenKind;
+use rowan::ast::AstNode;
+
+use super::generated::{
+    AstArrayQuery, AstCall, AstComponentCall, AstCondition, AstExpression, AstExpressionAtom,
+    AstTenaryConditional,
+};
+
+// a node which only wraps a token, eg: the `+` in `a + b`
+fn is_token_wrapper(node: &SyntaxNode) -> bool {
+    node.first_child().is_none()
+}
+
+fn non_trivial_children(node: &SyntaxNode) -> Vec<SyntaxNode> {
+    node.children()
+        .filter(|child| !child.kind().is_trivial())
+        .collect()
+}
+
+// `a, b` is parsed as an infix expression, not as two expressions
+fn flatten_comma(expr: AstExpr, result: &mut Vec<AstExpr>) {
+    match expr {
+        AstExpr::Binary(binary) if binary.operator() == Comma => {
+            if let Some(lhs) = binary.lhs() {
+                flatten_comma(lhs, result);
+            }
+            if let Some(rhs) = binary.rhs() {
+                flatten_comma(rhs, result);
+            }
+        }
+        AstExpr::Expression(expression) if !expression.is_parenthesized() => {
+            if let Some(inner) = expression.expr() {
+                flatten_comma(inner, result);
+            }
+        }
+        expr => result.push(expr),
+    }
+}
+
+// eg: [a, b + 1] for `a, b + 1`
+pub(super) fn split_comma(expr: AstExpr) -> Vec<AstExpr> {
+    let mut result = Vec::new();
+    flatten_comma(expr, &mut result);
+    result
+}
+
+impl AstExpression {
+    // (<expression>)
+    pub fn is_parenthesized(&self) -> bool {
+        self.syntax()
+            .children()
+            .any(|child| child.kind() == LParen && is_token_wrapper(&child))
+    }
+
+    /// the wrapped expression, for `(a + b)` this is `a + b`
+    pub fn expr(&self) -> Option<AstExpr> {
+        self.syntax().children().find_map(AstExpr::cast)
+    }
+}
+
+/// <expression> <operator> <expression>, eg: a + b, a == b, a, b
+#[derive(Debug, Clone)]
+pub struct AstBinaryExpression {
+    syntax: SyntaxNode,
+}
+
+impl AstNode for AstBinaryExpression {
+    type Language = CircomLanguage;
+    fn can_cast(token_kind: TokenKind) -> bool {
+        token_kind.infix().is_some()
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if !Self::can_cast(syntax.kind()) {
+            return None;
+        }
+        match non_trivial_children(&syntax).first() {
+            Some(first) if !is_token_wrapper(first) => Some(Self { syntax }),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+
+impl AstBinaryExpression {
+    pub fn lhs(&self) -> Option<AstExpr> {
+        self.syntax().children().find_map(AstExpr::cast)
+    }
+
+    pub fn operator(&self) -> TokenKind {
+        self.syntax().kind()
+    }
+
+    pub fn rhs(&self) -> Option<AstExpr> {
+        self.syntax().children().filter_map(AstExpr::cast).nth(1)
+    }
+}
+
+/// <operator> <expression>, eg: -a, !a, ++a
+#[derive(Debug, Clone)]
+pub struct AstPrefixExpression {
+    syntax: SyntaxNode,
+}
+
+impl AstNode for AstPrefixExpression {
+    type Language = CircomLanguage;
+    fn can_cast(token_kind: TokenKind) -> bool {
+        token_kind.prefix().is_some()
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if !Self::can_cast(syntax.kind()) {
+            return None;
+        }
+        match non_trivial_children(&syntax).first() {
+            Some(first) if first.kind() == syntax.kind() && is_token_wrapper(first) => {
+                Some(Self { syntax })
+            }
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+
+impl AstPrefixExpression {
+    pub fn operator(&self) -> TokenKind {
+        self.syntax().kind()
+    }
+
+    pub fn operand(&self) -> Option<AstExpr> {
+        self.syntax().children().find_map(AstExpr::cast)
+    }
+}
+
+/// <expression> <operator>, eg: a++, a--
+#[derive(Debug, Clone)]
+pub struct AstPostfixExpression {
+    syntax: SyntaxNode,
+}
+
+impl AstNode for AstPostfixExpression {
+    type Language = CircomLanguage;
+    fn can_cast(token_kind: TokenKind) -> bool {
+        matches!(token_kind, UnitInc | UnitDec)
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if !Self::can_cast(syntax.kind()) {
+            return None;
+        }
+        match non_trivial_children(&syntax).first() {
+            Some(first) if !is_token_wrapper(first) => Some(Self { syntax }),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+
+impl AstPostfixExpression {
+    pub fn operator(&self) -> TokenKind {
+        self.syntax().kind()
+    }
+
+    pub fn operand(&self) -> Option<AstExpr> {
+        self.syntax().children().find_map(AstExpr::cast)
+    }
+}
+
+impl AstCondition {
+    pub fn expr(&self) -> Option<AstExpr> {
+        self.syntax().children().find_map(AstExpr::cast)
+    }
+}
+
+/*
+ * <callee>(<arguments>), eg: f(a, b)
+ * in a component declaration (`component c = T(a, b)`) or the main component,
+ * the template name is outside of the Call node and `callee` is None
+ */
+
+impl AstCall {
+    pub fn callee(&self) -> Option<AstExpr> {
+        let first = non_trivial_children(self.syntax()).into_iter().next()?;
+        AstExpr::cast(first)
+    }
+
+    pub fn arguments(&self) -> Vec<AstExpr> {
+        let callee = usize::from(self.callee().is_some());
+        self.syntax()
+            .children()
+            .filter_map(AstExpr::cast)
+            .skip(callee)
+            .flat_map(split_comma)
+            .collect()
+    }
+}
+
+// <array>[<index>], eg: a[i], a[i][j + 1]
+
+impl AstArrayQuery {
+    pub fn array(&self) -> Option<AstExpr> {
+        self.syntax().children().find_map(AstExpr::cast)
+    }
+
+    pub fn index(&self) -> Option<AstExpression> {
+        match self.syntax().children().filter_map(AstExpr::cast).nth(1) {
+            Some(AstExpr::Expression(index)) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// the innermost array and every index, eg: `a` and `[i, j + 1]` for a[i][j + 1]
+    pub fn indices(&self) -> (Option<AstExpr>, Vec<AstExpression>) {
+        let mut indices = Vec::new();
+        let mut query = self.clone();
+        loop {
+            if let Some(index) = query.index() {
+                indices.push(index);
+            }
+            match query.array() {
+                Some(AstExpr::ArrayQuery(inner)) => query = inner,
+                array => {
+                    indices.reverse();
+                    return (array, indices);
+                }
+            }
+        }
+    }
+}
+
+/**
+ * the signal of a component, eg: `comp[i].out`
+ * the parser produces a `ComponentCall` node for it, there is no `SignalOfComponent` kind
+ */
+pub type AstSignalOfComponent = AstComponentCall;
+
+/// any expression
+#[derive(Debug, Clone)]
+pub enum AstExpr {
+    Atom(AstExpressionAtom),
+    Expression(AstExpression),
+    Prefix(AstPrefixExpression),
+    Binary(AstBinaryExpression),
+    Postfix(AstPostfixExpression),
+    Tenary(AstTenaryConditional),
+    Call(AstCall),
+    ArrayQuery(AstArrayQuery),
+    ComponentCall(AstComponentCall),
+}
+
+impl AstNode for AstExpr {
+    type Language = CircomLanguage;
+    fn can_cast(token_kind: TokenKind) -> bool {
+        AstExpressionAtom::can_cast(token_kind)
+            || AstExpression::can_cast(token_kind)
+            || AstPrefixExpression::can_cast(token_kind)
+            || AstBinaryExpression::can_cast(token_kind)
+            || AstPostfixExpression::can_cast(token_kind)
+            || AstTenaryConditional::can_cast(token_kind)
+            || AstCall::can_cast(token_kind)
+            || AstArrayQuery::can_cast(token_kind)
+            || AstComponentCall::can_cast(token_kind)
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        // token wrappers (`+` of a + b, `,`, ...) are not expressions
+        if is_token_wrapper(&syntax) {
+            return None;
+        }
+
+        match syntax.kind() {
+            ExpressionAtom => AstExpressionAtom::cast(syntax).map(Self::Atom),
+            Expression => AstExpression::cast(syntax).map(Self::Expression),
+            TenaryConditional => AstTenaryConditional::cast(syntax).map(Self::Tenary),
+            Call => AstCall::cast(syntax).map(Self::Call),
+            ArrayQuery => AstArrayQuery::cast(syntax).map(Self::ArrayQuery),
+            ComponentCall => AstComponentCall::cast(syntax).map(Self::ComponentCall),
+            _ => AstPrefixExpression::cast(syntax.clone())
+                .map(Self::Prefix)
+                .or_else(|| AstPostfixExpression::cast(syntax.clone()).map(Self::Postfix))
+                .or_else(|| AstBinaryExpression::cast(syntax).map(Self::Binary)),
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::Atom(it) => it.syntax(),
+            Self::Expression(it) => it.syntax(),
+            Self::Prefix(it) => it.syntax(),
+            Self::Binary(it) => it.syntax(),
+            Self::Postfix(it) => it.syntax(),
+            Self::Tenary(it) => it.syntax(),
+            Self::Call(it) => it.syntax(),
+            Self::ArrayQuery(it) => it.syntax(),
+            Self::ComponentCall(it) => it.syntax(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::token_kind::TokenKind;
+    use rowan::ast::AstNode;
+
+    use crate::abstract_syntax_tree::{AstCircomProgram, AstExpr, AstStatement};
+    use crate::syntax::SyntaxTreeBuilder;
+
+    // the right hand side of `x <== <expression>;`
+    fn expr(expression: &str) -> AstExpr {
+        let source = format!("template A() {{ x <== {expression}; }}");
+        let syntax = SyntaxTreeBuilder::syntax_tree(&source);
+        let ast = AstCircomProgram::cast(syntax).unwrap();
+        let statement = ast.template_list()[0]
+            .statements()
+            .unwrap()
+            .statement_list()
+            .into_iter()
+            .next();
+        let Some(AstStatement::AssignStatement(assign)) = statement else {
+            unreachable!()
+        };
+        assign.rhs().unwrap().expr().unwrap()
+    }
+
+    fn text(expr: &AstExpr) -> String {
+        expr.syntax().text().to_string().trim().to_string()
+    }
+
+    #[test]
+    fn operator_test() {
+        let AstExpr::Binary(binary) = expr("a + b * c") else {
+            unreachable!()
+        };
+        assert_eq!(binary.operator(), TokenKind::Add);
+        assert_eq!(text(&binary.lhs().unwrap()), "a");
+        let Some(AstExpr::Binary(rhs)) = binary.rhs() else {
+            unreachable!()
+        };
+        assert_eq!(rhs.operator(), TokenKind::Mul);
+
+        let AstExpr::Prefix(prefix) = expr("-a") else {
+            unreachable!()
+        };
+        assert_eq!(prefix.operator(), TokenKind::Sub);
+        assert_eq!(text(&prefix.operand().unwrap()), "a");
+
+        let AstExpr::Postfix(postfix) = expr("a++") else {
+            unreachable!()
+        };
+        assert_eq!(postfix.operator(), TokenKind::UnitInc);
+        assert_eq!(text(&postfix.operand().unwrap()), "a");
+
+        let AstExpr::Expression(parenthesized) = expr("(a - b)") else {
+            unreachable!()
+        };
+        assert!(parenthesized.is_parenthesized());
+        assert!(matches!(parenthesized.expr(), Some(AstExpr::Binary(_))));
+
+        let AstExpr::Atom(atom) = expr("0xFF") else {
+            unreachable!()
+        };
+        assert_eq!(atom.number().unwrap().value().unwrap(), 255u32.into());
+    }
+
+    #[test]
+    fn tenary_conditional_test() {
+        let AstExpr::Tenary(tenary) = expr("c == 0 ? a : b + 1") else {
+            unreachable!()
+        };
+        let condition = tenary.condition().unwrap().expr().unwrap();
+        assert_eq!(text(&condition), "c == 0");
+        let if_true = AstExpr::Expression(tenary.if_true().unwrap());
+        assert_eq!(text(&if_true), "a");
+        let if_false = AstExpr::Expression(tenary.if_false().unwrap());
+        assert_eq!(text(&if_false), "b + 1");
+    }
+
+    #[test]
+    fn call_test() {
+        let AstExpr::Call(call) = expr("f(a, b + 1, g(c, d))") else {
+            unreachable!()
+        };
+        assert_eq!(text(&call.callee().unwrap()), "f");
+        let arguments: Vec<String> = call.arguments().iter().map(text).collect();
+        assert_eq!(arguments, vec!["a", "b + 1", "g(c, d)"]);
+
+        let AstExpr::Call(call) = expr("f()") else {
+            unreachable!()
+        };
+        assert!(call.arguments().is_empty());
+    }
+
+    #[test]
+    fn array_query_test() {
+        let AstExpr::ArrayQuery(query) = expr("a[i][j + 1]") else {
+            unreachable!()
+        };
+        assert_eq!(query.index().unwrap().syntax().text(), "j + 1");
+
+        let (array, indices) = query.indices();
+        assert_eq!(text(&array.unwrap()), "a");
+        let indices: Vec<String> = indices
+            .iter()
+            .map(|index| index.syntax().text().to_string())
+            .collect();
+        assert_eq!(indices, vec!["i", "j + 1"]);
+
+        let AstExpr::ComponentCall(component_call) = expr("comp[i].out") else {
+            unreachable!()
+        };
+        assert!(matches!(
+            component_call.component(),
+            Some(AstExpr::ArrayQuery(_))
+        ));
+        assert_eq!(component_call.signal().unwrap().syntax().text(), "out");
+    }
+}
@@ -0,0 +1,403 @@
+//! Generated by `cargo xtask codegen` from `crates/syntax/circom.ungram`, do not edit by hand.
+
+use parser::token_kind::TokenKind::*;
+
+use crate::syntax_node::CircomLanguage;
+use crate::syntax_node::SyntaxNode;
+use parser::token_kind::TokenKind;
+use rowan::ast::{support, AstNode};
+
+ast_node!(AstIdentifier, Identifier);
+
+ast_node!(AstNumber, Number);
+
+ast_node!(AstCircomString, CircomString);
+
+ast_node!(AstVersion, Version);
+
+ast_node!(AstCircomProgram, CircomProgram);
+
+impl AstCircomProgram {
+    pub fn pragma(&self) -> Option<AstPragma> {
+        support::child(self.syntax())
+    }
+    pub fn libs(&self) -> Vec<AstInclude> {
+        support::children(self.syntax()).collect()
+    }
+    pub fn template_list(&self) -> Vec<AstTemplateDef> {
+        support::children(self.syntax()).collect()
+    }
+    pub fn function_list(&self) -> Vec<AstFunctionDef> {
+        support::children(self.syntax()).collect()
+    }
+    pub fn main_component(&self) -> Option<AstMainComponent> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstPragma, Pragma);
+
+impl AstPragma {
+    pub fn version(&self) -> Option<AstVersion> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstInclude, Include);
+
+impl AstInclude {
+    pub fn lib(&self) -> Option<AstCircomString> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstTemplateDef, TemplateDef);
+
+impl AstTemplateDef {
+    pub fn name(&self) -> Option<AstTemplateName> {
+        support::child(self.syntax())
+    }
+    pub fn parameter_list(&self) -> Option<AstParameterList> {
+        support::child(self.syntax())
+    }
+    pub fn func_body(&self) -> Option<AstBlock> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstTemplateName, TemplateName);
+
+impl AstTemplateName {
+    pub fn name(&self) -> Option<AstIdentifier> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstFunctionDef, FunctionDef);
+
+impl AstFunctionDef {
+    pub fn function_name(&self) -> Option<AstFunctionName> {
+        support::child(self.syntax())
+    }
+    pub fn argument_list(&self) -> Option<AstParameterList> {
+        support::child(self.syntax())
+    }
+    pub fn body(&self) -> Option<AstBlock> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstFunctionName, FunctionName);
+
+impl AstFunctionName {
+    pub fn name(&self) -> Option<AstIdentifier> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstParameterList, ParameterList);
+
+impl AstParameterList {
+    pub fn parameters(&self) -> Vec<AstIdentifier> {
+        support::children(self.syntax()).collect()
+    }
+}
+
+ast_node!(AstBlock, Block);
+
+impl AstBlock {
+    pub fn statement_list(&self) -> Option<AstStatementList> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstStatementList, StatementList);
+
+impl AstStatementList {
+    pub fn statement_list(&self) -> Vec<AstStatement> {
+        support::children(self.syntax()).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum AstStatement {
+    IfStatement(AstIfStatement),
+    ForLoop(AstForLoop),
+    WhileLoop(AstWhileLoop),
+    AssertStatement(AstAssertStatement),
+    LogStatement(AstLogStatement),
+    ReturnStatement(AstReturnStatement),
+    AssignStatement(AstAssignStatement),
+    Block(AstBlock),
+    VarDecl(AstVarDecl),
+    SignalDecl(AstSignalDecl),
+    InputSignalDecl(AstInputSignalDecl),
+    OutputSignalDecl(AstOutputSignalDecl),
+    ComponentDecl(AstComponentDecl),
+}
+
+impl AstNode for AstStatement {
+    type Language = CircomLanguage;
+    fn can_cast(token_kind: TokenKind) -> bool {
+        matches!(
+            token_kind,
+            IfStatement
+                | ForLoop
+                | WhileLoop
+                | AssertStatement
+                | LogStatement
+                | ReturnStatement
+                | AssignStatement
+                | Block
+                | VarDecl
+                | SignalDecl
+                | InputSignalDecl
+                | OutputSignalDecl
+                | ComponentDecl
+        )
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let node = match syntax.kind() {
+            IfStatement => Self::IfStatement(AstIfStatement::cast(syntax)?),
+            ForLoop => Self::ForLoop(AstForLoop::cast(syntax)?),
+            WhileLoop => Self::WhileLoop(AstWhileLoop::cast(syntax)?),
+            AssertStatement => Self::AssertStatement(AstAssertStatement::cast(syntax)?),
+            LogStatement => Self::LogStatement(AstLogStatement::cast(syntax)?),
+            ReturnStatement => Self::ReturnStatement(AstReturnStatement::cast(syntax)?),
+            AssignStatement => Self::AssignStatement(AstAssignStatement::cast(syntax)?),
+            Block => Self::Block(AstBlock::cast(syntax)?),
+            VarDecl => Self::VarDecl(AstVarDecl::cast(syntax)?),
+            SignalDecl => Self::SignalDecl(AstSignalDecl::cast(syntax)?),
+            InputSignalDecl => Self::InputSignalDecl(AstInputSignalDecl::cast(syntax)?),
+            OutputSignalDecl => Self::OutputSignalDecl(AstOutputSignalDecl::cast(syntax)?),
+            ComponentDecl => Self::ComponentDecl(AstComponentDecl::cast(syntax)?),
+            _ => return None,
+        };
+        Some(node)
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::IfStatement(it) => it.syntax(),
+            Self::ForLoop(it) => it.syntax(),
+            Self::WhileLoop(it) => it.syntax(),
+            Self::AssertStatement(it) => it.syntax(),
+            Self::LogStatement(it) => it.syntax(),
+            Self::ReturnStatement(it) => it.syntax(),
+            Self::AssignStatement(it) => it.syntax(),
+            Self::Block(it) => it.syntax(),
+            Self::VarDecl(it) => it.syntax(),
+            Self::SignalDecl(it) => it.syntax(),
+            Self::InputSignalDecl(it) => it.syntax(),
+            Self::OutputSignalDecl(it) => it.syntax(),
+            Self::ComponentDecl(it) => it.syntax(),
+        }
+    }
+}
+
+ast_node!(AstIfStatement, IfStatement);
+
+impl AstIfStatement {
+    pub fn condition(&self) -> Option<AstExpression> {
+        support::child(self.syntax())
+    }
+    pub fn then_branch(&self) -> Option<AstStatement> {
+        support::child(self.syntax())
+    }
+    pub fn else_branch(&self) -> Option<AstStatement> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+ast_node!(AstForLoop, ForLoop);
+
+impl AstForLoop {
+    pub fn init(&self) -> Option<AstStatement> {
+        support::child(self.syntax())
+    }
+    pub fn condition(&self) -> Option<AstExpression> {
+        support::child(self.syntax())
+    }
+    pub fn step(&self) -> Option<AstStatement> {
+        support::children(self.syntax()).nth(1)
+    }
+    pub fn body(&self) -> Option<AstStatement> {
+        support::children(self.syntax()).nth(2)
+    }
+}
+
+ast_node!(AstWhileLoop, WhileLoop);
+
+impl AstWhileLoop {
+    pub fn condition(&self) -> Option<AstExpression> {
+        support::child(self.syntax())
+    }
+    pub fn body(&self) -> Option<AstStatement> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstAssertStatement, AssertStatement);
+
+impl AstAssertStatement {
+    pub fn condition(&self) -> Option<AstExpression> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstLogStatement, LogStatement);
+
+ast_node!(AstReturnStatement, ReturnStatement);
+
+impl AstReturnStatement {
+    pub fn value(&self) -> Option<AstExpression> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstAssignStatement, AssignStatement);
+
+impl AstAssignStatement {
+    pub fn lhs(&self) -> Option<AstExpression> {
+        support::child(self.syntax())
+    }
+    pub fn rhs(&self) -> Option<AstExpression> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+ast_node!(AstComplexIdentifier, ComplexIdentifier);
+
+impl AstComplexIdentifier {
+    pub fn name(&self) -> Option<AstIdentifier> {
+        support::child(self.syntax())
+    }
+    pub fn dimensions(&self) -> Vec<AstExpression> {
+        support::children(self.syntax()).collect()
+    }
+}
+
+ast_node!(AstSignalDecl, SignalDecl);
+
+impl AstSignalDecl {
+    pub fn signal_header(&self) -> Option<AstSignalHeader> {
+        support::child(self.syntax())
+    }
+    pub fn signal_identifier(&self) -> Option<AstComplexIdentifier> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstInputSignalDecl, InputSignalDecl);
+
+impl AstInputSignalDecl {
+    pub fn signal_header(&self) -> Option<AstSignalHeader> {
+        support::child(self.syntax())
+    }
+    pub fn signal_identifier(&self) -> Option<AstComplexIdentifier> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstOutputSignalDecl, OutputSignalDecl);
+
+impl AstOutputSignalDecl {
+    pub fn signal_header(&self) -> Option<AstSignalHeader> {
+        support::child(self.syntax())
+    }
+    pub fn signal_identifier(&self) -> Option<AstComplexIdentifier> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstSignalHeader, SignalHeader);
+
+impl AstSignalHeader {
+    pub fn tag(&self) -> Option<AstIdentifier> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstVarDecl, VarDecl);
+
+impl AstVarDecl {
+    pub fn var_identifier(&self) -> Option<AstComplexIdentifier> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstComponentDecl, ComponentDecl);
+
+impl AstComponentDecl {
+    pub fn component_identifier(&self) -> Option<AstComplexIdentifier> {
+        support::child(self.syntax())
+    }
+    pub fn template(&self) -> Option<AstTemplateName> {
+        support::child(self.syntax())
+    }
+    pub fn call(&self) -> Option<AstCall> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstComponentCall, ComponentCall);
+
+impl AstComponentCall {
+    pub fn signal(&self) -> Option<AstIdentifier> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstMainComponent, MainComponent);
+
+impl AstMainComponent {
+    pub fn public_signal_list(&self) -> Option<AstPublicSignalList> {
+        support::child(self.syntax())
+    }
+    pub fn template(&self) -> Option<AstTemplateName> {
+        support::child(self.syntax())
+    }
+    pub fn call(&self) -> Option<AstCall> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstPublicSignalList, PublicSignalList);
+
+impl AstPublicSignalList {
+    pub fn signals(&self) -> Vec<AstIdentifier> {
+        support::children(self.syntax()).collect()
+    }
+}
+
+ast_node!(AstExpressionAtom, ExpressionAtom);
+
+impl AstExpressionAtom {
+    pub fn identifier(&self) -> Option<AstIdentifier> {
+        support::child(self.syntax())
+    }
+    pub fn number(&self) -> Option<AstNumber> {
+        support::child(self.syntax())
+    }
+}
+
+ast_node!(AstExpression, Expression);
+
+ast_node!(AstCall, Call);
+
+ast_node!(AstTenaryConditional, TenaryConditional);
+
+impl AstTenaryConditional {
+    pub fn condition(&self) -> Option<AstCondition> {
+        support::child(self.syntax())
+    }
+    pub fn if_true(&self) -> Option<AstExpression> {
+        support::child(self.syntax())
+    }
+    pub fn if_false(&self) -> Option<AstExpression> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+ast_node!(AstCondition, Condition);
+
+ast_node!(AstArrayQuery, ArrayQuery);
@@ -29,7 +29,11 @@ macro_rules! ast_node {
 }
 
 pub mod ast;
+pub mod expression;
+pub mod generated;
+pub mod statement;
 pub mod template;
 
-pub use ast::*;
-pub use template::*;
+pub use expression::*;
+pub use generated::*;
+pub use statement::*;
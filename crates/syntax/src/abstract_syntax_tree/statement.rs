@@ -0,0 +1,197 @@
+use parser::token_kind::TokenKind;
+use rowan::ast::AstNode;
+
+use super::expression::{split_comma, AstExpr};
+use super::generated::{AstAssignStatement, AstCircomString, AstLogStatement};
+
+#[derive(Debug, Clone)]
+pub enum AstLogArgument {
+    String(AstCircomString),
+    Expression(AstExpr),
+}
+
+impl AstLogStatement {
+    // eg: "a =" and a in log("a =", a)
+    pub fn arguments(&self) -> Vec<AstLogArgument> {
+        let mut result = Vec::new();
+        for child in self.syntax().children() {
+            if let Some(string) = AstCircomString::cast(child.clone()) {
+                result.push(AstLogArgument::String(string));
+            } else if let Some(expr) = AstExpr::cast(child) {
+                result.extend(
+                    split_comma(expr)
+                        .into_iter()
+                        .map(AstLogArgument::Expression),
+                );
+            }
+        }
+        result
+    }
+}
+
+impl AstAssignStatement {
+    // <lhs> <operator> <rhs>, eg: out <== a * b
+    // a statement without operator is a single expression, eg: i++
+    pub fn operator(&self) -> Option<TokenKind> {
+        self.syntax()
+            .children()
+            .map(|child| child.kind())
+            .find(|kind| kind.is_assign_token())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::token_kind::TokenKind;
+    use rowan::ast::AstNode;
+
+    use crate::abstract_syntax_tree::{AstCircomProgram, AstExpr, AstLogArgument, AstStatement};
+    use crate::syntax::SyntaxTreeBuilder;
+
+    fn statements(body: &str) -> Vec<AstStatement> {
+        let source = format!("template A() {{\n{body}\n}}");
+        let syntax = SyntaxTreeBuilder::syntax_tree(&source);
+        let ast = AstCircomProgram::cast(syntax).unwrap();
+        ast.template_list()[0]
+            .statements()
+            .unwrap()
+            .statement_list()
+    }
+
+    fn text<N: AstNode>(node: Option<N>) -> String {
+        node.unwrap().syntax().text().to_string().trim().to_string()
+    }
+
+    #[test]
+    fn statement_kinds_test() {
+        let statements = statements(
+            r#"
+    signal input a;
+    var x = 1;
+    component c = T(1);
+    if (a > 0) x = 1; else x = 2;
+    for (var i = 0; i < 2; i++) { x += i; }
+    while (x > 0) x--;
+    assert(x == 0);
+    log("x =", x, a);
+    a === x;
+    { x = 3; }"#,
+        );
+
+        let kinds: Vec<&str> = statements
+            .iter()
+            .map(|statement| match statement {
+                AstStatement::IfStatement(_) => "if",
+                AstStatement::ForLoop(_) => "for",
+                AstStatement::WhileLoop(_) => "while",
+                AstStatement::AssertStatement(_) => "assert",
+                AstStatement::LogStatement(_) => "log",
+                AstStatement::ReturnStatement(_) => "return",
+                AstStatement::AssignStatement(_) => "assign",
+                AstStatement::Block(_) => "block",
+                AstStatement::VarDecl(_) => "var",
+                AstStatement::SignalDecl(_) => "signal",
+                AstStatement::InputSignalDecl(_) => "input",
+                AstStatement::OutputSignalDecl(_) => "output",
+                AstStatement::ComponentDecl(_) => "component",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "input",
+                "var",
+                "component",
+                "if",
+                "for",
+                "while",
+                "assert",
+                "log",
+                "assign",
+                "block"
+            ]
+        );
+
+        let AstStatement::ComponentDecl(component) = &statements[2] else {
+            unreachable!()
+        };
+        assert_eq!(component.call().unwrap().arguments().len(), 1);
+    }
+
+    #[test]
+    fn control_flow_test() {
+        let statements = statements(
+            r#"
+    if (a > 0) x = 1; else x = 2;
+    for (var i = 0; i < 2; i++) { x += i; }
+    while (x > 0) x--;
+    assert(x == 0);"#,
+        );
+
+        let AstStatement::IfStatement(if_statement) = &statements[0] else {
+            unreachable!()
+        };
+        assert_eq!(text(if_statement.condition()), "a > 0");
+        assert_eq!(text(if_statement.then_branch()), "x = 1");
+        assert_eq!(text(if_statement.else_branch()), "x = 2");
+
+        let AstStatement::ForLoop(for_loop) = &statements[1] else {
+            unreachable!()
+        };
+        assert!(matches!(for_loop.init(), Some(AstStatement::VarDecl(_))));
+        assert_eq!(text(for_loop.condition()), "i < 2");
+        assert_eq!(text(for_loop.step()), "i++");
+        assert!(matches!(for_loop.body(), Some(AstStatement::Block(_))));
+
+        let AstStatement::WhileLoop(while_loop) = &statements[2] else {
+            unreachable!()
+        };
+        assert_eq!(text(while_loop.condition()), "x > 0");
+        assert_eq!(text(while_loop.body()), "x--");
+
+        let AstStatement::AssertStatement(assert) = &statements[3] else {
+            unreachable!()
+        };
+        assert_eq!(text(assert.condition()), "x == 0");
+    }
+
+    #[test]
+    fn assign_and_log_test() {
+        let statements = statements(
+            r#"
+    out[i] <== a * b;
+    i++;
+    log("x =", x, a + 1);"#,
+        );
+
+        let AstStatement::AssignStatement(assign) = &statements[0] else {
+            unreachable!()
+        };
+        assert_eq!(text(assign.lhs()), "out[i]");
+        assert_eq!(assign.operator(), Some(TokenKind::RAssignConstraintSignal));
+        assert_eq!(text(assign.rhs()), "a * b");
+
+        let AstStatement::AssignStatement(increment) = &statements[1] else {
+            unreachable!()
+        };
+        assert_eq!(increment.operator(), None);
+        assert!(increment.rhs().is_none());
+
+        let AstStatement::LogStatement(log) = &statements[2] else {
+            unreachable!()
+        };
+        let arguments: Vec<String> = log
+            .arguments()
+            .into_iter()
+            .map(|argument| match argument {
+                AstLogArgument::String(string) => string.value(),
+                AstLogArgument::Expression(expr) => expr.syntax().text().to_string(),
+            })
+            .collect();
+        assert_eq!(arguments, vec!["x =", "x", "a + 1"]);
+        assert!(matches!(
+            log.arguments()[2],
+            AstLogArgument::Expression(AstExpr::Binary(_))
+        ));
+    }
+}
@@ -0,0 +1,965 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+use parser::token_kind::TokenKind::{self, *};
+use rowan::ast::AstNode;
+
+use crate::abstract_syntax_tree::{
+    AstCall, AstCircomProgram, AstComplexIdentifier, AstExpr, AstExpression, AstFunctionDef,
+    AstStatement, AstTemplateDef, AstVarDecl,
+};
+use crate::field::{FieldElement, Prime};
+use crate::syntax_node::SyntaxNode;
+
+/// statements and loop iterations executed before an evaluation is given up
+pub const DEFAULT_STEP_LIMIT: usize = 100_000;
+
+// nested function calls, recursive functions are rare in circom
+const MAX_CALL_DEPTH: usize = 128;
+
+/// value of a compile-time expression: a field element or an array
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Field(FieldElement),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_field(&self) -> Option<&FieldElement> {
+        match self {
+            Value::Field(element) => Some(element),
+            Value::Array(_) => None,
+        }
+    }
+
+    /// a field element which fits an index or a size
+    pub fn to_usize(&self) -> Option<usize> {
+        self.as_field()?.value().to_usize()
+    }
+
+    fn is_true(&self) -> Option<bool> {
+        self.as_field().map(|element| !element.is_zero())
+    }
+
+    fn get(&self, index: usize) -> Option<&Value> {
+        match self {
+            Value::Array(values) => values.get(index),
+            Value::Field(_) => None,
+        }
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut Value> {
+        match self {
+            Value::Array(values) => values.get_mut(index),
+            Value::Field(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Field(element) => write!(f, "{element}"),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// values of the template parameters and variables in scope
+pub type Bindings = HashMap<String, Value>;
+
+enum Flow {
+    Next,
+    Return(Value),
+}
+
+/// a component created while a template is run, eg: `c[i] = Num2Bits(i + 1)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instantiation {
+    pub template: String,
+    pub arguments: Vec<Value>,
+}
+
+/// the body of a template run with known parameters
+#[derive(Debug, Default)]
+pub struct TemplateRun {
+    /// values of the parameters and of the variables which are assigned once
+    pub bindings: Bindings,
+    /// components whose arguments are known, in the order they are created
+    pub instantiations: Vec<Instantiation>,
+    /// `<==`, `==>` and `===` executed by the body, without those of its components,
+    /// None if a loop or a condition around constraints or components is not known
+    pub constraints: Option<usize>,
+}
+
+// state of the template being run
+struct RunState {
+    // variable --> number of times it is assigned
+    assignments: HashMap<String, usize>,
+    instantiations: Vec<Instantiation>,
+    constraints: usize,
+    // false once a constraint or a component is skipped
+    complete: bool,
+}
+
+impl Default for RunState {
+    fn default() -> Self {
+        Self {
+            assignments: HashMap::new(),
+            instantiations: Vec::new(),
+            constraints: 0,
+            complete: true,
+        }
+    }
+}
+
+/// the operator of a compound assignment, eg: `+` for `+=`
+pub fn compound_operator(kind: TokenKind) -> Option<TokenKind> {
+    match kind {
+        AddAssign => Some(Add),
+        SubAssign => Some(Sub),
+        MulAssign => Some(Mul),
+        PowerAssign => Some(Power),
+        DivAssign => Some(Div),
+        IntDivAssign => Some(IntDiv),
+        ModAssign => Some(Mod),
+        BitAndAssign => Some(BitAnd),
+        BitOrAssign => Some(BitOr),
+        BitXorAssign => Some(BitXor),
+        ShiftLAssign => Some(ShiftL),
+        ShiftRAssign => Some(ShiftR),
+        _ => None,
+    }
+}
+
+fn strip_parentheses(expr: AstExpr) -> Option<AstExpr> {
+    match expr {
+        AstExpr::Expression(expression) => strip_parentheses(expression.expr()?),
+        expr => Some(expr),
+    }
+}
+
+// `a[i][j]` --> a, [i, j]
+fn assignment_target(expr: AstExpr) -> Option<(String, Vec<AstExpression>)> {
+    match strip_parentheses(expr)? {
+        AstExpr::Atom(atom) => Some((atom.identifier()?.syntax().text().to_string(), Vec::new())),
+        AstExpr::ArrayQuery(query) => {
+            let (array, indices) = query.indices();
+            let AstExpr::Atom(atom) = strip_parentheses(array?)? else {
+                return None;
+            };
+            Some((atom.identifier()?.syntax().text().to_string(), indices))
+        }
+        _ => None,
+    }
+}
+
+// variables which may be changed by a statement
+fn assigned_names(node: &SyntaxNode) -> Vec<String> {
+    let mut result = Vec::new();
+    for node in node.descendants() {
+        if let Some(statement) = AstStatement::cast(node.clone()) {
+            match statement {
+                AstStatement::AssignStatement(assign) => {
+                    let target = assign.lhs().and_then(|lhs| lhs.expr()).and_then(|lhs| {
+                        match strip_parentheses(lhs)? {
+                            AstExpr::Prefix(prefix) => prefix.operand(),
+                            AstExpr::Postfix(postfix) => postfix.operand(),
+                            lhs => Some(lhs),
+                        }
+                    });
+                    result.extend(target.and_then(assignment_target).map(|(name, _)| name));
+                }
+                AstStatement::VarDecl(declaration) => result.extend(
+                    declaration
+                        .syntax()
+                        .children()
+                        .filter_map(AstComplexIdentifier::cast)
+                        .filter_map(|identifier| identifier.name())
+                        .map(|name| name.syntax().text().to_string()),
+                ),
+                _ => {}
+            }
+        }
+    }
+    result
+}
+
+/**
+ * evaluates expressions known at compile time over the field of a prime,
+ * with calls to the functions of a program and its included libs
+ * eg: nbits(n) + 1 with n = 8 --> 5
+ *
+ * an expression which depends on signals, unknown names or
+ * runs for more than `step_limit` statements and loop iterations has no value
+ */
+pub struct ConstEvaluator {
+    zero: FieldElement,
+    // 2^b - 1, b = number of bits of the prime
+    mask: BigUint,
+    functions: HashMap<String, AstFunctionDef>,
+    step_limit: usize,
+    // steps left in the current evaluation
+    steps: usize,
+    depth: usize,
+    // in a template body, unknown values make variables unknown instead of failing
+    template: Option<RunState>,
+}
+
+impl ConstEvaluator {
+    pub fn new<'a>(
+        programs: impl IntoIterator<Item = &'a AstCircomProgram>,
+        prime: &Prime,
+    ) -> Self {
+        let mut functions = HashMap::new();
+        for program in programs {
+            for function in program.function_list() {
+                if let Some(name) = function.function_name() {
+                    functions
+                        .entry(name.syntax().text().to_string())
+                        .or_insert(function);
+                }
+            }
+        }
+
+        let zero = FieldElement::new(BigUint::default(), prime);
+        let mask = (BigUint::one() << zero.modulus().bits()) - 1u32;
+        Self {
+            zero,
+            mask,
+            functions,
+            step_limit: DEFAULT_STEP_LIMIT,
+            steps: DEFAULT_STEP_LIMIT,
+            depth: 0,
+            template: None,
+        }
+    }
+
+    pub fn with_step_limit(mut self, step_limit: usize) -> Self {
+        self.step_limit = step_limit;
+        self
+    }
+
+    pub fn number(&self, value: impl Into<BigUint>) -> Value {
+        Value::Field(self.zero.with_value(value.into()))
+    }
+
+    fn boolean(&self, value: bool) -> Value {
+        self.number(u32::from(value))
+    }
+
+    fn step(&mut self) -> Option<()> {
+        self.steps = self.steps.checked_sub(1)?;
+        Some(())
+    }
+
+    fn binary(&self, operator: TokenKind, lhs: &Value, rhs: &Value) -> Option<Value> {
+        match operator {
+            Equal => return Some(self.boolean(lhs == rhs)),
+            NotEqual => return Some(self.boolean(lhs != rhs)),
+            _ => {}
+        }
+
+        let (a, b) = (lhs.as_field()?, rhs.as_field()?);
+        let (x, y) = (a.value(), b.value());
+        let element = match operator {
+            Add => a.add(b),
+            Sub => a.sub(b),
+            Mul => a.mul(b),
+            Power => a.pow(b),
+            Div => a.div(b)?,
+            IntDiv if !b.is_zero() => self.zero.with_value(x / y),
+            Mod if !b.is_zero() => self.zero.with_value(x % y),
+            LessThan => return Some(self.boolean(a.signed() < b.signed())),
+            GreaterThan => return Some(self.boolean(a.signed() > b.signed())),
+            LessThanAndEqual => return Some(self.boolean(a.signed() <= b.signed())),
+            GreaterThanAndEqual => return Some(self.boolean(a.signed() >= b.signed())),
+            BoolAnd => return Some(self.boolean(!a.is_zero() && !b.is_zero())),
+            BoolOr => return Some(self.boolean(!a.is_zero() || !b.is_zero())),
+            BitAnd => self.zero.with_value(x & y),
+            BitOr => self.zero.with_value(x | y),
+            BitXor => self.zero.with_value(x ^ y),
+            ShiftL | ShiftR => {
+                // a shift by a negative amount is a shift in the other direction
+                let (left, amount) = match b.signed().to_i64()? {
+                    amount if amount < 0 => (operator == ShiftR, amount.unsigned_abs()),
+                    amount => (operator == ShiftL, amount.unsigned_abs()),
+                };
+                // every bit is shifted out past the size of the prime
+                let amount = amount.min(self.mask.bits());
+                if left {
+                    self.zero.with_value((x << amount) & &self.mask)
+                } else {
+                    self.zero.with_value(x >> amount)
+                }
+            }
+            _ => return None,
+        };
+        Some(Value::Field(element))
+    }
+
+    /// value of an expression, None if it is not known at compile time
+    pub fn evaluate(&mut self, expr: &AstExpr, bindings: &Bindings) -> Option<Value> {
+        self.steps = self.step_limit;
+        self.eval(expr, bindings)
+    }
+
+    /// value returned by the function `name`
+    pub fn call(&mut self, name: &str, arguments: Vec<Value>) -> Option<Value> {
+        self.steps = self.step_limit;
+        self.call_function(name, arguments)
+    }
+
+    fn eval(&mut self, expr: &AstExpr, bindings: &Bindings) -> Option<Value> {
+        match expr {
+            AstExpr::Atom(atom) => {
+                if let Some(number) = atom.number() {
+                    return Some(self.number(number.value()?));
+                }
+                bindings
+                    .get(&atom.identifier()?.syntax().text().to_string())
+                    .cloned()
+            }
+            AstExpr::Expression(expression) => self.eval(&expression.expr()?, bindings),
+            AstExpr::Binary(binary) => {
+                let operator = binary.operator();
+                let lhs = self.eval(&binary.lhs()?, bindings)?;
+                // `&&` and `||` are short-circuit
+                match (operator, lhs.is_true()) {
+                    (BoolAnd, Some(false)) => return Some(self.boolean(false)),
+                    (BoolOr, Some(true)) => return Some(self.boolean(true)),
+                    _ => {}
+                }
+                let rhs = self.eval(&binary.rhs()?, bindings)?;
+                self.binary(operator, &lhs, &rhs)
+            }
+            AstExpr::Prefix(prefix) => {
+                let operand = self.eval(&prefix.operand()?, bindings)?;
+                let element = operand.as_field()?;
+                match prefix.operator() {
+                    Sub => Some(Value::Field(element.neg())),
+                    Add => Some(operand.clone()),
+                    Not => Some(self.boolean(element.is_zero())),
+                    BitNot => Some(Value::Field(
+                        self.zero.with_value(element.value() ^ &self.mask),
+                    )),
+                    _ => None,
+                }
+            }
+            // ++ and -- are statements, they are executed by `execute`
+            AstExpr::Postfix(_) => None,
+            AstExpr::Tenary(tenary) => {
+                let condition = self.eval(&tenary.condition()?.expr()?, bindings)?;
+                let branch = if condition.is_true()? {
+                    tenary.if_true()
+                } else {
+                    tenary.if_false()
+                };
+                self.eval(&branch?.expr()?, bindings)
+            }
+            AstExpr::Call(call) => {
+                let Some(AstExpr::Atom(callee)) = call.callee() else {
+                    return None;
+                };
+                let name = callee.identifier()?.syntax().text().to_string();
+                let mut arguments = Vec::new();
+                for argument in call.arguments() {
+                    arguments.push(self.eval(&argument, bindings)?);
+                }
+                self.call_function(&name, arguments)
+            }
+            AstExpr::ArrayQuery(query) => {
+                let array = self.eval(&query.array()?, bindings)?;
+                let index = self.eval(&query.index()?.expr()?, bindings)?;
+                array.get(index.to_usize()?).cloned()
+            }
+            // signals of components are never known
+            AstExpr::ComponentCall(_) => None,
+        }
+    }
+
+    fn call_function(&mut self, name: &str, arguments: Vec<Value>) -> Option<Value> {
+        let function = self.functions.get(name)?.clone();
+        let parameters = function.argument_list()?.parameters();
+        if parameters.len() != arguments.len() || self.depth >= MAX_CALL_DEPTH {
+            return None;
+        }
+        self.step()?;
+
+        let mut bindings: Bindings = parameters
+            .iter()
+            .map(|parameter| parameter.syntax().text().to_string())
+            .zip(arguments)
+            .collect();
+
+        // a function always needs known values, even inside a template
+        let template = self.template.take();
+        self.depth += 1;
+        let result = function.body().and_then(|body| {
+            self.execute_block(&body.statement_list()?.statement_list(), &mut bindings)
+        });
+        self.depth -= 1;
+        self.template = template;
+
+        match result? {
+            Flow::Return(value) => Some(value),
+            Flow::Next => None,
+        }
+    }
+
+    /**
+     * run the body of a template with the values of its parameters,
+     * variables which depend on signals (or on unknown conditions) are unknown
+     */
+    pub fn run_template(
+        &mut self,
+        template: &AstTemplateDef,
+        arguments: Vec<Value>,
+    ) -> Option<TemplateRun> {
+        let parameters = template.parameter_list()?.parameters();
+        if parameters.len() != arguments.len() {
+            return None;
+        }
+        let mut bindings: Bindings = parameters
+            .iter()
+            .map(|parameter| parameter.syntax().text().to_string())
+            .zip(arguments)
+            .collect();
+
+        self.steps = self.step_limit;
+        let outer = self.template.replace(RunState::default());
+        let result = self.execute_block(&template.statements()?.statement_list(), &mut bindings);
+        let state = std::mem::replace(&mut self.template, outer)?;
+        result?;
+
+        // the value of a variable assigned in a loop depends on the iteration
+        bindings.retain(|name, _| state.assignments.get(name).copied().unwrap_or(0) <= 1);
+        Some(TemplateRun {
+            bindings,
+            instantiations: state.instantiations,
+            constraints: state.complete.then_some(state.constraints),
+        })
+    }
+
+    fn count_constraint(&mut self) {
+        if let Some(state) = &mut self.template {
+            state.constraints += 1;
+        }
+    }
+
+    fn count_assignment(&mut self, name: &str) {
+        if let Some(state) = &mut self.template {
+            *state.assignments.entry(name.to_string()).or_default() += 1;
+        }
+    }
+
+    // the template of a call which creates a component, eg: `T` in c = T(1) or T(1)(a)
+    fn instantiated_template(&self, call: &AstCall) -> Option<String> {
+        let Some(AstExpr::Atom(callee)) = call.callee() else {
+            return None;
+        };
+        let name = callee.identifier()?.syntax().text().to_string();
+        (!self.functions.contains_key(&name)).then_some(name)
+    }
+
+    // T(...) in `c = T(...)` or an anonymous component `T(...)(...)`, returns true if found
+    fn record_instantiations(&mut self, node: &SyntaxNode, bindings: &Bindings) -> bool {
+        let mut found = false;
+        for call in node.descendants().filter_map(AstCall::cast) {
+            let Some(name) = self.instantiated_template(&call) else {
+                continue;
+            };
+            found = true;
+            self.record_instantiation(name, call.arguments(), bindings);
+        }
+        found
+    }
+
+    fn record_instantiation(
+        &mut self,
+        template: String,
+        arguments: Vec<AstExpr>,
+        bindings: &Bindings,
+    ) {
+        let mut values = Vec::new();
+        for argument in arguments {
+            let Some(value) = self.eval(&argument, bindings) else {
+                if let Some(state) = &mut self.template {
+                    state.complete = false;
+                }
+                return;
+            };
+            values.push(value);
+        }
+        if let Some(state) = &mut self.template {
+            state.instantiations.push(Instantiation {
+                template,
+                arguments: values,
+            });
+        }
+    }
+
+    fn execute_block(
+        &mut self,
+        statements: &[AstStatement],
+        bindings: &mut Bindings,
+    ) -> Option<Flow> {
+        for statement in statements {
+            if let Flow::Return(value) = self.execute(statement, bindings)? {
+                return Some(Flow::Return(value));
+            }
+        }
+        Some(Flow::Next)
+    }
+
+    // in a template: the variables changed by a statement which can not be run are unknown
+    fn skip(&mut self, statement: &AstStatement, bindings: &mut Bindings) -> Option<Flow> {
+        self.template.as_ref()?;
+        for name in assigned_names(statement.syntax()) {
+            bindings.remove(&name);
+        }
+
+        // the number of constraints and components of the statement is not known
+        let has_constraints = statement.syntax().descendants().any(|node| {
+            node.kind().is_constraint_token()
+                || node.kind() == ComponentDecl
+                || AstCall::cast(node)
+                    .is_some_and(|call| self.instantiated_template(&call).is_some())
+        });
+        if let Some(state) = self.template.as_mut().filter(|_| has_constraints) {
+            state.complete = false;
+        }
+        Some(Flow::Next)
+    }
+
+    fn condition(&mut self, condition: Option<AstExpression>, bindings: &Bindings) -> Option<bool> {
+        self.eval(&condition?.expr()?, bindings)?.is_true()
+    }
+
+    fn execute(&mut self, statement: &AstStatement, bindings: &mut Bindings) -> Option<Flow> {
+        self.step()?;
+
+        match statement {
+            AstStatement::Block(block) => {
+                self.execute_block(&block.statement_list()?.statement_list(), bindings)
+            }
+            AstStatement::VarDecl(declaration) => {
+                if self.declare(declaration, bindings).is_none() {
+                    return self.skip(statement, bindings);
+                }
+                Some(Flow::Next)
+            }
+            AstStatement::AssignStatement(assign) => {
+                if self.template.is_some() {
+                    let instantiation = self.record_instantiations(assign.syntax(), bindings);
+                    let operator = assign.operator();
+                    if operator.is_some_and(TokenKind::is_constraint_token) {
+                        self.count_constraint();
+                    }
+                    let is_signal = operator.is_some_and(|operator| {
+                        operator.is_signal_assign_token() || operator == EqualSignal
+                    });
+                    if instantiation || is_signal {
+                        return Some(Flow::Next);
+                    }
+                }
+                if self
+                    .assign(assign.lhs(), assign.operator(), assign.rhs(), bindings)
+                    .is_none()
+                {
+                    return self.skip(statement, bindings);
+                }
+                Some(Flow::Next)
+            }
+            AstStatement::IfStatement(if_statement) => {
+                let Some(condition) = self.condition(if_statement.condition(), bindings) else {
+                    return self.skip(statement, bindings);
+                };
+                let branch = if condition {
+                    if_statement.then_branch()
+                } else {
+                    if_statement.else_branch()
+                };
+                match branch {
+                    Some(branch) => self.execute(&branch, bindings),
+                    None => Some(Flow::Next),
+                }
+            }
+            AstStatement::ForLoop(for_loop) => {
+                if let Some(init) = for_loop.init() {
+                    self.execute(&init, bindings)?;
+                }
+                loop {
+                    let Some(condition) = self.condition(for_loop.condition(), bindings) else {
+                        return self.skip(statement, bindings);
+                    };
+                    if !condition {
+                        return Some(Flow::Next);
+                    }
+                    if let Flow::Return(value) = self.execute(&for_loop.body()?, bindings)? {
+                        return Some(Flow::Return(value));
+                    }
+                    self.execute(&for_loop.step()?, bindings)?;
+                }
+            }
+            AstStatement::WhileLoop(while_loop) => loop {
+                let Some(condition) = self.condition(while_loop.condition(), bindings) else {
+                    return self.skip(statement, bindings);
+                };
+                if !condition {
+                    return Some(Flow::Next);
+                }
+                if let Flow::Return(value) = self.execute(&while_loop.body()?, bindings)? {
+                    return Some(Flow::Return(value));
+                }
+            },
+            AstStatement::ReturnStatement(return_statement) => {
+                let value = self.eval(&return_statement.value()?.expr()?, bindings)?;
+                Some(Flow::Return(value))
+            }
+            // nothing to compute
+            AstStatement::AssertStatement(_) | AstStatement::LogStatement(_) => Some(Flow::Next),
+            AstStatement::ComponentDecl(declaration) => {
+                self.template.as_ref()?;
+                if let (Some(template), Some(call)) = (declaration.template(), declaration.call()) {
+                    let name = template.syntax().text().to_string();
+                    self.record_instantiation(name, call.arguments(), bindings);
+                }
+                Some(Flow::Next)
+            }
+            AstStatement::SignalDecl(_)
+            | AstStatement::InputSignalDecl(_)
+            | AstStatement::OutputSignalDecl(_) => {
+                self.template.as_ref()?;
+                self.record_instantiations(statement.syntax(), bindings);
+                // signal output b <== a;
+                if statement
+                    .syntax()
+                    .children()
+                    .any(|child| child.kind().is_constraint_token())
+                {
+                    self.count_constraint();
+                }
+                Some(Flow::Next)
+            }
+        }
+    }
+
+    fn zeros(&self, dimensions: &[usize]) -> Value {
+        match dimensions.split_first() {
+            Some((0, _)) => Value::Array(Vec::new()),
+            Some((size, rest)) => Value::Array(vec![self.zeros(rest); *size]),
+            None => self.number(0u32),
+        }
+    }
+
+    // an array of zeros, each of its elements costs a step
+    fn allocate(&mut self, dimensions: &[usize]) -> Option<Value> {
+        let elements = dimensions
+            .iter()
+            .try_fold(1usize, |elements, size| elements.checked_mul(*size))?;
+        self.steps = self.steps.checked_sub(elements)?;
+        Some(self.zeros(dimensions))
+    }
+
+    // var a[N] = ..., b; every variable starts at 0
+    fn declare(&mut self, declaration: &AstVarDecl, bindings: &mut Bindings) -> Option<()> {
+        let mut declared = None;
+        for child in declaration.syntax().children() {
+            if let Some(identifier) = AstComplexIdentifier::cast(child.clone()) {
+                let name = identifier.name()?.syntax().text().to_string();
+                let mut dimensions = Vec::new();
+                for dimension in identifier.dimensions() {
+                    dimensions.push(self.eval(&dimension.expr()?, bindings)?.to_usize()?);
+                }
+                self.count_assignment(&name);
+                let value = self.allocate(&dimensions)?;
+                bindings.insert(name.clone(), value);
+                declared = Some(name);
+            } else if let Some(value) = AstExpression::cast(child) {
+                let value = self.eval(&value.expr()?, bindings)?;
+                bindings.insert(declared.clone()?, value);
+            }
+        }
+        Some(())
+    }
+
+    // a = b, a[i] += b, a++, ...
+    fn assign(
+        &mut self,
+        lhs: Option<AstExpression>,
+        operator: Option<TokenKind>,
+        rhs: Option<AstExpression>,
+        bindings: &mut Bindings,
+    ) -> Option<()> {
+        let lhs = strip_parentheses(lhs?.expr()?)?;
+
+        let (target, update) = match operator {
+            Some(Assign) => (lhs, None),
+            Some(operator) => (lhs, Some(compound_operator(operator)?)),
+            // i++, --i
+            None => match lhs {
+                AstExpr::Postfix(postfix) => (postfix.operand()?, Some(postfix.operator())),
+                AstExpr::Prefix(prefix) if matches!(prefix.operator(), UnitInc | UnitDec) => {
+                    (prefix.operand()?, Some(prefix.operator()))
+                }
+                _ => return None,
+            },
+        };
+
+        let (name, index_expressions) = assignment_target(target)?;
+        self.count_assignment(&name);
+        let mut indices = Vec::new();
+        for index in index_expressions {
+            indices.push(self.eval(&index.expr()?, bindings)?.to_usize()?);
+        }
+
+        let new_value = match update {
+            Some(UnitInc) => self.binary(
+                Add,
+                &self.current(bindings, &name, &indices)?,
+                &self.number(1u32),
+            )?,
+            Some(UnitDec) => self.binary(
+                Sub,
+                &self.current(bindings, &name, &indices)?,
+                &self.number(1u32),
+            )?,
+            Some(operator) => {
+                let value = self.eval(&rhs?.expr()?, bindings)?;
+                self.binary(operator, &self.current(bindings, &name, &indices)?, &value)?
+            }
+            None => self.eval(&rhs?.expr()?, bindings)?,
+        };
+
+        let mut slot = bindings.get_mut(&name)?;
+        for index in indices {
+            slot = slot.get_mut(index)?;
+        }
+        *slot = new_value;
+        Some(())
+    }
+
+    fn current(&self, bindings: &Bindings, name: &str, indices: &[usize]) -> Option<Value> {
+        let mut value = bindings.get(name)?;
+        for index in indices {
+            value = value.get(*index)?;
+        }
+        Some(value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rowan::ast::AstNode;
+
+    use crate::abstract_syntax_tree::AstCircomProgram;
+    use crate::field::Prime;
+    use crate::syntax::SyntaxTreeBuilder;
+
+    use super::ConstEvaluator;
+
+    const FUNCTIONS: &str = r#"
+function nbits(a) {
+    var n = 1;
+    var r = 0;
+    while (n - 1 < a) {
+        r++;
+        n *= 2;
+    }
+    return r;
+}
+
+function fib(n) {
+    var f[10];
+    f[1] = 1;
+    for (var i = 2; i < n; i++) {
+        f[i] = f[i - 1] + f[i - 2];
+    }
+    return f[n - 1];
+}
+
+function forever() {
+    while (1) {}
+    return 0;
+}
+
+function huge() {
+    var x[100000000000];
+    return 0;
+}
+
+function empty() {
+    var x[0][100000000000];
+    return 1;
+}
+"#;
+
+    fn program(source: &str) -> AstCircomProgram {
+        AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap()
+    }
+
+    // value of `expression` in a function, with `n` = 8
+    fn evaluate(expression: &str) -> Option<String> {
+        let source = format!("{FUNCTIONS}\nfunction test(n) {{ return {expression}; }}");
+        let program = program(&source);
+        let mut evaluator = ConstEvaluator::new([&program], &Prime::Goldilocks);
+        let n = evaluator.number(8u32);
+        evaluator
+            .call("test", vec![n])
+            .map(|value| value.to_string())
+    }
+
+    #[test]
+    fn operators_test() {
+        assert_eq!(evaluate("n * 2 + 1").as_deref(), Some("17"));
+        assert_eq!(evaluate("(1 / n) * n").as_deref(), Some("1"));
+        assert_eq!(evaluate("n \\ 3 + n % 3").as_deref(), Some("4"));
+        assert_eq!(evaluate("2 ** n").as_deref(), Some("256"));
+        assert_eq!(evaluate("-1 < 0 && n >= 8").as_deref(), Some("1"));
+        assert_eq!(evaluate("n == 8 || 1 / 0").as_deref(), Some("1"));
+        assert_eq!(evaluate("(1 << n) >> 4").as_deref(), Some("16"));
+        assert_eq!(evaluate("(n | 3) ^ 1").as_deref(), Some("10"));
+        assert_eq!(evaluate("1 << 1000000000000").as_deref(), Some("0"));
+        assert_eq!(evaluate("n >> 1000000000000").as_deref(), Some("0"));
+        assert_eq!(evaluate("n >> -1000000000000").as_deref(), Some("0"));
+        // complement on the 64 bits of the prime: (2^64 - 1) mod p = 2^32 - 2
+        assert_eq!(evaluate("~0").as_deref(), Some("4294967294"));
+        assert_eq!(evaluate("!n + (n > 4 ? 10 : 20)").as_deref(), Some("10"));
+        assert_eq!(evaluate("1 / 0"), None);
+        assert_eq!(evaluate("x + 1"), None);
+    }
+
+    #[test]
+    fn function_call_test() {
+        assert_eq!(evaluate("nbits(255)").as_deref(), Some("8"));
+        assert_eq!(evaluate("nbits(n)").as_deref(), Some("4"));
+        assert_eq!(evaluate("fib(n)").as_deref(), Some("13"));
+        assert_eq!(evaluate("fib(n, 1)"), None);
+        assert_eq!(evaluate("forever()"), None);
+        // too many elements to allocate within the step limit
+        assert_eq!(evaluate("huge()"), None);
+        assert_eq!(evaluate("empty()").as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn run_template_test() {
+        let source = format!(
+            r#"{FUNCTIONS}
+template Bits(n) {{
+    signal input in;
+    var bits = nbits(n);
+    var sum = 0;
+    for (var i = 0; i < bits; i++) {{
+        sum += i;
+    }}
+    var unknown = in * 2;
+    if (in > 0) {{
+        sum = 0;
+    }}
+    signal output out[bits];
+    component c[bits];
+    for (var j = 0; j < bits; j++) {{
+        c[j] = Num2Bits(j + 1);
+    }}
+    component d = IsZero();
+    out[0] <== Square(in * 2)(in);
+}}"#
+        );
+        let program = program(&source);
+        let template = &program.template_list()[0];
+        let mut evaluator = ConstEvaluator::new([&program], &Prime::default());
+        let run = evaluator
+            .run_template(template, vec![evaluator.number(8u32)])
+            .unwrap();
+
+        assert_eq!(run.bindings["n"].to_string(), "8");
+        assert_eq!(run.bindings["bits"].to_string(), "4");
+        // assigned in every iteration
+        assert!(!run.bindings.contains_key("i"));
+        assert!(!run.bindings.contains_key("unknown"));
+        // changed under a condition which depends on a signal
+        assert!(!run.bindings.contains_key("sum"));
+
+        let instantiations: Vec<String> = run
+            .instantiations
+            .iter()
+            .map(|instantiation| {
+                let arguments: Vec<String> = instantiation
+                    .arguments
+                    .iter()
+                    .map(|argument| argument.to_string())
+                    .collect();
+                format!("{}({})", instantiation.template, arguments.join(", "))
+            })
+            .collect();
+        // the argument of `Square` depends on a signal
+        assert_eq!(
+            instantiations,
+            vec![
+                "Num2Bits(1)",
+                "Num2Bits(2)",
+                "Num2Bits(3)",
+                "Num2Bits(4)",
+                "IsZero()"
+            ]
+        );
+        // so the number of constraints is not known either
+        assert_eq!(run.constraints, None);
+    }
+
+    #[test]
+    fn constraint_count_test() {
+        let source = r#"
+template A(n) {
+    signal input a[n];
+    signal output b;
+    signal c[n];
+    for (var i = 0; i < n; i++) {
+        c[i] <== a[i] * a[i];
+    }
+    b <== c[0];
+    a[0] === 1;
+    signal output d <== b;
+    signal e <-- b;
+    if (n > 2) {
+        b === 2;
+    }
+}
+
+template B() {
+    signal input x;
+    var k = 0;
+    while (k < x) {
+        x === k;
+        k++;
+    }
+}"#;
+        let program = program(source);
+        let templates = program.template_list();
+        let mut evaluator = ConstEvaluator::new([&program], &Prime::default());
+
+        let run = evaluator
+            .run_template(&templates[0], vec![evaluator.number(3u32)])
+            .unwrap();
+        assert_eq!(run.constraints, Some(7));
+        let run = evaluator
+            .run_template(&templates[0], vec![evaluator.number(1u32)])
+            .unwrap();
+        assert_eq!(run.constraints, Some(4));
+        // the number of iterations depends on a signal
+        let run = evaluator.run_template(&templates[1], Vec::new()).unwrap();
+        assert_eq!(run.constraints, None);
+    }
+}
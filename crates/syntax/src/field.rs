@@ -0,0 +1,271 @@
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Zero};
+
+// decimal moduli of the named primes, in the order of `Prime`
+const NAMED_MODULI: [&str; 7] = [
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    "52435875175126190479447740508185965837690552500527637822603658699938581184513",
+    "18446744069414584321",
+    "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+    "28948022309329048855892746252171976963363056481941560715954676764349967630337",
+    "28948022309329048855892746252171976963363056481941647379679742748393362948097",
+    "115792089210356248762697446949407573530086143415290314195533631308867097853951",
+];
+
+/// prime fields supported by the circom compiler (`circom --prime <name>`)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Prime {
+    /// BN254 scalar field, circom's default prime
+    #[default]
+    Bn128,
+    Bls12381,
+    Goldilocks,
+    Grumpkin,
+    Pallas,
+    Vesta,
+    Secq256r1,
+    /// a modulus greater than 1, see `Prime::custom`
+    Custom(Arc<BigUint>),
+}
+
+impl Prime {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "bn128" | "bn254" => Some(Self::Bn128),
+            "bls12381" | "bls12-381" => Some(Self::Bls12381),
+            "goldilocks" => Some(Self::Goldilocks),
+            "grumpkin" => Some(Self::Grumpkin),
+            "pallas" => Some(Self::Pallas),
+            "vesta" => Some(Self::Vesta),
+            "secq256r1" => Some(Self::Secq256r1),
+            _ => None,
+        }
+    }
+
+    /// None for 0 and 1, which would panic on `%` or make every element 0
+    pub fn custom(modulus: BigUint) -> Option<Self> {
+        (modulus > BigUint::one()).then(|| Self::Custom(Arc::new(modulus)))
+    }
+
+    /// the name of a prime, or the value of a custom modulus, eg: 0xFFFFFFFF00000001
+    pub fn from_setting(setting: &str) -> Option<Self> {
+        Self::from_name(setting).or_else(|| Self::custom(parse_literal(setting.trim())?))
+    }
+
+    /// the moduli of the named primes are parsed once
+    pub fn modulus(&self) -> &BigUint {
+        static MODULI: OnceLock<Vec<BigUint>> = OnceLock::new();
+        let moduli = MODULI.get_or_init(|| {
+            NAMED_MODULI
+                .iter()
+                .map(|decimal| BigUint::parse_bytes(decimal.as_bytes(), 10).unwrap())
+                .collect()
+        });
+
+        let index = match self {
+            Self::Bn128 => 0,
+            Self::Bls12381 => 1,
+            Self::Goldilocks => 2,
+            Self::Grumpkin => 3,
+            Self::Pallas => 4,
+            Self::Vesta => 5,
+            Self::Secq256r1 => 6,
+            Self::Custom(modulus) => return modulus,
+        };
+        &moduli[index]
+    }
+
+    // a literal greater or equal than the prime is silently reduced by the compiler
+    pub fn overflows(&self, value: &BigUint) -> bool {
+        value >= self.modulus()
+    }
+}
+
+/**
+ * parse the text of a Number token
+ * eg: 10, 0xFF, 0b1010
+ */
+pub fn parse_literal(text: &str) -> Option<BigUint> {
+    let (digits, radix) = match text.get(..2) {
+        Some("0x" | "0X") => (&text[2..], 16),
+        Some("0b" | "0B") => (&text[2..], 2),
+        _ => (text, 10),
+    };
+
+    BigUint::parse_bytes(digits.as_bytes(), radix)
+}
+
+/// an element of the prime field `Z/pZ`, always kept reduced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldElement {
+    value: BigUint,
+    prime: Prime,
+}
+
+impl FieldElement {
+    pub fn new(value: BigUint, prime: &Prime) -> Self {
+        Self {
+            value: value % prime.modulus(),
+            prime: prime.clone(),
+        }
+    }
+
+    pub fn from_literal(text: &str, prime: &Prime) -> Option<Self> {
+        parse_literal(text).map(|value| Self::new(value, prime))
+    }
+
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        self.prime.modulus()
+    }
+
+    /// an element of the same field
+    pub fn with_value(&self, value: BigUint) -> Self {
+        Self::new(value, &self.prime)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    /**
+     * the representative in (-p/2, p/2], used by relational operators
+     * eg: p - 1 --> -1
+     */
+    pub fn signed(&self) -> BigInt {
+        let half: BigUint = self.modulus() >> 1;
+        if self.value > half {
+            BigInt::from(self.value.clone()) - BigInt::from(self.modulus().clone())
+        } else {
+            BigInt::from(self.value.clone())
+        }
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        self.with_value(&self.value + &other.value)
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.with_value(&self.value + self.modulus() - &other.value)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        self.with_value(&self.value * &other.value)
+    }
+
+    pub fn neg(&self) -> Self {
+        self.with_value(self.modulus() - &self.value)
+    }
+
+    pub fn pow(&self, exponent: &Self) -> Self {
+        self.with_value(self.value.modpow(&exponent.value, self.modulus()))
+    }
+
+    /// multiplicative inverse, None for 0
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let exponent = self.modulus() - 2u32;
+        Some(self.with_value(self.value.modpow(&exponent, self.modulus())))
+    }
+
+    pub fn div(&self, other: &Self) -> Option<Self> {
+        other.inverse().map(|inverse| self.mul(&inverse))
+    }
+}
+
+impl fmt::Display for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, BigUint};
+
+    use super::{parse_literal, FieldElement, Prime};
+
+    #[test]
+    fn parse_literal_test() {
+        assert_eq!(parse_literal("42"), Some(BigUint::from(42u32)));
+        assert_eq!(parse_literal("0xFFFF"), Some(BigUint::from(0xFFFFu32)));
+        assert_eq!(parse_literal("0Xff"), Some(BigUint::from(0xFFu32)));
+        assert_eq!(parse_literal("0b1011"), Some(BigUint::from(11u32)));
+        assert_eq!(parse_literal("0x"), None);
+        assert_eq!(parse_literal("abc"), None);
+    }
+
+    #[test]
+    fn modulus_test() {
+        let two = BigUint::from(2u32);
+        assert_eq!(
+            Prime::Goldilocks.modulus(),
+            &(two.pow(64) - two.pow(32) + 1u32)
+        );
+        assert_eq!(
+            Prime::Secq256r1.modulus(),
+            &(two.pow(256) - two.pow(224) + two.pow(192) + two.pow(96) - 1u32)
+        );
+        assert_eq!(Prime::from_name("BN254"), Some(Prime::default()));
+        assert_eq!(Prime::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn custom_prime_test() {
+        let prime = Prime::from_setting("0xFFFFFFFF00000001").unwrap();
+        assert_eq!(prime.modulus(), Prime::Goldilocks.modulus());
+        assert_eq!(Prime::from_setting("vesta"), Some(Prime::Vesta));
+        assert_eq!(
+            FieldElement::from_literal("20", &Prime::from_setting("17").unwrap())
+                .unwrap()
+                .to_string(),
+            "3"
+        );
+
+        assert_eq!(Prime::from_setting("0"), None);
+        assert_eq!(Prime::from_setting("1"), None);
+        assert_eq!(Prime::from_setting("unknown"), None);
+    }
+
+    #[test]
+    fn field_element_test() {
+        let p = Prime::Bn128.modulus();
+        let minus_one = FieldElement::from_literal(&(p.clone() - 1u32).to_string(), &Prime::Bn128);
+        assert_eq!(minus_one.unwrap().value(), &(p.clone() - 1u32));
+
+        let overflow = parse_literal(&(p.clone() + 5u32).to_string()).unwrap();
+        assert!(Prime::Bn128.overflows(&overflow));
+        assert_eq!(
+            FieldElement::new(overflow, &Prime::Bn128).value(),
+            &BigUint::from(5u32)
+        );
+
+        let goldilocks = FieldElement::from_literal("0xFFFFFFFF00000001", &Prime::Goldilocks);
+        assert_eq!(goldilocks.unwrap().to_string(), "0");
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        let element = |value: u32| FieldElement::new(BigUint::from(value), &Prime::Goldilocks);
+        let p = Prime::Goldilocks.modulus();
+
+        assert_eq!(element(3).add(&element(4)), element(7));
+        assert_eq!(element(3).sub(&element(4)).value(), &(p.clone() - 1u32));
+        assert_eq!(element(3).sub(&element(4)).signed(), BigInt::from(-1));
+        assert_eq!(element(6).mul(&element(7)), element(42));
+        assert_eq!(element(2).pow(&element(10)), element(1024));
+        assert_eq!(element(5).neg().add(&element(5)), element(0));
+
+        let third = element(1).div(&element(3)).unwrap();
+        assert_eq!(third.mul(&element(3)), element(1));
+        assert_eq!(element(1).div(&element(0)), None);
+    }
+}
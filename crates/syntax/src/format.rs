@@ -0,0 +1,520 @@
+use parser::token_kind::TokenKind::{self, *};
+
+use crate::syntax::SyntaxTreeBuilder;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// one level of indentation, eg: 4 spaces or a tab
+    pub indent: String,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: "    ".to_string(),
+        }
+    }
+}
+
+// how an operator is used in an expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    // a + b, a <== b, a ? b : c
+    Binary,
+    // -a, !a, ++a
+    Prefix,
+    // a++
+    Postfix,
+    Other,
+}
+
+// a token which is not a space or a line break
+struct Item {
+    token: SyntaxToken,
+    // line breaks between the previous item and this one
+    newlines: usize,
+    role: Role,
+}
+
+impl Item {
+    fn kind(&self) -> TokenKind {
+        self.token.kind()
+    }
+
+    fn is_comment(&self) -> bool {
+        matches!(self.kind(), CommentLine | BlockComment)
+    }
+
+    // `{` and `}` of a block, not of a public signal list
+    fn is_block_brace(&self) -> bool {
+        matches!(self.kind(), LCurly | RCurly)
+            && self
+                .token
+                .parent()
+                .and_then(|wrapper| wrapper.parent())
+                .is_some_and(|parent| parent.kind() == Block)
+    }
+
+    // the child of the program this item belongs to, eg: a template
+    fn top_level(&self) -> Option<SyntaxNode> {
+        self.token.parent_ancestors().find(|node| {
+            node.parent()
+                .is_some_and(|parent| parent.kind() == CircomProgram)
+        })
+    }
+
+    fn top_level_kind(&self) -> Option<TokenKind> {
+        self.top_level().map(|node| node.kind())
+    }
+}
+
+fn is_trivia_node(node: &SyntaxNode) -> bool {
+    matches!(
+        node.kind(),
+        WhiteSpace | EndLine | CommentLine | BlockComment
+    )
+}
+
+fn role(token: &SyntaxToken) -> Role {
+    let kind = token.kind();
+    if kind.is_assign_token() || matches!(kind, MarkQuestion | Colon) {
+        return Role::Binary;
+    }
+
+    // every token is wrapped in a node of the same kind,
+    // an operator is a child of the expression of the same kind
+    let Some(wrapper) = token.parent() else {
+        return Role::Other;
+    };
+    let Some(expression) = wrapper.parent().filter(|parent| parent.kind() == kind) else {
+        return Role::Other;
+    };
+    let operands: Vec<SyntaxNode> = expression
+        .children()
+        .filter(|child| !is_trivia_node(child))
+        .collect();
+
+    if kind.prefix().is_some() && operands.first() == Some(&wrapper) {
+        Role::Prefix
+    } else if matches!(kind, UnitInc | UnitDec) && operands.last() == Some(&wrapper) {
+        Role::Postfix
+    } else if kind.infix().is_some() && kind != Comma {
+        Role::Binary
+    } else {
+        Role::Other
+    }
+}
+
+fn is_keyword(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        PragmaKw
+            | Circom
+            | TemplateKw
+            | FunctionKw
+            | ComponentKw
+            | MainKw
+            | PublicKw
+            | SignalKw
+            | VarKw
+            | IncludeKw
+            | InputKw
+            | OutputKw
+            | IfKw
+            | ElseKw
+            | ForKw
+            | WhileKw
+            | ReturnKw
+    )
+}
+
+// a space between two items on the same line
+fn space_between(prev: &Item, next: &Item) -> bool {
+    let (prev_kind, next_kind) = (prev.kind(), next.kind());
+
+    if next.is_comment() || prev.is_comment() {
+        return true;
+    }
+    if prev.role == Role::Prefix {
+        // - -a is not --a
+        return next.role == Role::Prefix && prev_kind == next_kind;
+    }
+    if next.role == Role::Postfix {
+        return false;
+    }
+    if matches!(next_kind, Comma | Semicolon | RParen | RBracket | Dot)
+        || matches!(prev_kind, LParen | LBracket | Dot)
+    {
+        return false;
+    }
+    if prev.role == Role::Binary || next.role == Role::Binary {
+        return true;
+    }
+    match next_kind {
+        // if (, for (, but f(a), log(a), T(n)
+        LParen => is_keyword(prev_kind),
+        // a[i], f(a)[i], but public [a]
+        LBracket => !matches!(prev_kind, Identifier | RParen | RBracket),
+        // {public [a]}, {}
+        RCurly => next.is_block_brace() && prev_kind != LCurly,
+        _ => prev_kind != LCurly || prev.is_block_brace(),
+    }
+}
+
+// lines of a formatted text, with the state of the line being written
+struct Printer<'a> {
+    options: &'a FormatOptions,
+    text: String,
+    // the line ending of the source, `\r\n` or `\n`
+    newline: &'static str,
+    // open blocks
+    depth: usize,
+    // open parentheses and brackets of the current statement
+    parens: usize,
+}
+
+impl Printer<'_> {
+    fn line_break(&mut self, blank_lines: usize) {
+        let trimmed = self.text.trim_end_matches([' ', '\t']).len();
+        self.text.truncate(trimmed);
+        for _ in 0..=blank_lines {
+            self.text.push_str(self.newline);
+        }
+    }
+
+    fn indent(&mut self, level: usize) {
+        for _ in 0..level {
+            self.text.push_str(&self.options.indent);
+        }
+    }
+}
+
+// blank lines before an item which starts a line
+fn blank_lines(prev: &Item, next: &Item, depth: usize) -> usize {
+    // none after `{` and before `}`
+    if (prev.kind() == LCurly && prev.is_block_brace())
+        || (next.kind() == RCurly && next.is_block_brace())
+    {
+        return 0;
+    }
+    let source = next.newlines.saturating_sub(1).min(1);
+    if depth > 0 {
+        return source;
+    }
+
+    let prev_item = prev.top_level_kind();
+    let next_item = next.top_level_kind();
+    if matches!(prev_item, Some(TemplateDef | FunctionDef)) {
+        return 1;
+    }
+    if matches!(next_item, Some(TemplateDef | FunctionDef | MainComponent)) && !prev.is_comment() {
+        return 1;
+    }
+    if prev_item == Some(Include) && next_item == Some(Include) {
+        return 0;
+    }
+    source
+}
+
+// path of an include without comments, eg: "circomlib/poseidon.circom"
+fn include_path(node: &SyntaxNode) -> Option<String> {
+    if node.kind() != Include
+        || node
+            .descendants_with_tokens()
+            .any(|element| matches!(element.kind(), CommentLine | BlockComment))
+    {
+        return None;
+    }
+    Some(
+        node.children()
+            .find(|child| child.kind() == CircomString)?
+            .text()
+            .to_string(),
+    )
+}
+
+// items of a program, consecutive includes sorted by path
+fn items(program: &SyntaxNode) -> Vec<Item> {
+    // items grouped by top level node, with the path of includes
+    let mut groups: Vec<(Option<String>, Vec<Item>)> = Vec::new();
+    let mut current: Option<SyntaxNode> = None;
+    let mut newlines = 0;
+
+    for token in program
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+    {
+        match token.kind() {
+            WhiteSpace => {}
+            EndLine => newlines += 1,
+            _ => {
+                let item = Item {
+                    role: role(&token),
+                    token,
+                    newlines,
+                };
+                newlines = 0;
+
+                let top_level = item.top_level();
+                match groups.last_mut() {
+                    Some((_, group)) if top_level.is_some() && top_level == current => {
+                        group.push(item)
+                    }
+                    _ => {
+                        let path = top_level.as_ref().and_then(include_path);
+                        current = top_level;
+                        groups.push((path, vec![item]));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut start = 0;
+    while start < groups.len() {
+        if groups[start].0.is_none() {
+            start += 1;
+            continue;
+        }
+        let end = start
+            + groups[start..]
+                .iter()
+                .take_while(|(path, _)| path.is_some())
+                .count();
+
+        // the first include keeps the line breaks before the run
+        let newlines = groups[start].1[0].newlines;
+        groups[start].1[0].newlines = 1;
+        groups[start..end].sort_by(|a, b| a.0.cmp(&b.0));
+        groups[start].1[0].newlines = newlines;
+        start = end;
+    }
+
+    groups.into_iter().flat_map(|(_, group)| group).collect()
+}
+
+/**
+ * the formatted text of a circom program, None if it has syntax errors:
+ * - one statement per line, indented by block
+ * - spaces around binary operators (`<==`, `===`, `+`, ...) and after commas
+ * - at most one blank line between statements, one between templates and functions
+ * - consecutive includes sorted by path
+ * - comments are kept as they are, a comment at the end of a line stays there
+ * - line breaks inside a statement are kept, the next lines are indented once more
+ */
+pub fn format(source: &str, options: &FormatOptions) -> Option<String> {
+    let (syntax, errors) = SyntaxTreeBuilder::syntax_tree_with_errors(source);
+    if !errors.is_empty() || syntax.descendants().any(|node| node.kind() == Error) {
+        return None;
+    }
+
+    let items = items(&syntax);
+    let mut printer = Printer {
+        options,
+        text: String::new(),
+        newline: if source.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        },
+        depth: 0,
+        parens: 0,
+    };
+
+    // the last item which is not a comment at the end of a line
+    let mut anchor: Option<usize> = None;
+    for (index, next) in items.iter().enumerate() {
+        let closes_block = next.kind() == RCurly && next.is_block_brace();
+        if closes_block {
+            printer.depth = printer.depth.saturating_sub(1);
+        }
+
+        if let Some(prev) = index.checked_sub(1).map(|prev| &items[prev]) {
+            let trailing = next.is_comment() && next.newlines == 0;
+            let statement_end = anchor.map(|anchor| &items[anchor]).is_some_and(|anchor| {
+                (anchor.kind() == Semicolon && printer.parens == 0)
+                    || (anchor.kind() == LCurly && anchor.is_block_brace())
+                    || (anchor.kind() == RCurly
+                        && anchor.is_block_brace()
+                        && !matches!(next.kind(), ElseKw | Semicolon | Comma | RParen))
+            });
+            let empty_block = prev.kind() == LCurly && prev.is_block_brace() && closes_block;
+            let forced = !trailing
+                && !empty_block
+                && (statement_end
+                    || prev.kind() == CommentLine
+                    || closes_block
+                    || (prev.is_comment() && next.newlines > 0));
+            // a line break inside a statement
+            let joined = matches!(next.kind(), ElseKw | Semicolon)
+                || (next.kind() == LCurly && next.is_block_brace())
+                || prev.kind() == ElseKw;
+            let kept = !trailing && next.newlines > 0 && !joined;
+
+            if forced {
+                printer.line_break(blank_lines(prev, next, printer.depth));
+                printer.parens = 0;
+                printer.indent(printer.depth);
+            } else if kept && !empty_block {
+                printer.line_break(0);
+                let level = if matches!(next.kind(), RParen | RBracket) {
+                    printer.parens.saturating_sub(1)
+                } else {
+                    printer.parens.max(1)
+                };
+                printer.indent(printer.depth + level);
+            } else if space_between(prev, next) {
+                printer.text.push(' ');
+            }
+        }
+
+        printer.text.push_str(next.token.text());
+        match next.kind() {
+            LParen | LBracket => printer.parens += 1,
+            RParen | RBracket => printer.parens = printer.parens.saturating_sub(1),
+            LCurly if next.is_block_brace() => printer.depth += 1,
+            _ => {}
+        }
+        if !(next.is_comment() && next.newlines == 0) {
+            anchor = Some(index);
+        }
+    }
+
+    if !printer.text.is_empty() {
+        printer.line_break(0);
+    }
+    Some(printer.text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format, FormatOptions};
+
+    fn formatted(source: &str) -> String {
+        let text = format(source, &FormatOptions::default()).unwrap();
+        // formatting twice changes nothing
+        assert_eq!(format(&text, &FormatOptions::default()).unwrap(), text);
+        text
+    }
+
+    #[test]
+    fn statements_test() {
+        let source = r#"pragma circom  2.1.6;
+template Num2Bits(n)
+{
+  signal input in;signal output out[n];
+    var lc1=0 ;
+    var e2=1;
+    for (var i = 0; i<n; i++) {
+        out[i] <-- (in >> i) & 1;
+        out[i] * (out[i] -1 ) === 0;
+        lc1 += out[i] * e2;e2 = e2+e2;
+    }
+    if(n>2){lc1=-lc1;}else{lc1++;}
+    lc1 === in;
+}
+component main {public [in]} = Num2Bits( 8 );"#;
+        assert_eq!(
+            formatted(source),
+            r#"pragma circom 2.1.6;
+
+template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+    var lc1 = 0;
+    var e2 = 1;
+    for (var i = 0; i < n; i++) {
+        out[i] <-- (in >> i) & 1;
+        out[i] * (out[i] - 1) === 0;
+        lc1 += out[i] * e2;
+        e2 = e2 + e2;
+    }
+    if (n > 2) {
+        lc1 = -lc1;
+    } else {
+        lc1++;
+    }
+    lc1 === in;
+}
+
+component main {public [in]} = Num2Bits(8);
+"#
+        );
+    }
+
+    #[test]
+    fn items_test() {
+        let source = r#"include "lib/z.circom";
+include "lib/b.circom"; include "a.circom";
+// helpers
+function square(x) { return x * x; }
+template Empty() {
+}
+
+
+template Square() {
+    signal input in;
+
+
+    signal output out <== in * in;
+}"#;
+        assert_eq!(
+            formatted(source),
+            r#"include "a.circom";
+include "lib/b.circom";
+include "lib/z.circom";
+// helpers
+function square(x) {
+    return x * x;
+}
+
+template Empty() {}
+
+template Square() {
+    signal input in;
+
+    signal output out <== in * in;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn comments_test() {
+        let source = "/* license\n   text */\ntemplate A() {   // first\n    signal input a; /* inline */ signal input b;\n\t// indented\n    a === b ;   // last\n}\n";
+        assert_eq!(
+            formatted(source),
+            "/* license\n   text */\ntemplate A() { // first\n    signal input a; /* inline */\n    signal input b;\n    // indented\n    a === b; // last\n}\n"
+        );
+    }
+
+    #[test]
+    fn line_break_test() {
+        let source = "template A() {\n    component c = B(1 +\n2, f(3,\n    4\n));\n    x <== a ? -b\n    : c;\n}\n";
+        assert_eq!(
+            formatted(source),
+            "template A() {\n    component c = B(1 +\n        2, f(3,\n            4\n        ));\n    x <== a ? -b\n        : c;\n}\n"
+        );
+    }
+
+    #[test]
+    fn options_test() {
+        let options = FormatOptions {
+            indent: "\t".to_string(),
+        };
+        let source = "template A() {\r\n  signal input a;\r\n  if (a) { a === 1; }\r\n}\r\n";
+        assert_eq!(
+            format(source, &options).unwrap(),
+            "template A() {\r\n\tsignal input a;\r\n\tif (a) {\r\n\t\ta === 1;\r\n\t}\r\n}\r\n"
+        );
+    }
+
+    #[test]
+    fn syntax_error_test() {
+        assert_eq!(
+            format("template A() { signal input }", &FormatOptions::default()),
+            None
+        );
+    }
+}
@@ -1,5 +1,12 @@
 pub mod abstract_syntax_tree;
+pub mod const_eval;
+pub mod field;
+pub mod format;
+#[cfg(test)]
+mod lossless;
+pub mod reparsing;
 pub mod syntax;
 pub mod syntax_node;
 mod utils;
+#[cfg(test)]
 mod view_syntax;
@@ -0,0 +1,243 @@
+//! Property tests: the syntax tree is lossless, `root.text() == source`
+//! for any input, including syntax errors and unterminated comments.
+
+use proptest::prelude::*;
+
+use crate::reparsing::reparse;
+use crate::syntax::SyntaxTreeBuilder;
+use crate::view_syntax::view_ast;
+
+const TEST_FILES: &[&str] = &[
+    include_str!("test_files/happy/full_circom_program.circom"),
+    include_str!("test_files/happy/statements.circom"),
+    include_str!("test_files/happy/block_comment.circom"),
+    include_str!("test_files/happy/line_comment.circom"),
+    include_str!("test_files/happy/template.circom"),
+    include_str!("test_files/happy/main_component.circom"),
+    include_str!("test_files/recovery/invalid_statement.circom"),
+    include_str!("test_files/recovery/missing_semicolon.circom"),
+    include_str!("test_files/recovery/unclosed_block.circom"),
+];
+
+const FRAGMENTS: &[&str] = &[
+    "pragma",
+    "circom",
+    "2.1.6",
+    "template",
+    "function",
+    "component",
+    "main",
+    "public",
+    "signal",
+    "input",
+    "output",
+    "var",
+    "include",
+    "log",
+    "if",
+    "else",
+    "for",
+    "while",
+    "return",
+    "assert",
+    "parallel",
+    "a",
+    "b",
+    "in",
+    "out",
+    "Num2Bits",
+    "0",
+    "10",
+    "0xFF",
+    "0b101",
+    "\"lib.circom\"",
+    "\"",
+    "(",
+    ")",
+    "{",
+    "}",
+    "[",
+    "]",
+    ";",
+    ",",
+    ".",
+    "?",
+    ":",
+    "=",
+    "==",
+    "===",
+    "<==",
+    "==>",
+    "<--",
+    "-->",
+    "+",
+    "-",
+    "*",
+    "**",
+    "/",
+    "\\",
+    "%",
+    "++",
+    "--",
+    "+=",
+    "<<",
+    ">>",
+    "&",
+    "|",
+    "^",
+    "~",
+    "!",
+    "&&",
+    "||",
+    "<",
+    ">",
+    "<=",
+    ">=",
+    "!=",
+    "/*",
+    "*/",
+    "// comment\n",
+    "/* block */",
+    " ",
+    "  ",
+    "\t",
+    "\n",
+    "\r\n",
+    "#",
+    "@",
+    "é",
+];
+
+fn check_lossless(source: &str) {
+    let root = SyntaxTreeBuilder::syntax_tree(source);
+    assert_eq!(root.text().to_string(), source);
+
+    let (root, _) = SyntaxTreeBuilder::syntax_tree_with_errors(source);
+    assert_eq!(usize::from(root.text_range().len()), source.len());
+}
+
+fn token_soup() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(FRAGMENTS), 0..120)
+        .prop_map(|fragments| fragments.concat())
+}
+
+#[derive(Debug, Clone)]
+enum Mutation {
+    Delete(usize, usize),
+    Insert(usize, &'static str),
+    Duplicate(usize, usize),
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+impl Mutation {
+    fn apply(&self, text: &str) -> String {
+        match self {
+            Self::Delete(start, len) => {
+                let start = floor_char_boundary(text, *start);
+                let end = floor_char_boundary(text, start + len);
+                format!("{}{}", &text[..start], &text[end..])
+            }
+            Self::Insert(at, fragment) => {
+                let at = floor_char_boundary(text, *at);
+                format!("{}{}{}", &text[..at], fragment, &text[at..])
+            }
+            Self::Duplicate(start, len) => {
+                let start = floor_char_boundary(text, *start);
+                let end = floor_char_boundary(text, start + len);
+                format!("{}{}", &text[..end], &text[start..])
+            }
+        }
+    }
+}
+
+fn mutation() -> impl Strategy<Value = Mutation> {
+    prop_oneof![
+        (0..1000usize, 0..20usize).prop_map(|(start, len)| Mutation::Delete(start, len)),
+        (0..1000usize, prop::sample::select(FRAGMENTS))
+            .prop_map(|(at, fragment)| Mutation::Insert(at, fragment)),
+        (0..1000usize, 0..40usize).prop_map(|(start, len)| Mutation::Duplicate(start, len)),
+    ]
+}
+
+fn mutated_test_file() -> impl Strategy<Value = (String, String)> {
+    (
+        prop::sample::select(TEST_FILES),
+        prop::collection::vec(mutation(), 1..6),
+    )
+        .prop_map(|(source, mutations)| {
+            let mutated = mutations
+                .iter()
+                .fold(source.to_string(), |text, mutation| mutation.apply(&text));
+            (source.to_string(), mutated)
+        })
+}
+
+#[test]
+fn test_files_are_lossless() {
+    for source in TEST_FILES {
+        check_lossless(source);
+    }
+}
+
+#[test]
+fn edge_cases_are_lossless() {
+    for source in [
+        "",
+        " ",
+        "/*",
+        "/* unterminated\n template A() {}",
+        "*/",
+        "}}}",
+        "{{{",
+        "template",
+        "template A(",
+        "signal input a, b, c;",
+        "template A() { var a, b = 1, c; signal input x, y; }",
+        "template A() { x <== c ? a : b; }",
+        "template A() { x <== c ? ; }",
+        "component main {public [a, b]} = A(1, 2);",
+        "\"unterminated string",
+        "é ü 中",
+        "template A() { a[ ; }",
+        "template A() { a.b.c(1)[2]++; }",
+    ] {
+        check_lossless(source);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn arbitrary_text_is_lossless(source in any::<String>()) {
+        check_lossless(&source);
+    }
+
+    #[test]
+    fn token_soup_is_lossless(source in token_soup()) {
+        check_lossless(&source);
+    }
+
+    #[test]
+    fn mutated_files_are_lossless((_, source) in mutated_test_file()) {
+        check_lossless(&source);
+    }
+
+    #[test]
+    fn reparse_matches_full_parse((old, new) in mutated_test_file()) {
+        let (old_root, old_errors) = SyntaxTreeBuilder::syntax_tree_with_errors(&old);
+        let (root, errors) = reparse(&old_root, &old_errors, &new);
+        let (full, full_errors) = SyntaxTreeBuilder::syntax_tree_with_errors(&new);
+
+        prop_assert_eq!(root.text().to_string(), new);
+        prop_assert_eq!(view_ast(&root), view_ast(&full));
+        prop_assert_eq!(errors, full_errors);
+    }
+}
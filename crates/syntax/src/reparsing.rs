@@ -0,0 +1,327 @@
+use parser::error::ParseError;
+use parser::grammar::entry::Scope;
+use parser::input::Input;
+use parser::parser::Parser;
+use parser::token_kind::TokenKind;
+use rowan::{TextRange, TextSize};
+
+use crate::syntax::SyntaxTreeBuilder;
+use crate::syntax_node::SyntaxNode;
+
+/// replace the `delete` range of a text with `insert`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub delete: TextRange,
+    pub insert: String,
+}
+
+impl TextEdit {
+    /**
+     * the smallest edit which turns `old` into `new`
+     * (everything between their common prefix and common suffix)
+     */
+    pub fn diff(old: &str, new: &str) -> Self {
+        let mut prefix = old
+            .bytes()
+            .zip(new.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+
+        let max_suffix = old.len().min(new.len()) - prefix;
+        let mut suffix = old
+            .bytes()
+            .rev()
+            .zip(new.bytes().rev())
+            .take(max_suffix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix)
+        {
+            suffix -= 1;
+        }
+
+        Self {
+            delete: TextRange::new(
+                TextSize::from(prefix as u32),
+                TextSize::from((old.len() - suffix) as u32),
+            ),
+            insert: new[prefix..new.len() - suffix].to_string(),
+        }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let start: usize = self.delete.start().into();
+        let end: usize = self.delete.end().into();
+        format!("{}{}{}", &text[..start], self.insert, &text[end..])
+    }
+}
+
+/**
+ * { ... } without any other { or } closing the block before its end,
+ * eg: `{ a <== b; }` is balanced, `{ a } b }` and `{ {` are not
+ */
+fn is_balanced(input: &Input) -> bool {
+    let size = input.size();
+    if size == 0
+        || input.kind_of(0) != TokenKind::LCurly
+        || input.kind_of(size - 1) != TokenKind::RCurly
+    {
+        return false;
+    }
+
+    let mut balance = 0;
+    for index in 0..size {
+        match input.kind_of(index) {
+            TokenKind::LCurly => balance += 1,
+            TokenKind::RCurly => {
+                balance -= 1;
+                if balance == 0 && index != size - 1 {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    balance == 0
+}
+
+// the innermost Block which contains the edit, without touching its `{` and `}`
+fn edited_block(root: &SyntaxNode, edit: &TextEdit) -> Option<SyntaxNode> {
+    root.covering_element(edit.delete)
+        .ancestors()
+        .filter(|node| node.kind() == TokenKind::Block)
+        .find(|block| {
+            let range = block.text_range();
+            range.start() < edit.delete.start() && edit.delete.end() < range.end()
+        })
+}
+
+/**
+ * re-lex and re-parse only the innermost Block which contains the edit
+ * (without touching its `{` and `}`) and splice it into the old tree,
+ * with the errors found in that block (offsets in the new source).
+ * return None when the edit can not be handled locally,
+ * the caller must fall back to a full parse.
+ */
+pub fn reparse_block(root: &SyntaxNode, edit: &TextEdit) -> Option<(SyntaxNode, Vec<ParseError>)> {
+    let block = edited_block(root, edit)?;
+
+    let block_range = block.text_range();
+    let local_edit = TextEdit {
+        delete: edit.delete - block_range.start(),
+        insert: edit.insert.clone(),
+    };
+    let new_text = local_edit.apply(&block.text().to_string());
+
+    let input = Input::new(&new_text);
+    if !is_balanced(&input) {
+        return None;
+    }
+
+    let (output, errors) = Parser::parsing_with_scope(&input, Scope::Block);
+    let mut builder = SyntaxTreeBuilder::new(&input);
+    builder.build(output);
+    let green = builder.finish();
+
+    let new_block = SyntaxNode::new_root(green.clone());
+    if new_block.kind() != TokenKind::Block
+        || usize::from(new_block.text_range().len()) != new_text.len()
+    {
+        return None;
+    }
+
+    let start = usize::from(block_range.start());
+    let errors = errors
+        .into_iter()
+        .map(|mut error| {
+            error.range = error.range.start + start..error.range.end + start;
+            error
+        })
+        .collect();
+
+    Some((SyntaxNode::new_root(block.replace_with(green)), errors))
+}
+
+/**
+ * parse `new_source` reusing the tree of the previous version of the file
+ * when the change is contained inside a single block.
+ * `old_errors` are the parse errors of the previous version, the errors
+ * outside of the re-parsed block are kept (and moved by the edit)
+ */
+pub fn reparse(
+    old_root: &SyntaxNode,
+    old_errors: &[ParseError],
+    new_source: &str,
+) -> (SyntaxNode, Vec<ParseError>) {
+    let edit = TextEdit::diff(&old_root.text().to_string(), new_source);
+
+    let Some(block) = edited_block(old_root, &edit) else {
+        return SyntaxTreeBuilder::syntax_tree_with_errors(new_source);
+    };
+    let Some((root, block_errors)) = reparse_block(old_root, &edit) else {
+        return SyntaxTreeBuilder::syntax_tree_with_errors(new_source);
+    };
+
+    let (start, end) = (
+        usize::from(block.text_range().start()),
+        usize::from(block.text_range().end()),
+    );
+    let (deleted, inserted) = (usize::from(edit.delete.len()), edit.insert.len());
+    let before = old_errors
+        .iter()
+        .filter(|error| error.range.start < start)
+        .cloned();
+    let after = old_errors
+        .iter()
+        .filter(|error| error.range.start >= end)
+        .map(|error| {
+            let mut error = error.clone();
+            error.range =
+                error.range.start + inserted - deleted..error.range.end + inserted - deleted;
+            error
+        });
+
+    let errors = before.chain(block_errors).chain(after).collect();
+    (root, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use rowan::{TextRange, TextSize};
+
+    use crate::syntax::SyntaxTreeBuilder;
+    use crate::view_syntax::view_ast;
+
+    use super::{reparse, reparse_block, TextEdit};
+
+    const SOURCE: &str = r#"pragma circom 2.0.0;
+
+template Multiplier2() {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a * b;
+}
+
+template Adder() {
+    signal input a;
+    signal output b;
+    for (var i = 0; i < 2; i++) {
+        b <== a + i;
+    }
+}"#;
+
+    fn edit_at(source: &str, pattern: &str, insert: &str) -> TextEdit {
+        let start = source.find(pattern).unwrap();
+        TextEdit {
+            delete: TextRange::at(
+                TextSize::from(start as u32),
+                TextSize::from(pattern.len() as u32),
+            ),
+            insert: insert.to_string(),
+        }
+    }
+
+    fn check_incremental(pattern: &str, insert: &str) {
+        let old_root = SyntaxTreeBuilder::syntax_tree(SOURCE);
+        let edit = edit_at(SOURCE, pattern, insert);
+        let new_source = edit.apply(SOURCE);
+
+        let (incremental, errors) =
+            reparse_block(&old_root, &edit).expect("should reparse the block");
+        let (full, full_errors) = SyntaxTreeBuilder::syntax_tree_with_errors(&new_source);
+
+        assert_eq!(incremental.text().to_string(), new_source);
+        assert_eq!(view_ast(&incremental), view_ast(&full));
+        assert_eq!(errors, full_errors);
+    }
+
+    fn check_full(pattern: &str, insert: &str) {
+        let old_root = SyntaxTreeBuilder::syntax_tree(SOURCE);
+        let edit = edit_at(SOURCE, pattern, insert);
+
+        assert!(reparse_block(&old_root, &edit).is_none());
+    }
+
+    #[test]
+    fn diff_test() {
+        let edit = TextEdit::diff("signal a;", "signal abc;");
+        assert_eq!(edit.delete, TextRange::empty(TextSize::from(8)));
+        assert_eq!(edit.insert, "bc");
+        assert_eq!(edit.apply("signal a;"), "signal abc;");
+
+        let edit = TextEdit::diff("aaa", "aa");
+        assert_eq!(edit.apply("aaa"), "aa");
+
+        let edit = TextEdit::diff("x <== é;", "x <== è;");
+        assert_eq!(edit.apply("x <== é;"), "x <== è;");
+    }
+
+    #[test]
+    fn reparse_statement_test() {
+        check_incremental("c <== a * b;", "c <== a * b * a;");
+        check_incremental("signal output c;", "signal output c;\n    signal d;");
+    }
+
+    #[test]
+    fn reparse_nested_block_test() {
+        check_incremental("b <== a + i;", "b <== a - i;");
+        check_incremental("b <== a + i;", "{ b <== a + i; }");
+    }
+
+    #[test]
+    fn fallback_to_full_parse_test() {
+        // unbalanced curly brackets
+        check_full("c <== a * b;", "c <== a * b; }");
+        check_full("c <== a * b;", "{ c <== a * b;");
+        // edit outside of any block
+        check_full("Multiplier2", "Multiplier3");
+        // edit touching the `{` of a block
+        check_full("Adder() {", "Adder() { signal x;");
+    }
+
+    #[test]
+    fn reparse_test() {
+        let old_root = SyntaxTreeBuilder::syntax_tree(SOURCE);
+
+        let new_source = SOURCE.replace("c <== a * b;", "c <== a * b * b;");
+        let (root, errors) = reparse(&old_root, &[], &new_source);
+        assert_eq!(
+            view_ast(&root),
+            view_ast(&SyntaxTreeBuilder::syntax_tree(&new_source))
+        );
+        assert!(errors.is_empty());
+
+        let new_source = SOURCE.replace("template Adder", "function Adder");
+        let (root, _) = reparse(&old_root, &[], &new_source);
+        assert_eq!(
+            view_ast(&root),
+            view_ast(&SyntaxTreeBuilder::syntax_tree(&new_source))
+        );
+    }
+
+    #[test]
+    fn reparse_errors_test() {
+        // errors before and after the edited block are kept, the ones after are moved
+        let old_source = SOURCE
+            .replace("signal input b;", "signal input b")
+            .replace("signal output b;", "signal output b");
+        let (old_root, old_errors) = SyntaxTreeBuilder::syntax_tree_with_errors(&old_source);
+        assert_eq!(old_errors.len(), 2);
+
+        for (pattern, insert) in [
+            ("c <== a * b;", "c <== a * b * b;"),
+            ("c <== a * b;", "c <== a * b"),
+            ("signal input b", "signal input b;"),
+        ] {
+            let new_source = old_source.replace(pattern, insert);
+            let (_, errors) = reparse(&old_root, &old_errors, &new_source);
+            let (_, full_errors) = SyntaxTreeBuilder::syntax_tree_with_errors(&new_source);
+            assert_eq!(errors, full_errors);
+        }
+    }
+}
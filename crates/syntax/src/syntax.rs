@@ -1,3 +1,4 @@
+use parser::error::ParseError;
 use parser::grammar::entry::Scope;
 use parser::input::Input;
 use parser::output::{Child, Output};
@@ -37,12 +38,10 @@ impl<'a> SyntaxTreeBuilder<'a> {
                     self.builder.finish_node();
                 }
                 Child::Tree(child_tree) => self.build_rec(child_tree),
-                Child::Error(error) => {
-                    let token_kind = TokenKind::Error;
-                    let token_value = error.as_str();
-
-                    self.builder.start_node(token_kind.into());
-                    self.builder.token(token_kind.into(), token_value);
+                Child::Error(_) => {
+                    // an empty node marks where the error was found,
+                    // the message is kept in the parse errors, not in the source text
+                    self.builder.start_node(TokenKind::Error.into());
                     self.builder.finish_node();
                 }
             }
@@ -60,20 +59,28 @@ impl<'a> SyntaxTreeBuilder<'a> {
     }
 
     pub fn syntax_tree(source: &str) -> SyntaxNode {
+        Self::syntax_tree_with_errors(source).0
+    }
+
+    pub fn syntax_tree_with_errors(source: &str) -> (SyntaxNode, Vec<ParseError>) {
         let input = Input::new(source);
 
-        let output = Parser::parsing(&input);
+        let (output, errors) = Parser::parsing(&input);
 
         let mut builder = SyntaxTreeBuilder::new(&input);
         builder.build(output);
         let green = builder.finish();
-        SyntaxNode::new_root(green)
+        (SyntaxNode::new_root(green), errors)
     }
 }
 
 pub fn syntax_node_from_source(source: &str, scope: Scope) -> SyntaxNode {
-    let input = Input::new(&source);
-    let output = Parser::parsing_with_scope(&input, scope);
+    syntax_node_with_errors(source, scope).0
+}
+
+pub fn syntax_node_with_errors(source: &str, scope: Scope) -> (SyntaxNode, Vec<ParseError>) {
+    let input = Input::new(source);
+    let (output, errors) = Parser::parsing_with_scope(&input, scope);
 
     // output is a tree whose node is index of token, no content of token
     // convert output into green node
@@ -82,15 +89,16 @@ pub fn syntax_node_from_source(source: &str, scope: Scope) -> SyntaxNode {
     let green = builder.finish();
 
     // then cast green node into syntax node
-    let syntax = SyntaxNode::new_root(green);
-
-    syntax
+    (SyntaxNode::new_root(green), errors)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::abstract_syntax_tree::AstCircomProgram;
+    use crate::syntax::SyntaxTreeBuilder;
     use crate::test_syntax;
     use parser::grammar::entry::Scope;
+    use rowan::ast::AstNode;
 
     #[test]
     fn pragma_happy_test() {
@@ -132,4 +140,93 @@ mod tests {
             Scope::CircomProgram
         );
     }
+
+    #[test]
+    fn main_component_happy_test() {
+        test_syntax!(
+            "/src/test_files/happy/main_component.circom",
+            Scope::CircomProgram
+        );
+    }
+
+    #[test]
+    fn missing_semicolon_recovery_test() {
+        test_syntax!(
+            "/src/test_files/recovery/missing_semicolon.circom",
+            Scope::CircomProgram
+        );
+    }
+
+    #[test]
+    fn unclosed_block_recovery_test() {
+        test_syntax!(
+            "/src/test_files/recovery/unclosed_block.circom",
+            Scope::CircomProgram
+        );
+    }
+
+    #[test]
+    fn invalid_statement_recovery_test() {
+        test_syntax!(
+            "/src/test_files/recovery/invalid_statement.circom",
+            Scope::CircomProgram
+        );
+    }
+
+    #[test]
+    fn templates_after_error_test() {
+        let source = r#"
+    template Broken() {
+        signal input a
+        a <== ;
+
+    template Next() {
+        signal input b;
+    }
+
+    function f() {
+        return 1;
+    }"#;
+        let syntax = SyntaxTreeBuilder::syntax_tree(source);
+        let ast = AstCircomProgram::cast(syntax).unwrap();
+
+        let names: Vec<String> = ast
+            .template_list()
+            .iter()
+            .map(|template| template.name().unwrap().syntax().text().to_string())
+            .collect();
+        assert_eq!(names, vec!["Broken", "Next"]);
+
+        let next = &ast.template_list()[1];
+        assert!(next.find_input_signal("b").is_some());
+        assert_eq!(ast.function_list().len(), 1);
+    }
+
+    #[test]
+    fn main_component_ast_test() {
+        let source = include_str!("test_files/happy/main_component.circom");
+        let (syntax, errors) = SyntaxTreeBuilder::syntax_tree_with_errors(source);
+        assert!(errors.is_empty());
+
+        let ast = AstCircomProgram::cast(syntax).unwrap();
+        let main_component = ast.main_component().unwrap();
+
+        let signals: Vec<String> = main_component
+            .public_signals()
+            .iter()
+            .map(|signal| signal.syntax().text().to_string())
+            .collect();
+        assert_eq!(signals, vec!["a", "b"]);
+
+        let template = main_component.template().unwrap();
+        assert_eq!(template.syntax().text(), "Multiplier");
+        assert!(ast.get_template_by_name(&template).is_some());
+        assert_eq!(main_component.call().unwrap().arguments().len(), 1);
+
+        let source = "component main = Multiplier(2);";
+        let ast = AstCircomProgram::cast(SyntaxTreeBuilder::syntax_tree(source)).unwrap();
+        let main_component = ast.main_component().unwrap();
+        assert!(main_component.public_signal_list().is_none());
+        assert!(main_component.public_signals().is_empty());
+    }
 }
@@ -0,0 +1,17 @@
+pragma circom 2.0.0;
+
+signal input outside;
+
+template A() {
+    template m = M();
+    signal input a;
+    signal output b;
+    b <== a + ;
+}
+
+} ;
+
+template B() {
+    component a = A();
+}
+
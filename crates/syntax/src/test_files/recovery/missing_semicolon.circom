@@ -0,0 +1,14 @@
+pragma circom 2.0.0;
+
+template Multiplier2() {
+    signal input a;
+    signal input b
+    signal output c;
+    c <== a * b
+}
+
+template Multiplier3() {
+    signal input a;
+    signal output b;
+    b <== a * a * a;
+}
@@ -0,0 +1,16 @@
+pragma circom 2.0.0;
+
+template Broken(n) {
+    signal input in[n];
+    for (var i = 0; i < n; i++) {
+        in[i] === 0;
+
+template Next() {
+    signal input a;
+    signal output b;
+    b <== a;
+}
+
+function f(x) {
+    return x + 1;
+}
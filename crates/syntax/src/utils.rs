@@ -4,7 +4,13 @@ macro_rules! test_syntax {
         let crate_path = std::env::var("CARGO_MANIFEST_DIR").unwrap();
         let full_path = format!("{}{}", crate_path, $file_path);
         let source = std::fs::read_to_string(full_path).expect("Should not failed");
-        let syntax = crate::syntax::syntax_node_from_source(&source, $scope);
-        insta::assert_snapshot!($file_path, crate::view_syntax::view_ast(&syntax));
+        let (syntax, errors) = $crate::syntax::syntax_node_with_errors(&source, $scope);
+
+        // parse errors are listed after the tree, eg: 90..90 expected `;` after declaration
+        let mut view = $crate::view_syntax::view_ast(&syntax);
+        for error in errors {
+            view.push_str(&format!("\n{:?} {}", error.range, error.message));
+        }
+        insta::assert_snapshot!($file_path, view);
     };
 }
@@ -37,13 +37,13 @@ pub(crate) fn view_ast(node: &SyntaxNode) -> String {
                     }
                 }
                 result.push('\n');
-                level = level + 1;
+                level += 1;
             }
 
             WalkEvent::Leave(_it) => {
-                level = level - 1;
+                level -= 1;
             }
         }
     }
-    return result;
+    result
 }
@@ -1,10 +1,12 @@
 import path = require("path");
-import { ExtensionContext, commands, window } from "vscode";
+import { ExtensionContext, Uri, commands, window, workspace } from "vscode";
 
 import {
   Executable,
   LanguageClient,
   LanguageClientOptions,
+  Location,
+  Position,
   ServerOptions,
   Trace,
 } from "vscode-languageclient/node";
@@ -43,6 +45,18 @@ export async function activate(context: ExtensionContext) {
   const clientOptions: LanguageClientOptions = {
     // Register the server for plain text documents
     documentSelector: [{ scheme: "file", language: "circom" }],
+    initializationOptions: {
+      alignAssignments: workspace
+        .getConfiguration("circom-lsp")
+        .get<boolean>("format.alignAssignments", false),
+      libraryPaths: workspace
+        .getConfiguration("circom-lsp")
+        .get<string[]>("libraryPaths", []),
+      prime: workspace.getConfiguration("circom-lsp").get<string>("prime"),
+      warnMissingMain: workspace
+        .getConfiguration("circom-lsp")
+        .get<boolean>("warnMissingMain", false),
+    },
   };
 
   // Create the language client and start the client.
@@ -66,6 +80,36 @@ export async function activate(context: ExtensionContext) {
   );
 
   context.subscriptions.push(disposable);
+
+  // used by the code lenses of templates and functions
+  context.subscriptions.push(
+    commands.registerCommand(
+      "circom.showReferences",
+      async (uri: string, position: Position, locations: Location[]) => {
+        const converter = client.protocol2CodeConverter;
+        await commands.executeCommand(
+          "editor.action.showReferences",
+          Uri.parse(uri),
+          converter.asPosition(position),
+          locations.map(converter.asLocation)
+        );
+      }
+    )
+  );
+
+  // used by the constraint estimate lenses of templates
+  context.subscriptions.push(
+    commands.registerCommand(
+      "circom.showConstraintEstimates",
+      async (instances: string[]) => {
+        await window.showInformationMessage(
+          instances.length > 0
+            ? instances.join(", ")
+            : "no known instance of this template"
+        );
+      }
+    )
+  );
 }
 
 export async function deactivate() {
@@ -0,0 +1,670 @@
+//! Generates the node kinds of `TokenKind` and the typed AST wrappers
+//! from `crates/syntax/circom.ungram`.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use anyhow::{bail, format_err, Context};
+use xshell::{cmd, Shell};
+
+use crate::{flags, project_root};
+
+const GRAMMAR: &str = "crates/syntax/circom.ungram";
+const TOKEN_KIND: &str = "crates/parser/src/token_kind.rs";
+const AST_NODES: &str = "crates/syntax/src/abstract_syntax_tree/generated.rs";
+
+const GENERATED_START: &str = "// generated start";
+const GENERATED_END: &str = "// generated end";
+
+// nodes which are written by hand, accessors returning them are not generated
+const HAND_WRITTEN: &[&str] = &["Expr"];
+
+impl flags::Codegen {
+    pub(crate) fn run(self, sh: &Shell) -> anyhow::Result<()> {
+        let stale = generate(sh, self.check)?;
+        if self.check && !stale.is_empty() {
+            bail!(
+                "generated code is stale, run `cargo xtask codegen`: {}",
+                stale.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// update (or only compare, if `check`) the generated files,
+/// return the files which were not up to date
+pub(crate) fn generate(sh: &Shell, check: bool) -> anyhow::Result<Vec<String>> {
+    let root = project_root();
+    let grammar_text = fs::read_to_string(root.join(GRAMMAR))?;
+    let grammar = parse_grammar(&grammar_text)?;
+
+    let mut stale = Vec::new();
+
+    let token_kind_path = root.join(TOKEN_KIND);
+    let token_kind = fs::read_to_string(&token_kind_path)?;
+    let token_kind_new = generate_token_kinds(&grammar, &token_kind)?;
+    if ensure_file_contents(&token_kind_path, &token_kind, &token_kind_new, check)? {
+        stale.push(TOKEN_KIND.to_string());
+    }
+
+    let ast_path = root.join(AST_NODES);
+    let ast = fs::read_to_string(&ast_path).unwrap_or_default();
+    let ast_new = reformat(sh, &generate_ast(&grammar)?)?;
+    if ensure_file_contents(&ast_path, &ast, &ast_new, check)? {
+        stale.push(AST_NODES.to_string());
+    }
+
+    Ok(stale)
+}
+
+// return true if the file is not up to date
+fn ensure_file_contents(path: &Path, old: &str, new: &str, check: bool) -> anyhow::Result<bool> {
+    if old == new {
+        return Ok(false);
+    }
+    if !check {
+        eprintln!("updating {}", path.display());
+        fs::write(path, new).with_context(|| format!("write {}", path.display()))?;
+    }
+    Ok(true)
+}
+
+fn reformat(sh: &Shell, text: &str) -> anyhow::Result<String> {
+    let output = cmd!(sh, "rustfmt --edition 2021").stdin(text).read()?;
+    Ok(format!("{output}\n"))
+}
+
+/* grammar */
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    Node(String),
+    Token(String),
+    Labeled(String, Box<Rule>),
+    Seq(Vec<Rule>),
+    Alt(Vec<Rule>),
+    Opt(Box<Rule>),
+    Rep(Box<Rule>),
+}
+
+#[derive(Debug)]
+struct GrammarRule {
+    name: String,
+    comments: Vec<String>,
+    rule: Rule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Label(String),
+    Literal(String),
+    Eq,
+    Pipe,
+    Star,
+    QMark,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug)]
+struct Lexed {
+    token: Token,
+    // comments directly above the token
+    comments: Vec<String>,
+}
+
+fn lex(text: &str) -> anyhow::Result<Vec<Lexed>> {
+    let mut result = Vec::new();
+    let mut comments: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix("//") {
+            comments.push(comment.trim_end().to_string());
+            continue;
+        }
+        if line.is_empty() {
+            comments.clear();
+            continue;
+        }
+
+        let mut chars = line.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            let token = match c {
+                ' ' | '\t' => {
+                    chars.next();
+                    continue;
+                }
+                '=' => Token::Eq,
+                '|' => Token::Pipe,
+                '*' => Token::Star,
+                '?' => Token::QMark,
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                '\'' => {
+                    chars.next();
+                    let literal: String = chars.by_ref().take_while(|&c| c != '\'').collect();
+                    result.push(Lexed {
+                        token: Token::Literal(literal),
+                        comments: std::mem::take(&mut comments),
+                    });
+                    continue;
+                }
+                c if c.is_alphanumeric() || c == '_' => {
+                    let mut ident = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if !(c.is_alphanumeric() || c == '_') {
+                            break;
+                        }
+                        ident.push(c);
+                        chars.next();
+                    }
+                    let token = if chars.peek() == Some(&':') {
+                        chars.next();
+                        Token::Label(ident)
+                    } else {
+                        Token::Ident(ident)
+                    };
+                    result.push(Lexed {
+                        token,
+                        comments: std::mem::take(&mut comments),
+                    });
+                    continue;
+                }
+                c => bail!("unexpected character `{c}` in the grammar"),
+            };
+            chars.next();
+            result.push(Lexed {
+                token,
+                comments: std::mem::take(&mut comments),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+struct GrammarParser {
+    tokens: Vec<Lexed>,
+    pos: usize,
+}
+
+impl GrammarParser {
+    fn peek(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n).map(|lexed| &lexed.token)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|lexed| lexed.token.clone());
+        self.pos += 1;
+        token
+    }
+
+    // `Name =` starts a new rule
+    fn at_rule_start(&self) -> bool {
+        matches!(
+            (self.peek(0), self.peek(1)),
+            (Some(Token::Ident(_)), Some(Token::Eq))
+        )
+    }
+
+    fn rule(&mut self) -> anyhow::Result<GrammarRule> {
+        let comments = self.tokens[self.pos].comments.clone();
+        let Some(Token::Ident(name)) = self.bump() else {
+            bail!("expected a rule name");
+        };
+        self.bump();
+        let rule = self.alt()?;
+        Ok(GrammarRule {
+            name,
+            comments,
+            rule,
+        })
+    }
+
+    fn alt(&mut self) -> anyhow::Result<Rule> {
+        let mut alternatives = vec![self.seq()?];
+        while self.peek(0) == Some(&Token::Pipe) {
+            self.bump();
+            alternatives.push(self.seq()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.pop().unwrap(),
+            _ => Rule::Alt(alternatives),
+        })
+    }
+
+    fn seq(&mut self) -> anyhow::Result<Rule> {
+        let mut items = Vec::new();
+        while let Some(token) = self.peek(0) {
+            if matches!(token, Token::Pipe | Token::RParen) || self.at_rule_start() {
+                break;
+            }
+            items.push(self.postfix()?);
+        }
+        Ok(match items.len() {
+            1 => items.pop().unwrap(),
+            _ => Rule::Seq(items),
+        })
+    }
+
+    fn postfix(&mut self) -> anyhow::Result<Rule> {
+        let mut rule = self.atom()?;
+        loop {
+            match self.peek(0) {
+                Some(Token::Star) => rule = Rule::Rep(Box::new(rule)),
+                Some(Token::QMark) => rule = Rule::Opt(Box::new(rule)),
+                _ => return Ok(rule),
+            }
+            self.bump();
+        }
+    }
+
+    fn atom(&mut self) -> anyhow::Result<Rule> {
+        match self.bump() {
+            Some(Token::Ident(name)) => Ok(Rule::Node(name)),
+            Some(Token::Literal(literal)) => Ok(Rule::Token(literal)),
+            Some(Token::Label(label)) => {
+                let rule = self.postfix()?;
+                match rule {
+                    // `label:Node*` labels the node, not the repetition
+                    Rule::Rep(inner) => Ok(Rule::Rep(Box::new(Rule::Labeled(label, inner)))),
+                    Rule::Opt(inner) => Ok(Rule::Opt(Box::new(Rule::Labeled(label, inner)))),
+                    rule => Ok(Rule::Labeled(label, Box::new(rule))),
+                }
+            }
+            Some(Token::LParen) => {
+                let rule = self.alt()?;
+                match self.bump() {
+                    Some(Token::RParen) => Ok(rule),
+                    _ => bail!("expected `)` in the grammar"),
+                }
+            }
+            token => Err(format_err!("unexpected {token:?} in the grammar")),
+        }
+    }
+}
+
+fn parse_grammar(text: &str) -> anyhow::Result<Vec<GrammarRule>> {
+    let mut parser = GrammarParser {
+        tokens: lex(text)?,
+        pos: 0,
+    };
+    let mut rules = Vec::new();
+    while parser.peek(0).is_some() {
+        if !parser.at_rule_start() {
+            bail!("expected a rule, found {:?}", parser.peek(0));
+        }
+        rules.push(parser.rule()?);
+    }
+
+    let names: HashSet<&str> = rules.iter().map(|rule| rule.name.as_str()).collect();
+    for rule in &rules {
+        let mut nodes = Vec::new();
+        collect_nodes(&rule.rule, &mut nodes);
+        for node in nodes {
+            if !names.contains(node.as_str()) && !HAND_WRITTEN.contains(&node.as_str()) {
+                bail!("undefined node `{node}` in rule `{}`", rule.name);
+            }
+        }
+    }
+
+    Ok(rules)
+}
+
+fn collect_nodes(rule: &Rule, result: &mut Vec<String>) {
+    match rule {
+        Rule::Node(name) => result.push(name.clone()),
+        Rule::Token(_) => {}
+        Rule::Labeled(_, inner) | Rule::Opt(inner) | Rule::Rep(inner) => {
+            collect_nodes(inner, result)
+        }
+        Rule::Seq(items) | Rule::Alt(items) => {
+            items.iter().for_each(|item| collect_nodes(item, result))
+        }
+    }
+}
+
+/* lowering */
+
+// `Statement = IfStatement | ForLoop | ...`
+fn enum_variants(rule: &Rule) -> Option<Vec<String>> {
+    let Rule::Alt(alternatives) = rule else {
+        return None;
+    };
+    alternatives
+        .iter()
+        .map(|alternative| match alternative {
+            Rule::Node(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct Occurrence {
+    ty: String,
+    label: Option<String>,
+    repeated: bool,
+    // number of occurrences of the same node before this one
+    index: usize,
+}
+
+fn occurrences(
+    rule: &Rule,
+    label: Option<&str>,
+    repeated: bool,
+    counts: &mut BTreeMap<String, usize>,
+    result: &mut Vec<Occurrence>,
+) {
+    match rule {
+        Rule::Node(ty) => {
+            let index = counts.entry(ty.clone()).or_default();
+            result.push(Occurrence {
+                ty: ty.clone(),
+                label: label.map(str::to_string),
+                repeated,
+                index: *index,
+            });
+            *index += 1;
+        }
+        Rule::Token(_) => {}
+        Rule::Labeled(label, inner) => occurrences(inner, Some(label), repeated, counts, result),
+        Rule::Opt(inner) => occurrences(inner, label, repeated, counts, result),
+        Rule::Rep(inner) => occurrences(inner, label, true, counts, result),
+        Rule::Seq(items) => {
+            for item in items {
+                occurrences(item, label, repeated, counts, result);
+            }
+        }
+        Rule::Alt(alternatives) => {
+            // every alternative starts at the same position in the tree
+            let start = counts.clone();
+            let mut end = counts.clone();
+            for alternative in alternatives {
+                let mut alternative_counts = start.clone();
+                occurrences(
+                    alternative,
+                    label,
+                    repeated,
+                    &mut alternative_counts,
+                    result,
+                );
+                for (ty, count) in alternative_counts {
+                    let max = end.entry(ty).or_default();
+                    *max = (*max).max(count);
+                }
+            }
+            *counts = end;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Field {
+    name: String,
+    ty: String,
+    many: bool,
+    index: usize,
+}
+
+fn fields(rule: &Rule) -> anyhow::Result<Vec<Field>> {
+    let mut all = Vec::new();
+    occurrences(rule, None, false, &mut BTreeMap::new(), &mut all);
+
+    let mut result: Vec<Field> = Vec::new();
+    for occurrence in &all {
+        if HAND_WRITTEN.contains(&occurrence.ty.as_str()) {
+            continue;
+        }
+
+        let name = match &occurrence.label {
+            Some(label) => label.clone(),
+            None => {
+                // ambiguous, the accessor is hand-written if needed
+                if all.iter().filter(|other| other.ty == occurrence.ty).count() > 1 {
+                    continue;
+                }
+                let name = to_snake_case(&occurrence.ty);
+                if occurrence.repeated {
+                    format!("{name}s")
+                } else {
+                    name
+                }
+            }
+        };
+
+        match result.iter_mut().find(|field| field.name == name) {
+            Some(field) => {
+                if field.ty != occurrence.ty {
+                    bail!(
+                        "label `{name}` is used for `{}` and `{}`",
+                        field.ty,
+                        occurrence.ty
+                    );
+                }
+                field.many = true;
+            }
+            None => result.push(Field {
+                name,
+                ty: occurrence.ty.clone(),
+                many: occurrence.repeated,
+                index: occurrence.index,
+            }),
+        }
+    }
+
+    Ok(result)
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            result.push('_');
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+    result
+}
+
+/* TokenKind */
+
+// variants of TokenKind outside of the generated part
+fn fixed_kinds(token_kind: &str) -> HashSet<String> {
+    let mut result = HashSet::new();
+    let mut generated = false;
+    for line in token_kind.lines() {
+        let line = line.trim();
+        if line == GENERATED_START {
+            generated = true;
+        } else if line == GENERATED_END {
+            generated = false;
+        } else if !generated && !line.starts_with("//") && !line.starts_with('#') {
+            let name = line.split([',', ' ']).next().unwrap_or_default();
+            if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                result.insert(name.to_string());
+            }
+        }
+    }
+    result
+}
+
+fn generate_token_kinds(grammar: &[GrammarRule], token_kind: &str) -> anyhow::Result<String> {
+    let eol = if token_kind.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let text = token_kind.replace("\r\n", "\n");
+
+    let (Some(start), Some(end)) = (text.find(GENERATED_START), text.find(GENERATED_END)) else {
+        bail!("can not find `{GENERATED_START}` and `{GENERATED_END}` in {TOKEN_KIND}");
+    };
+    let start = start + text[start..].find('\n').unwrap() + 1;
+    let end = text[..end].rfind('\n').unwrap() + 1;
+
+    let fixed = fixed_kinds(&text);
+    let mut kinds = String::new();
+    kinds.push_str("    // Node kinds, generated by `cargo xtask codegen` from circom.ungram.\n");
+    for rule in grammar {
+        if fixed.contains(&rule.name) || enum_variants(&rule.rule).is_some() {
+            continue;
+        }
+        for comment in &rule.comments {
+            kinds.push_str(&format!("    //{comment}\n"));
+        }
+        kinds.push_str(&format!("    {},\n", rule.name));
+    }
+
+    let text = format!("{}{}{}", &text[..start], kinds, &text[end..]);
+    Ok(text.replace('\n', eol))
+}
+
+/* AST */
+
+fn generate_ast(grammar: &[GrammarRule]) -> anyhow::Result<String> {
+    let mut text = String::new();
+    text.push_str(&format!(
+        "//! Generated by `cargo xtask codegen` from `{GRAMMAR}`, do not edit by hand.\n\n"
+    ));
+    text.push_str(
+        "use parser::token_kind::TokenKind::*;\n\
+         \n\
+         use crate::syntax_node::CircomLanguage;\n\
+         use crate::syntax_node::SyntaxNode;\n\
+         use parser::token_kind::TokenKind;\n\
+         use rowan::ast::{support, AstNode};\n\n",
+    );
+
+    for rule in grammar {
+        let name = &rule.name;
+        if let Some(variants) = enum_variants(&rule.rule) {
+            text.push_str(&generate_enum(name, &variants));
+            continue;
+        }
+
+        text.push_str(&format!("ast_node!(Ast{name}, {name});\n\n"));
+
+        let fields = fields(&rule.rule)?;
+        if fields.is_empty() {
+            continue;
+        }
+        text.push_str(&format!("impl Ast{name} {{\n"));
+        for field in fields {
+            let (field_name, ty) = (&field.name, &field.ty);
+            let body = if field.many {
+                format!("pub fn {field_name}(&self) -> Vec<Ast{ty}> {{ support::children(self.syntax()).collect() }}\n")
+            } else if field.index == 0 {
+                format!("pub fn {field_name}(&self) -> Option<Ast{ty}> {{ support::child(self.syntax()) }}\n")
+            } else {
+                format!(
+                    "pub fn {field_name}(&self) -> Option<Ast{ty}> {{ support::children(self.syntax()).nth({}) }}\n",
+                    field.index
+                )
+            };
+            text.push_str(&body);
+        }
+        text.push_str("}\n\n");
+    }
+
+    Ok(text)
+}
+
+fn generate_enum(name: &str, variants: &[String]) -> String {
+    let mut text = String::new();
+    text.push_str(&format!("#[derive(Debug, Clone)]\npub enum Ast{name} {{\n"));
+    for variant in variants {
+        text.push_str(&format!("{variant}(Ast{variant}),\n"));
+    }
+    text.push_str("}\n\n");
+
+    text.push_str(&format!(
+        "impl AstNode for Ast{name} {{\ntype Language = CircomLanguage;\n"
+    ));
+    text.push_str(&format!(
+        "fn can_cast(token_kind: TokenKind) -> bool {{ matches!(token_kind, {}) }}\n",
+        variants.join(" | ")
+    ));
+    text.push_str(
+        "fn cast(syntax: SyntaxNode) -> Option<Self> {\nlet node = match syntax.kind() {\n",
+    );
+    for variant in variants {
+        text.push_str(&format!(
+            "{variant} => Self::{variant}(Ast{variant}::cast(syntax)?),\n"
+        ));
+    }
+    text.push_str("_ => return None,\n};\nSome(node)\n}\n");
+    text.push_str("fn syntax(&self) -> &SyntaxNode {\nmatch self {\n");
+    for variant in variants {
+        text.push_str(&format!("Self::{variant}(it) => it.syntax(),\n"));
+    }
+    text.push_str("}\n}\n}\n\n");
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use xshell::Shell;
+
+    use super::{fields, parse_grammar, Field};
+
+    #[test]
+    fn generated_code_is_fresh() {
+        let sh = Shell::new().unwrap();
+        let stale = super::generate(&sh, true).unwrap();
+        assert!(
+            stale.is_empty(),
+            "generated code is stale, run `cargo xtask codegen`: {stale:?}"
+        );
+    }
+
+    fn field(name: &str, ty: &str, many: bool, index: usize) -> Field {
+        Field {
+            name: name.to_string(),
+            ty: ty.to_string(),
+            many,
+            index,
+        }
+    }
+
+    #[test]
+    fn fields_test() {
+        let grammar = parse_grammar(
+            "
+            A = 'a'
+            // comment
+            B =
+              first:A ('x' second:A)?
+              A* (items:B (',' items:B)*)?
+              C
+            C = A | B
+            ",
+        )
+        .unwrap();
+        assert_eq!(grammar.len(), 3);
+        assert_eq!(grammar[1].comments, vec![" comment"]);
+
+        assert_eq!(
+            fields(&grammar[1].rule).unwrap(),
+            vec![
+                field("first", "A", false, 0),
+                field("second", "A", false, 1),
+                field("items", "B", true, 0),
+                field("c", "C", false, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn undefined_node_test() {
+        assert!(parse_grammar("A = B").is_err());
+        assert!(parse_grammar("A = Expr").is_ok());
+    }
+}
@@ -18,6 +18,12 @@ xflags::xflags! {
             /// Install only the language server.
             optional --server
         }
+
+        /// Generate syntax kinds and AST wrappers from circom.ungram.
+        cmd codegen {
+            /// Fail if the generated code is not up to date, without writing it.
+            optional --check
+        }
     }
 }
 
@@ -32,6 +38,7 @@ pub struct Xtask {
 #[derive(Debug)]
 pub enum XtaskCmd {
     Install(Install),
+    Codegen(Codegen),
 }
 
 #[derive(Debug)]
@@ -41,6 +48,11 @@ pub struct Install {
     pub server: bool,
 }
 
+#[derive(Debug)]
+pub struct Codegen {
+    pub check: bool,
+}
+
 impl Xtask {
     #[allow(dead_code)]
     pub fn from_env_or_exit() -> Self {
@@ -16,6 +16,7 @@
 
 mod flags;
 
+mod codegen;
 mod install;
 
 use std::{
@@ -32,6 +33,7 @@ fn main() -> anyhow::Result<()> {
 
     match flags.subcommand {
         flags::XtaskCmd::Install(cmd) => cmd.run(sh),
+        flags::XtaskCmd::Codegen(cmd) => cmd.run(sh),
     }
 }
 